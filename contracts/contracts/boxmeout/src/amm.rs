@@ -1,9 +1,12 @@
 // contracts/amm.rs - Automated Market Maker for Outcome Shares
 // Enables trading YES/NO outcome shares with dynamic odds pricing (Polymarket model)
 
-use soroban_sdk::{contract, contractimpl, token, Address, BytesN, Env, Symbol};
+use soroban_sdk::{
+    auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation},
+    contract, contractimpl, token, vec, Address, BytesN, Env, IntoVal, Symbol, Vec,
+};
 
-use boxmeout::{amm, helpers::*};
+use crate::helpers::*;
 
 // Storage keys
 const ADMIN_KEY: &str = "admin";
@@ -13,15 +16,41 @@ const MAX_LIQUIDITY_CAP_KEY: &str = "max_liquidity_cap";
 const SLIPPAGE_PROTECTION_KEY: &str = "slippage_protection";
 const TRADING_FEE_KEY: &str = "trading_fee";
 const PRICING_MODEL_KEY: &str = "pricing_model";
+const TOKEN_DECIMALS_KEY: &str = "token_decimals";
 
 // Pool storage keys
 const POOL_YES_RESERVE_KEY: &str = "pool_yes_reserve";
 const POOL_NO_RESERVE_KEY: &str = "pool_no_reserve";
 const POOL_EXISTS_KEY: &str = "pool_exists";
 const POOL_K_KEY: &str = "pool_k";
+const POOL_LIQUIDITY_KEY: &str = "pool_liquidity";
 const POOL_LP_SUPPLY_KEY: &str = "pool_lp_supply";
 const POOL_LP_TOKENS_KEY: &str = "pool_lp_tokens";
 const USER_SHARES_KEY: &str = "user_shares";
+const POOL_MARKET_KEY: &str = "pool_market";
+const POOL_SETTLED_KEY: &str = "pool_settled";
+const POOL_WINNING_OUTCOME_KEY: &str = "pool_winning_outcome";
+const POOL_REDEMPTION_PRICE_KEY: &str = "pool_redemption_price";
+const POOL_CREATOR_KEY: &str = "pool_creator";
+const POOL_ROYALTY_BPS_KEY: &str = "pool_royalty_bps";
+const CREATOR_ROYALTY_KEY: &str = "creator_royalty";
+const POOL_FEE_FREE_UNTIL_KEY: &str = "pool_fee_free_until";
+const POOL_LP_ENTRY_KEY: &str = "pool_lp_entry";
+const PROTOCOL_FEE_SPLIT_KEY: &str = "protocol_fee_split";
+const POOL_FEE_OVERRIDE_KEY: &str = "pool_fee_override";
+const PROTOCOL_FEES_KEY: &str = "protocol_fees";
+const TREASURY_KEY: &str = "treasury";
+const POOL_CREATED_AT_KEY: &str = "pool_created_at";
+const POOL_LIQUIDITY_CAP_KEY: &str = "pool_liquidity_cap";
+const TOTAL_VALUE_LOCKED_KEY: &str = "total_value_locked";
+const POOL_IDS_KEY: &str = "pool_ids";
+
+/// Redemption value of one winning share in basis points of one USDC unit (1:1)
+const WINNING_SHARE_PRICE_BPS: u128 = 10000;
+/// Most trade sizes `get_odds_at_amounts` quotes in one call
+const MAX_DEPTH_QUOTES: u32 = 20;
+/// Fixed-point scale of `marginal_price` (1 USDC = 1e7 at 7 decimals)
+const PRICE_SCALE: u128 = 10_000_000;
 
 // Pool data structure
 #[derive(Clone)]
@@ -64,6 +93,11 @@ impl AMM {
             .persistent()
             .set(&Symbol::new(&env, USDC_KEY), &usdc_token);
 
+        env.storage().persistent().set(
+            &Symbol::new(&env, TOKEN_DECIMALS_KEY),
+            &read_token_decimals(&env, &usdc_token),
+        );
+
         // Set max_liquidity_cap per market
         env.storage().persistent().set(
             &Symbol::new(&env, MAX_LIQUIDITY_CAP_KEY),
@@ -93,12 +127,70 @@ impl AMM {
         );
     }
 
+    /// Get the factory this AMM reports pools to
+    pub fn get_factory(env: Env) -> Address {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, FACTORY_KEY))
+            .expect("AMM not initialized")
+    }
+
+    /// Get the treasury protocol fees are swept into
+    pub fn get_treasury(env: Env) -> Address {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, TREASURY_KEY))
+            .expect("treasury not set")
+    }
+
+    /// Get AMM configuration
+    /// Returns (slippage_protection_bps, trading_fee_bps, pricing_model, max_liquidity_cap)
+    pub fn get_config(env: Env) -> (u32, u32, Symbol, u128) {
+        let slippage_protection: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, SLIPPAGE_PROTECTION_KEY))
+            .unwrap_or(200);
+        let trading_fee: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, TRADING_FEE_KEY))
+            .unwrap_or(20);
+        let pricing_model: Symbol = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, PRICING_MODEL_KEY))
+            .unwrap_or(Symbol::new(&env, "CPMM"));
+        let max_liquidity_cap: u128 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, MAX_LIQUIDITY_CAP_KEY))
+            .unwrap_or(0);
+
+        (
+            slippage_protection,
+            trading_fee,
+            pricing_model,
+            max_liquidity_cap,
+        )
+    }
+
+    /// Get decimals of the AMM's USDC token
+    pub fn get_token_decimals(env: Env) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, TOKEN_DECIMALS_KEY))
+            .unwrap_or(DEFAULT_TOKEN_DECIMALS)
+    }
+
     /// Create new liquidity pool for market
     pub fn create_pool(
         env: Env,
         creator: Address,
         market_id: BytesN<32>,
         initial_liquidity: u128,
+        creator_royalty_bps: u32,
+        fee_free_until: u64,
     ) {
         // Require creator auth to transfer USDC
         creator.require_auth();
@@ -114,20 +206,98 @@ impl AMM {
             panic!("initial liquidity must be greater than 0");
         }
 
+        // Royalty is a share of the trading fee; LPs and protocol keep the rest
+        if creator_royalty_bps > 10000 {
+            panic!("creator royalty exceeds trading fee");
+        }
+
+        Self::init_pool(
+            &env,
+            &creator,
+            &market_id,
+            initial_liquidity,
+            creator_royalty_bps,
+            fee_free_until,
+        );
+
+        // Transfer USDC from creator to contract
+        let usdc_token: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, USDC_KEY))
+            .expect("usdc token not set");
+
+        let token_client = token::Client::new(&env, &usdc_token);
+        token_client.transfer(
+            &creator,
+            env.current_contract_address(),
+            &(initial_liquidity as i128),
+        );
+    }
+
+    /// Create many 50/50 pools for `creator` in one call, pulling their USDC in one transfer
+    ///
+    /// Takes (market_id, initial_liquidity) pairs. Ids that already have a pool
+    /// are skipped rather than aborting the batch. Pools get no creator royalty
+    /// and no fee-free window. Returns the number of pools created.
+    pub fn create_pools_batch(env: Env, creator: Address, pools: Vec<(BytesN<32>, u128)>) -> u32 {
+        creator.require_auth();
+
+        let mut created = 0u32;
+        let mut total_liquidity: u128 = 0;
+        for (market_id, initial_liquidity) in pools.iter() {
+            // Skip ids that already have a pool
+            let pool_exists_key = (Symbol::new(&env, POOL_EXISTS_KEY), market_id.clone());
+            if env.storage().persistent().has(&pool_exists_key) {
+                continue;
+            }
+            if initial_liquidity == 0 {
+                panic!("initial liquidity must be greater than 0");
+            }
+
+            Self::init_pool(&env, &creator, &market_id, initial_liquidity, 0, 0);
+            total_liquidity = total_liquidity
+                .checked_add(initial_liquidity)
+                .expect("Overflow in batch liquidity");
+            created += 1;
+        }
+
+        if total_liquidity > 0 {
+            let usdc_token: Address = env
+                .storage()
+                .persistent()
+                .get(&Symbol::new(&env, USDC_KEY))
+                .expect("usdc token not set");
+            token::Client::new(&env, &usdc_token).transfer(
+                &creator,
+                env.current_contract_address(),
+                &(total_liquidity as i128),
+            );
+        }
+
+        created
+    }
+
+    /// Helper: Store a new 50/50 pool, mint the creator's LP tokens and announce it
+    fn init_pool(
+        env: &Env,
+        creator: &Address,
+        market_id: &BytesN<32>,
+        initial_liquidity: u128,
+        creator_royalty_bps: u32,
+        fee_free_until: u64,
+    ) {
         // Initialize 50/50 split
         let yes_reserve = initial_liquidity / 2;
         let no_reserve = initial_liquidity / 2;
 
-        // Calculate constant product k = x * y
-        let k = yes_reserve * no_reserve;
-
         // Create storage keys for this pool using tuples
-        let yes_key = (Symbol::new(&env, POOL_YES_RESERVE_KEY), market_id.clone());
-        let no_key = (Symbol::new(&env, POOL_NO_RESERVE_KEY), market_id.clone());
-        let k_key = (Symbol::new(&env, POOL_K_KEY), market_id.clone());
-        let lp_supply_key = (Symbol::new(&env, POOL_LP_SUPPLY_KEY), market_id.clone());
+        let pool_exists_key = (Symbol::new(env, POOL_EXISTS_KEY), market_id.clone());
+        let yes_key = (Symbol::new(env, POOL_YES_RESERVE_KEY), market_id.clone());
+        let no_key = (Symbol::new(env, POOL_NO_RESERVE_KEY), market_id.clone());
+        let lp_supply_key = (Symbol::new(env, POOL_LP_SUPPLY_KEY), market_id.clone());
         let lp_balance_key = (
-            Symbol::new(&env, POOL_LP_TOKENS_KEY),
+            Symbol::new(env, POOL_LP_TOKENS_KEY),
             market_id.clone(),
             creator.clone(),
         );
@@ -135,35 +305,161 @@ impl AMM {
         // Store reserves
         env.storage().persistent().set(&yes_key, &yes_reserve);
         env.storage().persistent().set(&no_key, &no_reserve);
-        env.storage().persistent().set(&k_key, &k);
+        Self::checkpoint_pool(env, market_id, yes_reserve, no_reserve);
         env.storage().persistent().set(&pool_exists_key, &true);
+        Self::increase_tvl(env, yes_reserve + no_reserve);
+        env.storage().persistent().set(
+            &(Symbol::new(env, POOL_CREATED_AT_KEY), market_id.clone()),
+            &env.ledger().timestamp(),
+        );
+        env.storage().persistent().set(
+            &(Symbol::new(env, POOL_CREATOR_KEY), market_id.clone()),
+            creator,
+        );
+        env.storage().persistent().set(
+            &(Symbol::new(env, POOL_ROYALTY_BPS_KEY), market_id.clone()),
+            &creator_royalty_bps,
+        );
+        // Optional launch window during which trades pay no fee (0 = none)
+        if fee_free_until > 0 {
+            env.storage().persistent().set(
+                &(Symbol::new(env, POOL_FEE_FREE_UNTIL_KEY), market_id.clone()),
+                &fee_free_until,
+            );
+        }
 
         // Mint LP tokens to creator (equal to initial_liquidity for first LP)
         let lp_tokens = initial_liquidity;
         env.storage().persistent().set(&lp_supply_key, &lp_tokens);
-        env.storage()
+        env.storage().persistent().set(&lp_balance_key, &lp_tokens);
+        Self::record_lp_entry(env, market_id, creator, yes_reserve, no_reserve);
+
+        // Index the pool for contract-wide checks; a cancelled and recreated
+        // pool keeps its original entry
+        let pool_ids_key = Symbol::new(env, POOL_IDS_KEY);
+        let mut pool_ids: Vec<BytesN<32>> = env
+            .storage()
             .persistent()
-            .set(&lp_balance_key, &lp_tokens);
+            .get(&pool_ids_key)
+            .unwrap_or(Vec::new(env));
+        if !pool_ids.contains(market_id) {
+            pool_ids.push_back(market_id.clone());
+            env.storage().persistent().set(&pool_ids_key, &pool_ids);
+        }
+
+        // Let the factory know this market has a pool; best effort, since
+        // pools may be created for markets outside the factory
+        let factory: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, FACTORY_KEY))
+            .expect("factory not set");
+        let factory_client = crate::factory::MarketFactoryClient::new(env, &factory);
+        let _ = factory_client.try_report_pool_created(market_id);
+
+        // Emit PoolCreated event
+        env.events().publish(
+            (Symbol::new(env, "pool_created"),),
+            (
+                market_id.clone(),
+                initial_liquidity,
+                yes_reserve,
+                no_reserve,
+            ),
+        );
+    }
+
+    /// Cancel a pool before anyone else has used it and refund the creator
+    ///
+    /// Only allowed while the creator is the sole LP and no trades have
+    /// executed. The creator receives the full reserves and all pool storage
+    /// is cleared so the pool can be created again.
+    pub fn cancel_pool(env: Env, creator: Address, market_id: BytesN<32>) -> u128 {
+        creator.require_auth();
+
+        let pool_exists_key = (Symbol::new(&env, POOL_EXISTS_KEY), market_id.clone());
+        if !env.storage().persistent().has(&pool_exists_key) {
+            panic!("pool does not exist");
+        }
+
+        let creator_key = (Symbol::new(&env, POOL_CREATOR_KEY), market_id.clone());
+        let pool_creator: Address = env
+            .storage()
+            .persistent()
+            .get(&creator_key)
+            .expect("pool creator not set");
+        if creator != pool_creator {
+            panic!("Unauthorized: only pool creator can cancel");
+        }
+
+        if get_trade_count(&env, &market_id) > 0 {
+            panic!("pool has trades");
+        }
+
+        let lp_supply_key = (Symbol::new(&env, POOL_LP_SUPPLY_KEY), market_id.clone());
+        let lp_balance_key = (
+            Symbol::new(&env, POOL_LP_TOKENS_KEY),
+            market_id.clone(),
+            creator.clone(),
+        );
+        let lp_supply: u128 = env.storage().persistent().get(&lp_supply_key).unwrap_or(0);
+        let lp_balance: u128 = env.storage().persistent().get(&lp_balance_key).unwrap_or(0);
+        if lp_balance != lp_supply {
+            panic!("pool has other liquidity providers");
+        }
+
+        let (yes_reserve, no_reserve) = get_pool_reserves(&env, &market_id);
+        let refund = yes_reserve + no_reserve;
+        Self::decrease_tvl(&env, refund);
+
+        // Clear everything create_pool wrote for this market
+        let storage = env.storage().persistent();
+        storage.remove(&(Symbol::new(&env, POOL_YES_RESERVE_KEY), market_id.clone()));
+        storage.remove(&(Symbol::new(&env, POOL_NO_RESERVE_KEY), market_id.clone()));
+        storage.remove(&(Symbol::new(&env, POOL_K_KEY), market_id.clone()));
+        storage.remove(&(Symbol::new(&env, POOL_LIQUIDITY_KEY), market_id.clone()));
+        storage.remove(&pool_exists_key);
+        storage.remove(&(Symbol::new(&env, POOL_CREATED_AT_KEY), market_id.clone()));
+        storage.remove(&creator_key);
+        storage.remove(&(Symbol::new(&env, POOL_ROYALTY_BPS_KEY), market_id.clone()));
+        storage.remove(&(
+            Symbol::new(&env, POOL_FEE_FREE_UNTIL_KEY),
+            market_id.clone(),
+        ));
+        storage.remove(&lp_supply_key);
+        storage.remove(&lp_balance_key);
+        storage.remove(&(
+            Symbol::new(&env, POOL_LP_ENTRY_KEY),
+            market_id.clone(),
+            creator.clone(),
+        ));
 
-        // Transfer USDC from creator to contract
         let usdc_token: Address = env
             .storage()
             .persistent()
             .get(&Symbol::new(&env, USDC_KEY))
             .expect("usdc token not set");
-
-        let token_client = token::Client::new(&env, &usdc_token);
-        token_client.transfer(
-            &creator,
+        token::Client::new(&env, &usdc_token).transfer(
             &env.current_contract_address(),
-            &(initial_liquidity as i128),
+            &creator,
+            &(refund as i128),
         );
 
-        // Emit PoolCreated event
+        // Best effort, mirroring the report made in create_pool
+        let factory: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, FACTORY_KEY))
+            .expect("factory not set");
+        let factory_client = crate::factory::MarketFactoryClient::new(&env, &factory);
+        let _ = factory_client.try_report_pool_cancelled(&market_id);
+
         env.events().publish(
-            (Symbol::new(&env, "pool_created"),),
-            (market_id, initial_liquidity, yes_reserve, no_reserve),
+            (Symbol::new(&env, "PoolCancelled"),),
+            (market_id, creator, refund),
         );
+
+        refund
     }
 
     /// Buy outcome shares (YES or NO)
@@ -193,6 +489,7 @@ impl AMM {
         if !env.storage().persistent().has(&pool_exists_key) {
             panic!("pool does not exist");
         }
+        Self::require_not_settled(&env, &market_id);
 
         // Get current reserves
         let yes_key = (Symbol::new(&env, POOL_YES_RESERVE_KEY), market_id.clone());
@@ -205,15 +502,12 @@ impl AMM {
             panic!("insufficient liquidity");
         }
 
-        // Calculate trading fee (20 basis points = 0.2%)
-        let trading_fee_bps: u128 = env
-            .storage()
-            .persistent()
-            .get(&Symbol::new(&env, TRADING_FEE_KEY))
-            .unwrap_or(20);
+        // Calculate trading fee (20 basis points = 0.2%, waived during launch window)
+        let trading_fee_bps = Self::effective_fee_bps(&env, &market_id);
 
         let fee_amount = (amount * trading_fee_bps) / 10000;
         let amount_after_fee = amount - fee_amount;
+        Self::split_trade_fee(&env, &market_id, fee_amount);
 
         // CPMM calculation: shares_out = (amount_in * reserve_out) / (reserve_in + amount_in)
         let (reserve_in, reserve_out, new_reserve_in, new_reserve_out) = if outcome == 1 {
@@ -240,6 +534,11 @@ impl AMM {
 
         let shares_out = (amount_after_fee * reserve_out) / (reserve_in + amount_after_fee);
 
+        // Never take USDC for nothing, even when the caller set no minimum
+        if shares_out == 0 {
+            panic!("zero shares out");
+        }
+
         // Slippage protection
         if shares_out < min_shares {
             panic!(
@@ -273,6 +572,8 @@ impl AMM {
                 .persistent()
                 .set(&no_key, &(no_reserve - shares_out));
         }
+        let (new_yes_reserve, new_no_reserve) = get_pool_reserves(&env, &market_id);
+        Self::checkpoint_pool(&env, &market_id, new_yes_reserve, new_no_reserve);
 
         // Transfer USDC from buyer to contract
         let usdc_token: Address = env
@@ -300,7 +601,15 @@ impl AMM {
             .persistent()
             .set(&user_share_key, &(current_shares + shares_out));
 
-        // Record trade (Optional: Simplified to event only for this resolution)
+        // Realized price vs pre-trade spot (reserve_in / reserve_out), after fees
+        let price_ratio_bps = (amount_after_fee * reserve_out * 10000) / (shares_out * reserve_in);
+        let slippage_bps = price_ratio_bps.saturating_sub(10000).min(u32::MAX as u128) as u32;
+
+        // Record trade
+        increment_trade_count(&env, &market_id);
+        add_pool_volume(&env, &market_id, amount);
+        let (yes_odds, _) = Self::get_odds(env.clone(), market_id.clone());
+        record_price_sample(&env, &market_id, yes_odds);
         env.events().publish(
             (Symbol::new(&env, "buy_shares"),),
             (
@@ -310,6 +619,7 @@ impl AMM {
                 shares_out,
                 amount,
                 fee_amount,
+                slippage_bps,
             ),
         );
 
@@ -340,6 +650,7 @@ impl AMM {
         if !env.storage().persistent().has(&pool_exists_key) {
             panic!("pool does not exist");
         }
+        Self::require_not_settled(&env, &market_id);
 
         // Check user share balance
         let user_share_key = (
@@ -375,16 +686,18 @@ impl AMM {
             (shares * yes_reserve) / (no_reserve + shares)
         };
 
-        // Calculate trading fee (20 basis points = 0.2%)
-        let trading_fee_bps: u128 = env
-            .storage()
-            .persistent()
-            .get(&Symbol::new(&env, TRADING_FEE_KEY))
-            .unwrap_or(20);
+        // Calculate trading fee (20 basis points = 0.2%, waived during launch window)
+        let trading_fee_bps = Self::effective_fee_bps(&env, &market_id);
 
         let fee_amount = (payout * trading_fee_bps) / 10000;
         let payout_after_fee = payout - fee_amount;
 
+        // Never burn shares for nothing, even when the caller set no minimum
+        if payout_after_fee == 0 {
+            panic!("zero payout");
+        }
+        Self::split_trade_fee(&env, &market_id, fee_amount);
+
         // Slippage protection
         if payout_after_fee < min_payout {
             panic!(
@@ -419,6 +732,7 @@ impl AMM {
         if new_yes == 0 || new_no == 0 {
             panic!("insufficient pool liquidity");
         }
+        Self::checkpoint_pool(&env, &market_id, new_yes, new_no);
 
         // Burn user shares
         env.storage()
@@ -439,6 +753,21 @@ impl AMM {
             &(payout_after_fee as i128),
         );
 
+        // Realized payout vs shares valued at pre-trade spot, before fees
+        let (reserve_in, reserve_out) = if outcome == 1 {
+            (yes_reserve, no_reserve)
+        } else {
+            (no_reserve, yes_reserve)
+        };
+        let realized_bps = (payout * reserve_in * 10000) / (shares * reserve_out);
+        let slippage_bps = 10000u128.saturating_sub(realized_bps) as u32;
+
+        // Record trade
+        increment_trade_count(&env, &market_id);
+        add_pool_volume(&env, &market_id, payout_after_fee);
+        let (yes_odds, _) = Self::get_odds(env.clone(), market_id.clone());
+        record_price_sample(&env, &market_id, yes_odds);
+
         // Emit SellShares event
         env.events().publish(
             (Symbol::new(&env, "sell_shares"),),
@@ -449,109 +778,910 @@ impl AMM {
                 shares,
                 payout_after_fee,
                 fee_amount,
+                slippage_bps,
             ),
         );
 
         payout_after_fee
     }
 
-    /// Calculate current odds for an outcome
-    /// Returns (yes_odds, no_odds) in basis points (5000 = 50%)
-    /// Handles zero-liquidity safely by returning (5000, 5000)
-    /// Read-only function with no state changes
-    pub fn get_odds(env: Env, market_id: BytesN<32>) -> (u32, u32) {
-        // Check if pool exists
-        let pool_exists_key = (Symbol::new(&env, POOL_EXISTS_KEY), market_id.clone());
-        if !env.storage().persistent().has(&pool_exists_key) {
-            // No pool exists - return 50/50 odds
-            return (5000, 5000);
-        }
-
-        // Get pool reserves
-        let yes_key = (Symbol::new(&env, POOL_YES_RESERVE_KEY), market_id.clone());
-        let no_key = (Symbol::new(&env, POOL_NO_RESERVE_KEY), market_id.clone());
-
-        let yes_reserve: u128 = env.storage().persistent().get(&yes_key).unwrap_or(0);
-        let no_reserve: u128 = env.storage().persistent().get(&no_key).unwrap_or(0);
+    /// Claim trading royalties accrued to a pool's creator
+    /// Returns the USDC amount paid out
+    pub fn claim_creator_royalty(env: Env, creator: Address, market_id: BytesN<32>) -> u128 {
+        creator.require_auth();
 
-        // Handle zero liquidity case
-        if yes_reserve == 0 && no_reserve == 0 {
-            return (5000, 5000);
+        let pool_creator: Address = env
+            .storage()
+            .persistent()
+            .get(&(Symbol::new(&env, POOL_CREATOR_KEY), market_id.clone()))
+            .expect("pool does not exist");
+        if pool_creator != creator {
+            panic!("not pool creator");
         }
 
-        // Handle single-sided liquidity (edge case)
-        if yes_reserve == 0 {
-            return (0, 10000); // 0% YES, 100% NO
-        }
-        if no_reserve == 0 {
-            return (10000, 0); // 100% YES, 0% NO
+        let royalty_key = (Symbol::new(&env, CREATOR_ROYALTY_KEY), market_id.clone());
+        let accrued: u128 = env.storage().persistent().get(&royalty_key).unwrap_or(0);
+        if accrued == 0 {
+            panic!("no royalty to claim");
         }
+        env.storage().persistent().set(&royalty_key, &0u128);
 
-        let total_liquidity = yes_reserve + no_reserve;
-
-        // Calculate odds as percentage of total liquidity
-        // YES odds = no_reserve / total_liquidity (inverse relationship)
-        // NO odds = yes_reserve / total_liquidity (inverse relationship)
-        // This follows AMM pricing where higher reserve = lower price
+        let usdc_token: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, USDC_KEY))
+            .expect("usdc token not set");
+        let token_client = token::Client::new(&env, &usdc_token);
+        token_client.transfer(
+            &env.current_contract_address(),
+            &creator,
+            &(accrued as i128),
+        );
 
-        let yes_odds = ((no_reserve * 10000) / total_liquidity) as u32;
-        let no_odds = ((yes_reserve * 10000) / total_liquidity) as u32;
+        env.events().publish(
+            (Symbol::new(&env, "royalty_claimed"),),
+            (creator, market_id, accrued),
+        );
 
-        // Ensure odds sum to 10000 (handle rounding)
-        let total_odds = yes_odds + no_odds;
-        if total_odds != 10000 {
-            let adjustment = 10000 - total_odds;
-            if yes_odds >= no_odds {
-                return (yes_odds + adjustment, no_odds);
-            } else {
-                return (yes_odds, no_odds + adjustment);
-            }
-        }
+        accrued
+    }
 
-        (yes_odds, no_odds)
+    /// Get the ledger timestamp at which a pool was created
+    pub fn get_pool_created_at(env: Env, market_id: BytesN<32>) -> u64 {
+        env.storage()
+            .persistent()
+            .get(&(Symbol::new(&env, POOL_CREATED_AT_KEY), market_id))
+            .expect("pool does not exist")
     }
 
-    /// Remove liquidity from pool (redeem LP tokens)
-    ///
-    /// Validates LP token ownership, calculates proportional YES/NO withdrawal,
-    /// burns LP tokens, updates reserves and k, transfers tokens to user.
-    pub fn remove_liquidity(
-        env: Env,
-        lp_provider: Address,
-        market_id: BytesN<32>,
-        lp_tokens: u128,
-    ) -> (u128, u128) {
-        // Require LP provider authentication
-        lp_provider.require_auth();
+    /// Get unclaimed creator royalty for a pool
+    pub fn get_creator_royalty(env: Env, market_id: BytesN<32>) -> u128 {
+        env.storage()
+            .persistent()
+            .get(&(Symbol::new(&env, CREATOR_ROYALTY_KEY), market_id))
+            .unwrap_or(0)
+    }
 
-        // Validate lp_tokens > 0
-        if lp_tokens == 0 {
-            panic!("lp tokens must be positive");
+    /// Admin: Set the protocol's share of trading fees left after creator royalty
+    pub fn set_protocol_fee_split(env: Env, admin: Address, split_bps: u32) {
+        Self::require_admin(&env, &admin);
+        if split_bps > 10000 {
+            panic!("protocol split exceeds 100%");
         }
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, PROTOCOL_FEE_SPLIT_KEY), &split_bps);
+    }
 
-        // Check if pool exists for this market
-        let pool_exists_key = (Symbol::new(&env, POOL_EXISTS_KEY), market_id.clone());
-        if !env.storage().persistent().has(&pool_exists_key) {
-            panic!("pool does not exist");
+    /// Admin: Override the global trading fee for one pool
+    pub fn set_pool_fee_override(env: Env, admin: Address, market_id: BytesN<32>, fee_bps: u32) {
+        Self::require_admin(&env, &admin);
+        if fee_bps > 10000 {
+            panic!("fee exceeds 100%");
         }
-
-        // Create storage keys for this pool
-        let yes_reserve_key = (Symbol::new(&env, POOL_YES_RESERVE_KEY), market_id.clone());
-        let no_reserve_key = (Symbol::new(&env, POOL_NO_RESERVE_KEY), market_id.clone());
-        let k_key = (Symbol::new(&env, POOL_K_KEY), market_id.clone());
-        let lp_supply_key = (Symbol::new(&env, POOL_LP_SUPPLY_KEY), market_id.clone());
-        let lp_balance_key = (
-            Symbol::new(&env, POOL_LP_TOKENS_KEY),
-            market_id.clone(),
-            lp_provider.clone(),
+        env.storage().persistent().set(
+            &(Symbol::new(&env, POOL_FEE_OVERRIDE_KEY), market_id),
+            &fee_bps,
         );
+    }
 
-        // Get LP provider's current balance
-        let lp_balance: u128 = env.storage().persistent().get(&lp_balance_key).unwrap_or(0);
+    /// Admin: Override the global liquidity cap for one market
+    ///
+    /// The cap cannot be set below the pool's current liquidity.
+    pub fn set_market_liquidity_cap(env: Env, admin: Address, market_id: BytesN<32>, cap: u128) {
+        Self::require_admin(&env, &admin);
 
-        // Validate user has enough LP tokens
-        if lp_balance < lp_tokens {
-            panic!("insufficient lp tokens");
+        let (yes_reserve, no_reserve) = get_pool_reserves(&env, &market_id);
+        if cap < yes_reserve + no_reserve {
+            panic!("cap below current liquidity");
+        }
+
+        env.storage().persistent().set(
+            &(Symbol::new(&env, POOL_LIQUIDITY_CAP_KEY), market_id),
+            &cap,
+        );
+    }
+
+    /// Get the liquidity cap for a market (override or global, 0 = uncapped)
+    pub fn get_market_liquidity_cap(env: Env, market_id: BytesN<32>) -> u128 {
+        let cap_override: Option<u128> = env
+            .storage()
+            .persistent()
+            .get(&(Symbol::new(&env, POOL_LIQUIDITY_CAP_KEY), market_id));
+        cap_override.unwrap_or_else(|| {
+            env.storage()
+                .persistent()
+                .get(&Symbol::new(&env, MAX_LIQUIDITY_CAP_KEY))
+                .unwrap_or(0)
+        })
+    }
+
+    /// Get a pool's current trading fee in basis points (override or global,
+    /// 0 during its fee-free launch window)
+    pub fn get_fee_tier(env: Env, market_id: BytesN<32>) -> u32 {
+        Self::effective_fee_bps(&env, &market_id) as u32
+    }
+
+    /// Get the protocol's accrued, undistributed share of a pool's fees
+    pub fn get_protocol_fees_accrued(env: Env, market_id: BytesN<32>) -> u128 {
+        env.storage()
+            .persistent()
+            .get(&(Symbol::new(&env, PROTOCOL_FEES_KEY), market_id))
+            .unwrap_or(0)
+    }
+
+    /// Admin: Set the treasury that receives swept protocol fees
+    pub fn set_treasury(env: Env, admin: Address, treasury: Address) {
+        Self::require_admin(&env, &admin);
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, TREASURY_KEY), &treasury);
+    }
+
+    /// Sweep a pool's accrued protocol fees into the treasury's platform pool
+    ///
+    /// Callable by anyone (admin or keeper); funds can only move to the
+    /// configured treasury. Returns the amount swept.
+    pub fn sweep_protocol_fees(env: Env, market_id: BytesN<32>) -> u128 {
+        let protocol_key = (Symbol::new(&env, PROTOCOL_FEES_KEY), market_id.clone());
+        let accrued: u128 = env.storage().persistent().get(&protocol_key).unwrap_or(0);
+        if accrued == 0 {
+            panic!("no protocol fees to sweep");
+        }
+
+        let treasury: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, TREASURY_KEY))
+            .expect("treasury not set");
+        let usdc_token: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, USDC_KEY))
+            .expect("usdc token not set");
+
+        env.storage().persistent().set(&protocol_key, &0u128);
+
+        // Authorize the treasury's pull of the fees from this contract
+        let amm_address = env.current_contract_address();
+        let amount = accrued as i128;
+        env.authorize_as_current_contract(vec![
+            &env,
+            InvokerContractAuthEntry::Contract(SubContractInvocation {
+                context: ContractContext {
+                    contract: usdc_token,
+                    fn_name: Symbol::new(&env, "transfer"),
+                    args: (amm_address.clone(), treasury.clone(), amount).into_val(&env),
+                },
+                sub_invocations: vec![&env],
+            }),
+        ]);
+
+        crate::treasury::TreasuryClient::new(&env, &treasury).deposit_category_fees(
+            &amm_address,
+            &Symbol::new(&env, "platform"),
+            &amount,
+        );
+
+        env.events().publish(
+            (Symbol::new(&env, "protocol_fees_swept"),),
+            (market_id, treasury, accrued),
+        );
+
+        accrued
+    }
+
+    /// Helper: Require auth from the stored admin
+    fn require_admin(env: &Env, admin: &Address) {
+        admin.require_auth();
+        let stored_admin: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, ADMIN_KEY))
+            .expect("admin not set");
+        if *admin != stored_admin {
+            panic!("Unauthorized: only admin");
+        }
+    }
+
+    /// Helper: Reject trading and liquidity provision on a settled pool
+    fn require_not_settled(env: &Env, market_id: &BytesN<32>) {
+        let settled_key = (Symbol::new(env, POOL_SETTLED_KEY), market_id.clone());
+        if env
+            .storage()
+            .persistent()
+            .get(&settled_key)
+            .unwrap_or(false)
+        {
+            panic!("pool settled");
+        }
+    }
+
+    /// Helper: Trading fee in basis points for a pool at the current time
+    fn effective_fee_bps(env: &Env, market_id: &BytesN<32>) -> u128 {
+        let fee_free_until: u64 = env
+            .storage()
+            .persistent()
+            .get(&(Symbol::new(env, POOL_FEE_FREE_UNTIL_KEY), market_id.clone()))
+            .unwrap_or(0);
+        if env.ledger().timestamp() < fee_free_until {
+            return 0;
+        }
+
+        let fee_override: Option<u32> = env
+            .storage()
+            .persistent()
+            .get(&(Symbol::new(env, POOL_FEE_OVERRIDE_KEY), market_id.clone()));
+        if let Some(fee_bps) = fee_override {
+            return fee_bps as u128;
+        }
+
+        env.storage()
+            .persistent()
+            .get::<_, u32>(&Symbol::new(env, TRADING_FEE_KEY))
+            .unwrap_or(20) as u128
+    }
+
+    /// Helper: Split a trade's fee into creator royalty, protocol share and LP remainder
+    fn split_trade_fee(env: &Env, market_id: &BytesN<32>, fee_amount: u128) {
+        let royalty = Self::accrue_creator_royalty(env, market_id, fee_amount);
+
+        let split_bps: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, PROTOCOL_FEE_SPLIT_KEY))
+            .unwrap_or(0);
+        let protocol_fee = ((fee_amount - royalty) * split_bps as u128) / 10000;
+        if protocol_fee == 0 {
+            return;
+        }
+
+        let protocol_key = (Symbol::new(env, PROTOCOL_FEES_KEY), market_id.clone());
+        let accrued: u128 = env.storage().persistent().get(&protocol_key).unwrap_or(0);
+        env.storage()
+            .persistent()
+            .set(&protocol_key, &(accrued + protocol_fee));
+    }
+
+    /// Helper: Credit the pool creator's share of a trade's fee
+    /// Returns the royalty credited
+    fn accrue_creator_royalty(env: &Env, market_id: &BytesN<32>, fee_amount: u128) -> u128 {
+        let royalty_bps: u32 = env
+            .storage()
+            .persistent()
+            .get(&(Symbol::new(env, POOL_ROYALTY_BPS_KEY), market_id.clone()))
+            .unwrap_or(0);
+        let royalty = (fee_amount * royalty_bps as u128) / 10000;
+        if royalty == 0 {
+            return 0;
+        }
+
+        let royalty_key = (Symbol::new(env, CREATOR_ROYALTY_KEY), market_id.clone());
+        let accrued: u128 = env.storage().persistent().get(&royalty_key).unwrap_or(0);
+        env.storage()
+            .persistent()
+            .set(&royalty_key, &(accrued + royalty));
+        royalty
+    }
+
+    /// Calculate current odds for an outcome
+    /// Returns (yes_odds, no_odds) in basis points (5000 = 50%)
+    /// Handles zero-liquidity safely by returning (5000, 5000)
+    /// Read-only function with no state changes
+    pub fn get_odds(env: Env, market_id: BytesN<32>) -> (u32, u32) {
+        // Check if pool exists
+        let pool_exists_key = (Symbol::new(&env, POOL_EXISTS_KEY), market_id.clone());
+        if !env.storage().persistent().has(&pool_exists_key) {
+            // No pool exists - return 50/50 odds
+            return (5000, 5000);
+        }
+
+        // Get pool reserves
+        let yes_key = (Symbol::new(&env, POOL_YES_RESERVE_KEY), market_id.clone());
+        let no_key = (Symbol::new(&env, POOL_NO_RESERVE_KEY), market_id.clone());
+
+        let yes_reserve: u128 = env.storage().persistent().get(&yes_key).unwrap_or(0);
+        let no_reserve: u128 = env.storage().persistent().get(&no_key).unwrap_or(0);
+
+        // Handle zero liquidity case
+        if yes_reserve == 0 && no_reserve == 0 {
+            return (5000, 5000);
+        }
+
+        // Handle single-sided liquidity (edge case)
+        if yes_reserve == 0 {
+            return (0, 10000); // 0% YES, 100% NO
+        }
+        if no_reserve == 0 {
+            return (10000, 0); // 100% YES, 0% NO
+        }
+
+        let total_liquidity = yes_reserve + no_reserve;
+
+        // Calculate odds as percentage of total liquidity
+        // YES odds = no_reserve / total_liquidity (inverse relationship)
+        // NO odds = yes_reserve / total_liquidity (inverse relationship)
+        // This follows AMM pricing where higher reserve = lower price
+
+        let yes_odds = ((no_reserve * 10000) / total_liquidity) as u32;
+        let no_odds = ((yes_reserve * 10000) / total_liquidity) as u32;
+
+        // Ensure odds sum to 10000 (handle rounding)
+        let total_odds = yes_odds + no_odds;
+        if total_odds != 10000 {
+            let adjustment = 10000 - total_odds;
+            if yes_odds >= no_odds {
+                return (yes_odds + adjustment, no_odds);
+            } else {
+                return (yes_odds, no_odds + adjustment);
+            }
+        }
+
+        (yes_odds, no_odds)
+    }
+
+    /// Quote the USDC payout (after fee) for selling `shares` of an outcome
+    /// Mirrors sell_shares pricing; returns 0 if the pool doesn't exist
+    pub fn quote_sell(env: Env, market_id: BytesN<32>, outcome: u32, shares: u128) -> u128 {
+        if outcome > 1 {
+            panic!("Invalid outcome: must be 0 (NO) or 1 (YES)");
+        }
+
+        let (yes_reserve, no_reserve) = get_pool_reserves(&env, &market_id);
+        if shares == 0 || yes_reserve == 0 || no_reserve == 0 {
+            return 0;
+        }
+
+        let payout = if outcome == 1 {
+            (shares * no_reserve) / (yes_reserve + shares)
+        } else {
+            (shares * yes_reserve) / (no_reserve + shares)
+        };
+
+        let trading_fee_bps = Self::effective_fee_bps(&env, &market_id);
+        payout - (payout * trading_fee_bps) / 10000
+    }
+
+    /// Get a user's position in a pool
+    /// Returns (yes_shares, no_shares, total_usdc_value) where value is the
+    /// sum of quote_sell for each side
+    pub fn get_user_pool_position(
+        env: Env,
+        user: Address,
+        market_id: BytesN<32>,
+    ) -> (u128, u128, u128) {
+        let yes_shares: u128 = env
+            .storage()
+            .persistent()
+            .get(&(
+                Symbol::new(&env, USER_SHARES_KEY),
+                market_id.clone(),
+                user.clone(),
+                1u32,
+            ))
+            .unwrap_or(0);
+        let no_shares: u128 = env
+            .storage()
+            .persistent()
+            .get(&(
+                Symbol::new(&env, USER_SHARES_KEY),
+                market_id.clone(),
+                user,
+                0u32,
+            ))
+            .unwrap_or(0);
+
+        let value = Self::quote_sell(env.clone(), market_id.clone(), 1, yes_shares)
+            + Self::quote_sell(env, market_id, 0, no_shares);
+
+        (yes_shares, no_shares, value)
+    }
+
+    /// Quote the price impact of buying `amount` USDC of an outcome
+    /// Returns the gap between the pre-trade spot price (reserve_in / reserve_out)
+    /// and the trade's average price (amount / shares_out) in basis points.
+    /// Trading fees are excluded so only curve slippage is reported.
+    /// Read-only function with no state changes
+    pub fn get_price_impact(env: Env, market_id: BytesN<32>, outcome: u32, amount: u128) -> u32 {
+        if outcome > 1 {
+            panic!("outcome must be 0 (NO) or 1 (YES)");
+        }
+        if amount == 0 {
+            panic!("amount must be greater than 0");
+        }
+
+        // Check if pool exists
+        let pool_exists_key = (Symbol::new(&env, POOL_EXISTS_KEY), market_id.clone());
+        if !env.storage().persistent().has(&pool_exists_key) {
+            panic!("pool does not exist");
+        }
+
+        // Get current reserves
+        let yes_key = (Symbol::new(&env, POOL_YES_RESERVE_KEY), market_id.clone());
+        let no_key = (Symbol::new(&env, POOL_NO_RESERVE_KEY), market_id.clone());
+
+        let yes_reserve: u128 = env.storage().persistent().get(&yes_key).unwrap_or(0);
+        let no_reserve: u128 = env.storage().persistent().get(&no_key).unwrap_or(0);
+
+        if yes_reserve == 0 || no_reserve == 0 {
+            panic!("insufficient liquidity");
+        }
+
+        // Same fee deduction as buy_shares so the quote matches execution
+        let trading_fee_bps = Self::effective_fee_bps(&env, &market_id);
+        let amount_after_fee = amount - (amount * trading_fee_bps) / 10000;
+
+        let (reserve_in, reserve_out) = if outcome == 1 {
+            (no_reserve, yes_reserve)
+        } else {
+            (yes_reserve, no_reserve)
+        };
+
+        let shares_out = (amount_after_fee * reserve_out) / (reserve_in + amount_after_fee);
+        if shares_out == 0 {
+            // Trade too small to fill a single share - treat as full impact
+            return 10000;
+        }
+
+        // avg_price / spot_price = (amount / shares_out) / (reserve_in / reserve_out)
+        let price_ratio_bps = (amount_after_fee * reserve_out * 10000) / (shares_out * reserve_in);
+        let impact_bps = price_ratio_bps.saturating_sub(10000);
+
+        if impact_bps > u32::MAX as u128 {
+            u32::MAX
+        } else {
+            impact_bps as u32
+        }
+    }
+
+    /// Quote the average price of buying each of `amounts` USDC of an outcome
+    ///
+    /// Each entry is amount / shares_out in basis points of one USDC per share,
+    /// fees included, so a depth ladder shows what each trade size really costs.
+    /// Sizes too small to fill a share quote u32::MAX. At most 20 amounts.
+    /// Read-only function with no state changes
+    pub fn get_odds_at_amounts(
+        env: Env,
+        market_id: BytesN<32>,
+        outcome: u32,
+        amounts: Vec<u128>,
+    ) -> Vec<u32> {
+        if outcome > 1 {
+            panic!("outcome must be 0 (NO) or 1 (YES)");
+        }
+        if amounts.len() > MAX_DEPTH_QUOTES {
+            panic!("too many amounts");
+        }
+
+        // Check if pool exists
+        let pool_exists_key = (Symbol::new(&env, POOL_EXISTS_KEY), market_id.clone());
+        if !env.storage().persistent().has(&pool_exists_key) {
+            panic!("pool does not exist");
+        }
+
+        // Get current reserves
+        let yes_key = (Symbol::new(&env, POOL_YES_RESERVE_KEY), market_id.clone());
+        let no_key = (Symbol::new(&env, POOL_NO_RESERVE_KEY), market_id.clone());
+
+        let yes_reserve: u128 = env.storage().persistent().get(&yes_key).unwrap_or(0);
+        let no_reserve: u128 = env.storage().persistent().get(&no_key).unwrap_or(0);
+
+        if yes_reserve == 0 || no_reserve == 0 {
+            panic!("insufficient liquidity");
+        }
+
+        let (reserve_in, reserve_out) = if outcome == 1 {
+            (no_reserve, yes_reserve)
+        } else {
+            (yes_reserve, no_reserve)
+        };
+        let trading_fee_bps = Self::effective_fee_bps(&env, &market_id);
+
+        let mut prices = Vec::new(&env);
+        for amount in amounts.iter() {
+            if amount == 0 {
+                panic!("amount must be greater than 0");
+            }
+
+            // Same math as buy_shares
+            let amount_after_fee = amount - (amount * trading_fee_bps) / 10000;
+            let shares_out = (amount_after_fee * reserve_out) / (reserve_in + amount_after_fee);
+            let price_bps = (amount * 10000)
+                .checked_div(shares_out)
+                .map_or(u32::MAX, |price| price.min(u32::MAX as u128) as u32);
+            prices.push_back(price_bps);
+        }
+        prices
+    }
+
+    /// Quote the USDC (fees included) to buy so `outcome`'s odds reach `target_odds_bps`
+    ///
+    /// Buying moves `amount_in` into reserve_in, leaving reserve_out at k / reserve_in,
+    /// so the outcome's odds become reserve_in² / (k + reserve_in²). Solving for the
+    /// target gives reserve_in = sqrt(k * target / (10000 - target)).
+    /// Returns 0 when the outcome is already priced at or above the target.
+    /// Read-only function with no state changes
+    pub fn usdc_to_reach_odds(
+        env: Env,
+        market_id: BytesN<32>,
+        outcome: u32,
+        target_odds_bps: u32,
+    ) -> u128 {
+        if outcome > 1 {
+            panic!("outcome must be 0 (NO) or 1 (YES)");
+        }
+        if target_odds_bps == 0 || target_odds_bps >= 10000 {
+            panic!("target odds must be between 0 and 10000 bps");
+        }
+
+        // Check if pool exists
+        let pool_exists_key = (Symbol::new(&env, POOL_EXISTS_KEY), market_id.clone());
+        if !env.storage().persistent().has(&pool_exists_key) {
+            panic!("pool does not exist");
+        }
+
+        // Get current reserves
+        let yes_key = (Symbol::new(&env, POOL_YES_RESERVE_KEY), market_id.clone());
+        let no_key = (Symbol::new(&env, POOL_NO_RESERVE_KEY), market_id.clone());
+
+        let yes_reserve: u128 = env.storage().persistent().get(&yes_key).unwrap_or(0);
+        let no_reserve: u128 = env.storage().persistent().get(&no_key).unwrap_or(0);
+
+        if yes_reserve == 0 || no_reserve == 0 {
+            panic!("insufficient liquidity");
+        }
+
+        let (reserve_in, reserve_out) = if outcome == 1 {
+            (no_reserve, yes_reserve)
+        } else {
+            (yes_reserve, no_reserve)
+        };
+
+        let target = target_odds_bps as u128;
+        let k = reserve_in
+            .checked_mul(reserve_out)
+            .expect("Overflow in pool k");
+        let target_square =
+            k.checked_mul(target).expect("Overflow in target reserve") / (10000 - target);
+        // Round the root up so the trade reaches the target rather than stopping short
+        let mut target_reserve_in = integer_sqrt(target_square);
+        if target_reserve_in * target_reserve_in < target_square {
+            target_reserve_in += 1;
+        }
+        if target_reserve_in <= reserve_in {
+            return 0;
+        }
+        let amount_after_fee = target_reserve_in - reserve_in;
+
+        // Gross up so buy_shares' fee deduction leaves amount_after_fee in the pool
+        let trading_fee_bps = Self::effective_fee_bps(&env, &market_id);
+        (amount_after_fee * 10000).div_ceil(10000 - trading_fee_bps)
+    }
+
+    /// Get the USDC cost of the next unit of `outcome` at current reserves, scaled to 1e7
+    ///
+    /// For an infinitesimal buy shares_out / amount_in tends to reserve_out / reserve_in,
+    /// so the marginal cost per share is reserve_in / reserve_out (the spot price,
+    /// before the trading fee). It rises as the outcome's reserve shrinks.
+    /// Read-only function with no state changes
+    pub fn marginal_price(env: Env, market_id: BytesN<32>, outcome: u32) -> u128 {
+        if outcome > 1 {
+            panic!("outcome must be 0 (NO) or 1 (YES)");
+        }
+
+        // Check if pool exists
+        let pool_exists_key = (Symbol::new(&env, POOL_EXISTS_KEY), market_id.clone());
+        if !env.storage().persistent().has(&pool_exists_key) {
+            panic!("pool does not exist");
+        }
+
+        // Get current reserves
+        let yes_key = (Symbol::new(&env, POOL_YES_RESERVE_KEY), market_id.clone());
+        let no_key = (Symbol::new(&env, POOL_NO_RESERVE_KEY), market_id.clone());
+
+        let yes_reserve: u128 = env.storage().persistent().get(&yes_key).unwrap_or(0);
+        let no_reserve: u128 = env.storage().persistent().get(&no_key).unwrap_or(0);
+
+        if yes_reserve == 0 || no_reserve == 0 {
+            panic!("insufficient liquidity");
+        }
+
+        let (reserve_in, reserve_out) = if outcome == 1 {
+            (no_reserve, yes_reserve)
+        } else {
+            (yes_reserve, no_reserve)
+        };
+
+        reserve_in
+            .checked_mul(PRICE_SCALE)
+            .expect("Overflow in marginal price")
+            / reserve_out
+    }
+
+    /// Add liquidity to an existing pool
+    ///
+    /// Splits the deposit across YES/NO in the current reserve ratio so odds are
+    /// unchanged, and mints LP tokens proportional to the pool's liquidity.
+    /// Returns the number of LP tokens minted.
+    pub fn add_liquidity(
+        env: Env,
+        lp_provider: Address,
+        market_id: BytesN<32>,
+        amount: u128,
+    ) -> u128 {
+        // Require LP provider authentication
+        lp_provider.require_auth();
+
+        if amount == 0 {
+            panic!("amount must be greater than 0");
+        }
+
+        // Check if pool exists for this market
+        let pool_exists_key = (Symbol::new(&env, POOL_EXISTS_KEY), market_id.clone());
+        if !env.storage().persistent().has(&pool_exists_key) {
+            panic!("pool does not exist");
+        }
+        Self::require_not_settled(&env, &market_id);
+
+        let yes_reserve_key = (Symbol::new(&env, POOL_YES_RESERVE_KEY), market_id.clone());
+        let no_reserve_key = (Symbol::new(&env, POOL_NO_RESERVE_KEY), market_id.clone());
+        let lp_supply_key = (Symbol::new(&env, POOL_LP_SUPPLY_KEY), market_id.clone());
+        let lp_balance_key = (
+            Symbol::new(&env, POOL_LP_TOKENS_KEY),
+            market_id.clone(),
+            lp_provider.clone(),
+        );
+
+        let yes_reserve: u128 = env
+            .storage()
+            .persistent()
+            .get(&yes_reserve_key)
+            .unwrap_or(0);
+        let no_reserve: u128 = env.storage().persistent().get(&no_reserve_key).unwrap_or(0);
+        let total_reserve = yes_reserve + no_reserve;
+        if total_reserve == 0 {
+            panic!("insufficient liquidity");
+        }
+
+        // Enforce the per-market liquidity cap
+        let max_liquidity_cap = Self::get_market_liquidity_cap(env.clone(), market_id.clone());
+        if max_liquidity_cap > 0 && total_reserve + amount > max_liquidity_cap {
+            panic!("liquidity cap exceeded");
+        }
+
+        // Deposit in the current reserve ratio
+        let yes_amount = (amount * yes_reserve) / total_reserve;
+        let no_amount = amount - yes_amount;
+        let new_yes_reserve = yes_reserve + yes_amount;
+        let new_no_reserve = no_reserve + no_amount;
+        Self::increase_tvl(&env, amount);
+
+        // Mint LP tokens proportional to the added share of liquidity
+        let current_lp_supply: u128 = env.storage().persistent().get(&lp_supply_key).unwrap_or(0);
+        let lp_tokens = (amount * current_lp_supply) / total_reserve;
+        if lp_tokens == 0 {
+            panic!("deposit too small");
+        }
+
+        env.storage()
+            .persistent()
+            .set(&yes_reserve_key, &new_yes_reserve);
+        env.storage()
+            .persistent()
+            .set(&no_reserve_key, &new_no_reserve);
+        Self::checkpoint_pool(&env, &market_id, new_yes_reserve, new_no_reserve);
+        env.storage()
+            .persistent()
+            .set(&lp_supply_key, &(current_lp_supply + lp_tokens));
+        let lp_balance: u128 = env.storage().persistent().get(&lp_balance_key).unwrap_or(0);
+        env.storage()
+            .persistent()
+            .set(&lp_balance_key, &(lp_balance + lp_tokens));
+        Self::record_lp_entry(&env, &market_id, &lp_provider, yes_amount, no_amount);
+
+        // Transfer USDC from provider to contract
+        let usdc_token: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, USDC_KEY))
+            .expect("usdc token not set");
+        let token_client = token::Client::new(&env, &usdc_token);
+        token_client.transfer(
+            &lp_provider,
+            env.current_contract_address(),
+            &(amount as i128),
+        );
+
+        // Emit LiquidityAdded event
+        env.events().publish(
+            (Symbol::new(&env, "liquidity_added"),),
+            (market_id, lp_provider, lp_tokens, yes_amount, no_amount),
+        );
+
+        lp_tokens
+    }
+
+    /// Helper: Add deposited liquidity to the running TVL counter
+    fn increase_tvl(env: &Env, amount: u128) {
+        let tvl = Self::get_total_value_locked(env.clone());
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(env, TOTAL_VALUE_LOCKED_KEY), &(tvl + amount));
+    }
+
+    /// Helper: Remove withdrawn liquidity from the running TVL counter
+    fn decrease_tvl(env: &Env, amount: u128) {
+        let tvl = Self::get_total_value_locked(env.clone());
+        env.storage().persistent().set(
+            &Symbol::new(env, TOTAL_VALUE_LOCKED_KEY),
+            &tvl.saturating_sub(amount),
+        );
+    }
+
+    /// Get total liquidity locked across all pools
+    ///
+    /// Maintained as a running counter on pool creation, liquidity adds and
+    /// removals, so it reflects LP deposits rather than trade flow.
+    pub fn get_total_value_locked(env: Env) -> u128 {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, TOTAL_VALUE_LOCKED_KEY))
+            .unwrap_or(0)
+    }
+
+    /// Compare the AMM's USDC balance with what it owes across all pools
+    ///
+    /// Obligations are every pool's YES + NO reserves plus accrued protocol
+    /// fees and creator royalties. Returns (usdc_balance, total_obligations,
+    /// is_solvent) for monitoring.
+    pub fn check_solvency(env: Env) -> (u128, u128, bool) {
+        let pool_ids: Vec<BytesN<32>> = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, POOL_IDS_KEY))
+            .unwrap_or(Vec::new(&env));
+
+        let mut obligations: u128 = 0;
+        for market_id in pool_ids.iter() {
+            let (yes_reserve, no_reserve) = get_pool_reserves(&env, &market_id);
+            let protocol_fees: u128 = env
+                .storage()
+                .persistent()
+                .get(&(Symbol::new(&env, PROTOCOL_FEES_KEY), market_id.clone()))
+                .unwrap_or(0);
+            let royalties: u128 = env
+                .storage()
+                .persistent()
+                .get(&(Symbol::new(&env, CREATOR_ROYALTY_KEY), market_id.clone()))
+                .unwrap_or(0);
+            obligations += yes_reserve + no_reserve + protocol_fees + royalties;
+        }
+
+        let usdc_token: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, USDC_KEY))
+            .expect("usdc token not set");
+        let balance = token::Client::new(&env, &usdc_token)
+            .balance(&env.current_contract_address())
+            .max(0) as u128;
+
+        (balance, obligations, balance >= obligations)
+    }
+
+    /// Helper: Add a deposit's YES/NO split to the LP's entry position
+    ///
+    /// The split is made at the pool's odds at deposit time, so the entry
+    /// position is what the LP would hold had they bought the shares outright.
+    fn record_lp_entry(
+        env: &Env,
+        market_id: &BytesN<32>,
+        lp_provider: &Address,
+        yes_amount: u128,
+        no_amount: u128,
+    ) {
+        let entry_key = (
+            Symbol::new(env, POOL_LP_ENTRY_KEY),
+            market_id.clone(),
+            lp_provider.clone(),
+        );
+        let (yes_held, no_held): (u128, u128) =
+            env.storage().persistent().get(&entry_key).unwrap_or((0, 0));
+        env.storage()
+            .persistent()
+            .set(&entry_key, &(yes_held + yes_amount, no_held + no_amount));
+    }
+
+    /// Estimate an LP's impermanent loss in signed basis points
+    ///
+    /// Values the LP's current share of the reserves and its entry position
+    /// (see `record_lp_entry`) at current odds. Negative means the LP is worth
+    /// less than simply holding; returns 0 for accounts with no position.
+    pub fn get_lp_impermanent_loss(env: Env, market_id: BytesN<32>, lp_provider: Address) -> i32 {
+        let entry: Option<(u128, u128)> = env.storage().persistent().get(&(
+            Symbol::new(&env, POOL_LP_ENTRY_KEY),
+            market_id.clone(),
+            lp_provider.clone(),
+        ));
+        let (yes_held, no_held) = match entry {
+            Some(entry) => entry,
+            None => return 0,
+        };
+
+        let lp_balance: u128 = env
+            .storage()
+            .persistent()
+            .get(&(
+                Symbol::new(&env, POOL_LP_TOKENS_KEY),
+                market_id.clone(),
+                lp_provider,
+            ))
+            .unwrap_or(0);
+        let lp_supply: u128 = env
+            .storage()
+            .persistent()
+            .get(&(Symbol::new(&env, POOL_LP_SUPPLY_KEY), market_id.clone()))
+            .unwrap_or(0);
+        if lp_balance == 0 || lp_supply == 0 {
+            return 0;
+        }
+
+        let (yes_reserve, no_reserve) = get_pool_reserves(&env, &market_id);
+        let (yes_odds, no_odds) = Self::get_odds(env, market_id);
+        let (yes_price, no_price) = (yes_odds as u128, no_odds as u128);
+
+        let pool_yes = (lp_balance * yes_reserve) / lp_supply;
+        let pool_no = (lp_balance * no_reserve) / lp_supply;
+        let lp_value = (pool_yes * yes_price + pool_no * no_price) as i128;
+        let hold_value = (yes_held * yes_price + no_held * no_price) as i128;
+        if hold_value == 0 {
+            return 0;
+        }
+
+        let il_bps = ((lp_value - hold_value) * 10000) / hold_value;
+        il_bps.clamp(i32::MIN as i128, i32::MAX as i128) as i32
+    }
+
+    /// Remove liquidity from pool (redeem LP tokens)
+    ///
+    /// Validates LP token ownership, calculates proportional YES/NO withdrawal,
+    /// burns LP tokens, updates reserves and k, transfers tokens to user.
+    pub fn remove_liquidity(
+        env: Env,
+        lp_provider: Address,
+        market_id: BytesN<32>,
+        lp_tokens: u128,
+    ) -> (u128, u128) {
+        // Require LP provider authentication
+        lp_provider.require_auth();
+
+        // Validate lp_tokens > 0
+        if lp_tokens == 0 {
+            panic!("lp tokens must be positive");
+        }
+
+        // Check if pool exists for this market
+        let pool_exists_key = (Symbol::new(&env, POOL_EXISTS_KEY), market_id.clone());
+        if !env.storage().persistent().has(&pool_exists_key) {
+            panic!("pool does not exist");
+        }
+
+        // Create storage keys for this pool
+        let yes_reserve_key = (Symbol::new(&env, POOL_YES_RESERVE_KEY), market_id.clone());
+        let no_reserve_key = (Symbol::new(&env, POOL_NO_RESERVE_KEY), market_id.clone());
+        let lp_supply_key = (Symbol::new(&env, POOL_LP_SUPPLY_KEY), market_id.clone());
+        let lp_balance_key = (
+            Symbol::new(&env, POOL_LP_TOKENS_KEY),
+            market_id.clone(),
+            lp_provider.clone(),
+        );
+
+        // Get LP provider's current balance
+        let lp_balance: u128 = env.storage().persistent().get(&lp_balance_key).unwrap_or(0);
+
+        // Validate user has enough LP tokens
+        if lp_balance < lp_tokens {
+            panic!("insufficient lp tokens");
         }
 
         // Get current reserves
@@ -591,9 +1721,6 @@ impl AMM {
             panic!("cannot drain pool completely");
         }
 
-        // Update k
-        let new_k = new_yes_reserve * new_no_reserve;
-
         // Store updated reserves and k
         env.storage()
             .persistent()
@@ -601,7 +1728,7 @@ impl AMM {
         env.storage()
             .persistent()
             .set(&no_reserve_key, &new_no_reserve);
-        env.storage().persistent().set(&k_key, &new_k);
+        Self::checkpoint_pool(&env, &market_id, new_yes_reserve, new_no_reserve);
 
         // Burn LP tokens from provider
         let new_lp_balance = lp_balance - lp_tokens;
@@ -613,6 +1740,28 @@ impl AMM {
                 .set(&lp_balance_key, &new_lp_balance);
         }
 
+        // Shrink the recorded entry position by the share withdrawn
+        let entry_key = (
+            Symbol::new(&env, POOL_LP_ENTRY_KEY),
+            market_id.clone(),
+            lp_provider.clone(),
+        );
+        if new_lp_balance == 0 {
+            env.storage().persistent().remove(&entry_key);
+        } else if let Some((yes_held, no_held)) = env
+            .storage()
+            .persistent()
+            .get::<_, (u128, u128)>(&entry_key)
+        {
+            env.storage().persistent().set(
+                &entry_key,
+                &(
+                    (yes_held * new_lp_balance) / lp_balance,
+                    (no_held * new_lp_balance) / lp_balance,
+                ),
+            );
+        }
+
         // Update LP token supply
         let new_lp_supply = current_lp_supply - lp_tokens;
         env.storage()
@@ -629,6 +1778,7 @@ impl AMM {
 
         let token_client = token::Client::new(&env, &usdc_token);
         let total_withdrawal = yes_amount + no_amount;
+        Self::decrease_tvl(&env, total_withdrawal);
         token_client.transfer(
             &env.current_contract_address(),
             &lp_provider,
@@ -674,8 +1824,282 @@ impl AMM {
         )
     }
 
+    /// Check a pool's reserve invariants for monitoring
+    ///
+    /// Returns false instead of panicking if the pool is missing, a reserve is
+    /// empty, the pool has no LP supply, or the reserves no longer match the k
+    /// and total liquidity checkpointed by the last trade or liquidity change.
+    pub fn verify_pool_invariants(env: Env, market_id: BytesN<32>) -> bool {
+        let pool_exists_key = (Symbol::new(&env, POOL_EXISTS_KEY), market_id.clone());
+        if !env.storage().persistent().has(&pool_exists_key) {
+            return false;
+        }
+
+        let (yes_reserve, no_reserve) = get_pool_reserves(&env, &market_id);
+        if yes_reserve == 0 || no_reserve == 0 {
+            return false;
+        }
+
+        let stored_k: u128 = env
+            .storage()
+            .persistent()
+            .get(&(Symbol::new(&env, POOL_K_KEY), market_id.clone()))
+            .unwrap_or(0);
+        let stored_liquidity: u128 = env
+            .storage()
+            .persistent()
+            .get(&(Symbol::new(&env, POOL_LIQUIDITY_KEY), market_id.clone()))
+            .unwrap_or(0);
+        let lp_supply: u128 = env
+            .storage()
+            .persistent()
+            .get(&(Symbol::new(&env, POOL_LP_SUPPLY_KEY), market_id))
+            .unwrap_or(0);
+
+        let k_holds = yes_reserve.checked_mul(no_reserve) == Some(stored_k);
+        let liquidity_holds =
+            lp_supply > 0 && yes_reserve.checked_add(no_reserve) == Some(stored_liquidity);
+
+        k_holds && liquidity_holds
+    }
+
+    /// Helper: Record k and total liquidity after a reserve change, for `verify_pool_invariants`
+    fn checkpoint_pool(env: &Env, market_id: &BytesN<32>, yes_reserve: u128, no_reserve: u128) {
+        env.storage().persistent().set(
+            &(Symbol::new(env, POOL_K_KEY), market_id.clone()),
+            &(yes_reserve * no_reserve),
+        );
+        env.storage().persistent().set(
+            &(Symbol::new(env, POOL_LIQUIDITY_KEY), market_id.clone()),
+            &(yes_reserve + no_reserve),
+        );
+    }
+
+    /// Get number of trades (buys and sells) executed against a pool
+    pub fn get_trade_count(env: Env, market_id: BytesN<32>) -> u32 {
+        get_trade_count(&env, &market_id)
+    }
+
+    /// Get cumulative USDC volume traded against a pool
+    ///
+    /// Buys count their full `amount`; sells count the payout after fees.
+    pub fn get_pool_volume(env: Env, market_id: BytesN<32>) -> u128 {
+        get_pool_volume(&env, &market_id)
+    }
+
+    /// Get (timestamp, yes_odds_bps) samples recorded after each trade, oldest first
+    pub fn get_price_history(
+        env: Env,
+        market_id: BytesN<32>,
+        offset: u32,
+        limit: u32,
+    ) -> Vec<(u64, u32)> {
+        let mut page = Vec::new(&env);
+        let end = offset
+            .saturating_add(limit)
+            .min(get_price_history_len(&env, &market_id));
+        for i in offset..end {
+            page.push_back(get_price_sample(&env, &market_id, i).unwrap());
+        }
+        page
+    }
+
+    /// Get the number of price samples recorded for a pool
+    pub fn get_price_history_len(env: Env, market_id: BytesN<32>) -> u32 {
+        get_price_history_len(&env, &market_id)
+    }
+
+    /// Link a pool to its market contract so the market can trigger settlement
+    /// Only the factory may register the market address
+    pub fn set_pool_market(env: Env, market_id: BytesN<32>, market_address: Address) {
+        let factory: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, FACTORY_KEY))
+            .expect("factory not set");
+        factory.require_auth();
+
+        let pool_exists_key = (Symbol::new(&env, POOL_EXISTS_KEY), market_id.clone());
+        if !env.storage().persistent().has(&pool_exists_key) {
+            panic!("pool does not exist");
+        }
+
+        env.storage().persistent().set(
+            &(Symbol::new(&env, POOL_MARKET_KEY), market_id),
+            &market_address,
+        );
+    }
+
+    /// Settle pool to the market outcome reported by the oracle
+    ///
+    /// Reads the oracle consensus result, records the winning outcome,
+    /// marks the pool settled and fixes the per-share redemption price.
+    /// Only the factory or the pool's registered market may trigger settlement.
+    pub fn settle_pool_from_market(
+        env: Env,
+        caller: Address,
+        market_id: BytesN<32>,
+        oracle_address: Address,
+    ) {
+        caller.require_auth();
+
+        // Fetch the resolved outcome from the oracle
+        let oracle_client = crate::oracle::OracleManagerClient::new(&env, &oracle_address);
+        let winning_outcome = oracle_client.get_consensus_result(&market_id);
+        Self::settle_to_outcome(&env, &caller, market_id, winning_outcome);
+    }
+
+    /// Settle pool to the winning outcome recorded by its market
+    ///
+    /// Use when the market's outcome can differ from the oracle consensus (a
+    /// plurality timeout or an upheld dispute). Only the factory or the pool's
+    /// registered market may trigger settlement.
+    pub fn settle_pool(env: Env, caller: Address, market_id: BytesN<32>, winning_outcome: u32) {
+        caller.require_auth();
+        Self::settle_to_outcome(&env, &caller, market_id, winning_outcome);
+    }
+
+    /// Helper: Validate the settlement caller and settle the pool to `winning_outcome`
+    fn settle_to_outcome(env: &Env, caller: &Address, market_id: BytesN<32>, winning_outcome: u32) {
+        // Validate caller is the factory or the market linked to this pool
+        let factory: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, FACTORY_KEY))
+            .expect("factory not set");
+        let market: Option<Address> = env
+            .storage()
+            .persistent()
+            .get(&(Symbol::new(env, POOL_MARKET_KEY), market_id.clone()));
+        if *caller != factory && market.as_ref() != Some(caller) {
+            panic!("unauthorized settlement caller");
+        }
+
+        // Check if pool exists
+        let pool_exists_key = (Symbol::new(env, POOL_EXISTS_KEY), market_id.clone());
+        if !env.storage().persistent().has(&pool_exists_key) {
+            panic!("pool does not exist");
+        }
+
+        let settled_key = (Symbol::new(env, POOL_SETTLED_KEY), market_id.clone());
+        if env
+            .storage()
+            .persistent()
+            .get(&settled_key)
+            .unwrap_or(false)
+        {
+            panic!("pool already settled");
+        }
+
+        if winning_outcome > 1 {
+            panic!("Invalid oracle outcome");
+        }
+
+        env.storage().persistent().set(&settled_key, &true);
+        env.storage().persistent().set(
+            &(
+                Symbol::new(env, POOL_WINNING_OUTCOME_KEY),
+                market_id.clone(),
+            ),
+            &winning_outcome,
+        );
+        env.storage().persistent().set(
+            &(
+                Symbol::new(env, POOL_REDEMPTION_PRICE_KEY),
+                market_id.clone(),
+            ),
+            &WINNING_SHARE_PRICE_BPS,
+        );
+
+        // Emit PoolSettled event
+        env.events().publish(
+            (Symbol::new(env, "pool_settled"),),
+            (market_id, winning_outcome, WINNING_SHARE_PRICE_BPS),
+        );
+    }
+
+    /// Check whether a pool has been settled to its market outcome
+    pub fn is_pool_settled(env: Env, market_id: BytesN<32>) -> bool {
+        env.storage()
+            .persistent()
+            .get(&(Symbol::new(&env, POOL_SETTLED_KEY), market_id))
+            .unwrap_or(false)
+    }
+
+    /// Get the outcome a settled pool redeems, or None before settlement
+    pub fn get_pool_winning_outcome(env: Env, market_id: BytesN<32>) -> Option<u32> {
+        env.storage()
+            .persistent()
+            .get(&(Symbol::new(&env, POOL_WINNING_OUTCOME_KEY), market_id))
+    }
+
+    /// Redeem winning shares of a settled pool for USDC
+    /// Losing shares have no redemption value
+    /// Returns USDC payout amount
+    pub fn redeem_shares(env: Env, user: Address, market_id: BytesN<32>) -> u128 {
+        user.require_auth();
+
+        let settled_key = (Symbol::new(&env, POOL_SETTLED_KEY), market_id.clone());
+        if !env
+            .storage()
+            .persistent()
+            .get(&settled_key)
+            .unwrap_or(false)
+        {
+            panic!("pool not settled");
+        }
+
+        let winning_outcome: u32 = env
+            .storage()
+            .persistent()
+            .get(&(
+                Symbol::new(&env, POOL_WINNING_OUTCOME_KEY),
+                market_id.clone(),
+            ))
+            .expect("winning outcome not set");
+        let redemption_price: u128 = env
+            .storage()
+            .persistent()
+            .get(&(
+                Symbol::new(&env, POOL_REDEMPTION_PRICE_KEY),
+                market_id.clone(),
+            ))
+            .expect("redemption price not set");
+
+        let user_share_key = (
+            Symbol::new(&env, USER_SHARES_KEY),
+            market_id.clone(),
+            user.clone(),
+            winning_outcome,
+        );
+        let shares: u128 = env.storage().persistent().get(&user_share_key).unwrap_or(0);
+        if shares == 0 {
+            panic!("no winning shares to redeem");
+        }
+
+        let payout = (shares * redemption_price) / 10000;
+
+        // Burn redeemed shares before transferring
+        env.storage().persistent().remove(&user_share_key);
+
+        let usdc_token: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, USDC_KEY))
+            .expect("usdc token not set");
+        let token_client = token::Client::new(&env, &usdc_token);
+        token_client.transfer(&env.current_contract_address(), &user, &(payout as i128));
+
+        // Emit SharesRedeemed event
+        env.events().publish(
+            (Symbol::new(&env, "shares_redeemed"),),
+            (user, market_id, winning_outcome, shares, payout),
+        );
+
+        payout
+    }
+
     // TODO: Implement remaining AMM functions
-    // - add_liquidity()
     // - get_lp_position() / claim_lp_fees()
     // - calculate_spot_price()
     // - get_trade_history()
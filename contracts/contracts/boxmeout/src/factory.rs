@@ -2,7 +2,9 @@
 // Handles market creation and lifecycle management
 
 use soroban_sdk::{
-    contract, contractimpl, token, Address, Bytes, BytesN, Env, Symbol, Vec,
+    auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation},
+    contract, contractimpl, contracttype, token, vec, Address, Bytes, BytesN, Env, IntoVal, Symbol,
+    Vec,
 };
 
 // Storage keys
@@ -10,8 +12,60 @@ const ADMIN_KEY: &str = "admin";
 const USDC_KEY: &str = "usdc";
 const TREASURY_KEY: &str = "treasury";
 const MARKET_COUNT_KEY: &str = "market_count";
+const MARKET_WASM_HASH_KEY: &str = "market_wasm_hash";
+const ORACLE_KEY: &str = "oracle";
+const MARKET_IDS_KEY: &str = "market_ids";
+const TOKEN_DECIMALS_KEY: &str = "token_decimals";
+const CREATOR_BOND_KEY: &str = "creator_bond";
+const ESCROWED_BONDS_KEY: &str = "escrowed_bonds";
+const AMM_KEY: &str = "amm";
+const TOP_VOLUME_KEY: &str = "top_volume";
+
+/// Market creation fee in whole USDC, scaled by the token's decimals
+const CREATION_FEE_UNITS: i128 = 1;
+
+/// Most markets kept in the volume ranking
+const MAX_RANKED_MARKETS: u32 = 50;
+
+/// Most markets `get_markets_by_state` scans per call
+const MAX_STATE_SCAN: u32 = 100;
 
 /// MARKET FACTORY - Handles market creation, fee collection, and market registry
+/// Factory metadata combined with a deployed market's live state
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MarketSummaryFull {
+    pub market_id: BytesN<32>,
+    pub market_address: Address,
+    pub creator: Address,
+    pub title: Symbol,
+    pub description: Symbol,
+    pub category: Symbol,
+    pub closing_time: u64,
+    pub resolution_time: u64,
+    pub state: u32,
+    pub phase: Symbol,
+    pub yes_pool: i128,
+    pub no_pool: i128,
+    pub total_volume: i128,
+    pub yes_odds: u32,
+    pub no_odds: u32,
+}
+
+/// Addresses and parameters used by `initialize_platform` to wire the platform
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PlatformConfig {
+    pub usdc: Address,
+    pub treasury: Address,
+    pub oracle: Address,
+    pub amm: Address,
+    /// Oracle consensus threshold, used if the oracle is not yet initialized
+    pub required_consensus: u32,
+    /// AMM liquidity cap, used if the AMM is not yet initialized
+    pub max_liquidity_cap: u128,
+}
+
 #[contract]
 pub struct MarketFactory;
 
@@ -41,6 +95,12 @@ impl MarketFactory {
             .persistent()
             .set(&Symbol::new(&env, USDC_KEY), &usdc);
 
+        // Store USDC decimals so fixed amounts scale with the deployed token
+        let decimals = crate::helpers::read_token_decimals(&env, &usdc);
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, TOKEN_DECIMALS_KEY), &decimals);
+
         // Store Treasury contract address
         env.storage()
             .persistent()
@@ -58,6 +118,103 @@ impl MarketFactory {
         );
     }
 
+    /// Initialize the factory and wire the treasury, oracle and AMM to it in one call
+    ///
+    /// Contracts that are not yet initialized are initialized with `admin` and
+    /// this factory; ones that already are must point at this factory, USDC token
+    /// and treasury. The AMM is registered as a treasury depositor.
+    pub fn initialize_platform(env: Env, admin: Address, config: PlatformConfig) {
+        Self::initialize(
+            env.clone(),
+            admin.clone(),
+            config.usdc.clone(),
+            config.treasury.clone(),
+        );
+        let factory_address = env.current_contract_address();
+
+        // Treasury: holds fees on behalf of this factory
+        let treasury_client = crate::treasury::TreasuryClient::new(&env, &config.treasury);
+        if treasury_client.try_get_admin().is_ok() {
+            if treasury_client.get_factory() != factory_address {
+                panic!("treasury wired to a different factory");
+            }
+            if treasury_client.get_usdc() != config.usdc {
+                panic!("treasury uses a different USDC token");
+            }
+        } else {
+            treasury_client.initialize(&admin, &config.usdc, &factory_address);
+        }
+
+        // Oracle: resolves markets deployed by this factory
+        let oracle_client = crate::oracle::OracleManagerClient::new(&env, &config.oracle);
+        if oracle_client.try_get_admin().is_ok() {
+            if oracle_client
+                .get_usdc_token()
+                .is_some_and(|usdc| usdc != config.usdc)
+            {
+                panic!("oracle uses a different USDC token");
+            }
+            if oracle_client
+                .get_treasury()
+                .is_some_and(|treasury| treasury != config.treasury)
+            {
+                panic!("oracle wired to a different treasury");
+            }
+            if oracle_client
+                .get_factory()
+                .is_some_and(|factory| factory != factory_address)
+            {
+                panic!("oracle wired to a different factory");
+            }
+        } else {
+            oracle_client.initialize(
+                &admin,
+                &config.required_consensus,
+                &crate::oracle::OracleConfig {
+                    usdc_token: Some(config.usdc.clone()),
+                    treasury: Some(config.treasury.clone()),
+                    factory: Some(factory_address.clone()),
+                    ..Default::default()
+                },
+            );
+        }
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, ORACLE_KEY), &config.oracle);
+
+        // AMM: reports pools to this factory and sweeps fees into the treasury
+        let amm_client = crate::amm::AMMClient::new(&env, &config.amm);
+        if amm_client.try_get_factory().is_ok() {
+            if amm_client.get_factory() != factory_address {
+                panic!("AMM wired to a different factory");
+            }
+        } else {
+            amm_client.initialize(
+                &admin,
+                &factory_address,
+                &config.usdc,
+                &config.max_liquidity_cap,
+            );
+        }
+        amm_client.set_treasury(&admin, &config.treasury);
+        // Let the AMM deposit swept protocol fees
+        treasury_client.register_market_depositor(&factory_address, &config.amm);
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, AMM_KEY), &config.amm);
+
+        env.events().publish(
+            (Symbol::new(&env, "PlatformInitialized"),),
+            (
+                admin,
+                config.usdc,
+                config.treasury,
+                config.oracle,
+                config.amm,
+            ),
+        );
+    }
+
     /// Get total markets created
     pub fn get_market_count(env: Env) -> u32 {
         env.storage()
@@ -66,6 +223,32 @@ impl MarketFactory {
             .unwrap_or(0)
     }
 
+    /// Get admin address
+    pub fn get_admin(env: Env) -> Address {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, ADMIN_KEY))
+            .expect("Admin not set")
+    }
+
+    /// Get USDC token address
+    pub fn get_usdc(env: Env) -> Address {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, USDC_KEY))
+            .expect("USDC not set")
+    }
+
+    /// Get the market creation fee in USDC base units
+    pub fn get_creation_fee(env: Env) -> i128 {
+        let decimals: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, TOKEN_DECIMALS_KEY))
+            .unwrap_or(crate::helpers::DEFAULT_TOKEN_DECIMALS);
+        crate::helpers::whole_units(decimals, CREATION_FEE_UNITS)
+    }
+
     /// Get treasury address
     pub fn get_treasury(env: Env) -> Address {
         env.storage()
@@ -74,7 +257,282 @@ impl MarketFactory {
             .expect("Treasury not set")
     }
 
+    /// Admin: Configure market contract deployment
+    ///
+    /// Once set, `create_market` deploys and initializes a `PredictionMarket`
+    /// from `wasm_hash`, wired to `oracle`, for every new market.
+    pub fn set_market_deployment(env: Env, admin: Address, wasm_hash: BytesN<32>, oracle: Address) {
+        admin.require_auth();
+        let stored_admin: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ADMIN_KEY))
+            .expect("Admin not set");
+        if admin != stored_admin {
+            panic!("Unauthorized: only admin can configure deployment");
+        }
+
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, MARKET_WASM_HASH_KEY), &wasm_hash);
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, ORACLE_KEY), &oracle);
+    }
+
+    /// Admin: Set the refundable bond collected from creators on `create_market`
+    ///
+    /// Applies to markets created afterwards; 0 disables the bond.
+    pub fn set_creator_bond(env: Env, admin: Address, bond: i128) {
+        admin.require_auth();
+        let stored_admin: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ADMIN_KEY))
+            .expect("Admin not set");
+        if admin != stored_admin {
+            panic!("Unauthorized: only admin can set creator bond");
+        }
+        if bond < 0 {
+            panic!("Creator bond cannot be negative");
+        }
+
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, CREATOR_BOND_KEY), &bond);
+    }
+
+    /// Get the creator bond charged for new markets
+    pub fn get_creator_bond(env: Env) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, CREATOR_BOND_KEY))
+            .unwrap_or(0)
+    }
+
+    /// Get the bond still escrowed for a market
+    pub fn get_market_bond(env: Env, market_id: BytesN<32>) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&(Symbol::new(&env, "market_bond"), market_id))
+            .unwrap_or(0)
+    }
+
+    /// Creator: Reclaim the creator bond once the market has resolved
+    ///
+    /// The bond stays escrowed until the market's dispute window closes or a
+    /// dispute against it is rejected, so an upheld dispute can still forfeit it.
+    pub fn claim_creator_bond(env: Env, creator: Address, market_id: BytesN<32>) -> i128 {
+        creator.require_auth();
+
+        let metadata_key = (Symbol::new(&env, "market_meta"), market_id.clone());
+        let (market_creator, _, _, _, _, _): (Address, Symbol, Symbol, Symbol, u64, u64) = env
+            .storage()
+            .persistent()
+            .get(&metadata_key)
+            .expect("Market not found");
+        if creator != market_creator {
+            panic!("not market creator");
+        }
+
+        if Self::read_market_state(&env, &market_id) != crate::market::STATE_RESOLVED {
+            panic!("Market not resolved");
+        }
+
+        let market_address: Address = env
+            .storage()
+            .persistent()
+            .get(&(Symbol::new(&env, "market_addr"), market_id.clone()))
+            .expect("Market not deployed");
+        let market_client = crate::market::PredictionMarketClient::new(&env, &market_address);
+        match market_client.get_dispute_upheld() {
+            Some(true) => panic!("Dispute upheld"),
+            Some(false) => {}
+            None => {
+                let window_end = market_client
+                    .get_dispute_window_end()
+                    .expect("Market not resolved");
+                if env.ledger().timestamp() < window_end {
+                    panic!("Dispute window open");
+                }
+            }
+        }
+
+        let bond = Self::take_market_bond(&env, &market_id);
+        let usdc: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, USDC_KEY))
+            .expect("USDC not set");
+        token::Client::new(&env, &usdc).transfer(&env.current_contract_address(), &creator, &bond);
+
+        env.events().publish(
+            (Symbol::new(&env, "CreatorBondRefunded"),),
+            (market_id, creator, bond),
+        );
+
+        bond
+    }
+
+    /// Forfeit a cancelled market's creator bond to the treasury
+    ///
+    /// Permissionless, so anyone can settle the bond once the market is cancelled.
+    /// Bonds of markets whose dispute was upheld are forfeited by
+    /// `resolve_market_dispute`.
+    pub fn forfeit_creator_bond(env: Env, market_id: BytesN<32>) -> i128 {
+        if Self::read_market_state(&env, &market_id) != crate::market::STATE_CANCELLED {
+            panic!("Market not cancelled");
+        }
+
+        let bond = Self::take_market_bond(&env, &market_id);
+        Self::deposit_to_treasury(&env, bond);
+
+        env.events().publish(
+            (Symbol::new(&env, "CreatorBondForfeited"),),
+            (market_id, bond),
+        );
+
+        bond
+    }
+
+    /// Helper: Read a deployed market's lifecycle state
+    fn read_market_state(env: &Env, market_id: &BytesN<32>) -> u32 {
+        let market_address: Address = env
+            .storage()
+            .persistent()
+            .get(&(Symbol::new(env, "market_addr"), market_id.clone()))
+            .expect("Market not deployed");
+        crate::market::PredictionMarketClient::new(env, &market_address)
+            .get_market_state_value()
+            .unwrap_or(0)
+    }
+
+    /// Helper: Remove a market's escrowed bond and return its amount
+    fn take_market_bond(env: &Env, market_id: &BytesN<32>) -> i128 {
+        let bond_key = (Symbol::new(env, "market_bond"), market_id.clone());
+        let bond: i128 = env.storage().persistent().get(&bond_key).unwrap_or(0);
+        if bond <= 0 {
+            panic!("No bond to claim");
+        }
+        env.storage().persistent().remove(&bond_key);
+
+        let escrowed: i128 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, ESCROWED_BONDS_KEY))
+            .unwrap_or(0);
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(env, ESCROWED_BONDS_KEY), &(escrowed - bond));
+
+        bond
+    }
+
+    /// Helper: Deposit USDC held by the factory into the treasury
+    ///
+    /// The treasury only accepts deposits from the factory or registered markets
+    /// and pulls the funds itself, so the transfer is pre-authorized here.
+    fn deposit_to_treasury(env: &Env, amount: i128) {
+        let treasury_address: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, TREASURY_KEY))
+            .expect("Treasury address not set");
+        let usdc: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, USDC_KEY))
+            .expect("USDC not set");
+        let factory_address = env.current_contract_address();
+
+        env.authorize_as_current_contract(vec![
+            env,
+            InvokerContractAuthEntry::Contract(SubContractInvocation {
+                context: ContractContext {
+                    contract: usdc,
+                    fn_name: Symbol::new(env, "transfer"),
+                    args: (factory_address.clone(), treasury_address.clone(), amount).into_val(env),
+                },
+                sub_invocations: vec![env],
+            }),
+        ]);
+
+        crate::treasury::TreasuryClient::new(env, &treasury_address)
+            .deposit_fees(&factory_address, &amount);
+    }
+
+    /// Admin: Set the AMM contract allowed to report pools for factory markets
+    pub fn set_amm_address(env: Env, admin: Address, amm: Address) {
+        admin.require_auth();
+        let stored_admin: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ADMIN_KEY))
+            .expect("Admin not set");
+        if admin != stored_admin {
+            panic!("Unauthorized: only admin can set AMM");
+        }
+
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, AMM_KEY), &amm);
+    }
+
+    /// Get the AMM contract address
+    pub fn get_amm_address(env: Env) -> Address {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, AMM_KEY))
+            .expect("AMM not set")
+    }
+
+    /// AMM: Record that a pool now exists for a market
+    pub fn report_pool_created(env: Env, market_id: BytesN<32>) {
+        let amm = Self::get_amm_address(env.clone());
+        amm.require_auth();
+
+        let market_key = (Symbol::new(&env, "market"), market_id.clone());
+        if !env.storage().persistent().has(&market_key) {
+            panic!("Market not found");
+        }
+
+        env.storage()
+            .persistent()
+            .set(&(Symbol::new(&env, "has_pool"), market_id), &true);
+    }
+
+    /// AMM: Record that a market's pool was cancelled
+    pub fn report_pool_cancelled(env: Env, market_id: BytesN<32>) {
+        let amm = Self::get_amm_address(env.clone());
+        amm.require_auth();
+
+        env.storage()
+            .persistent()
+            .remove(&(Symbol::new(&env, "has_pool"), market_id));
+    }
+
+    /// Check whether an AMM pool exists for a market
+    pub fn market_has_pool(env: Env, market_id: BytesN<32>) -> bool {
+        env.storage()
+            .persistent()
+            .get(&(Symbol::new(&env, "has_pool"), market_id))
+            .unwrap_or(false)
+    }
+
+    /// Get deployed market contract address by market_id
+    pub fn get_market_address(env: Env, market_id: BytesN<32>) -> Option<Address> {
+        env.storage()
+            .persistent()
+            .get(&(Symbol::new(&env, "market_addr"), market_id))
+    }
+
     /// Create a new market instance
+    ///
+    /// When market deployment is configured, the market contract is deployed and
+    /// initialized after the creation fee is charged. No explicit refund path is
+    /// needed: if deployment or initialization fails the whole invocation reverts
+    /// atomically, including the fee transfer, so the creator is never charged
+    /// for a market that doesn't exist.
     pub fn create_market(
         env: Env,
         creator: Address,
@@ -132,17 +590,108 @@ impl MarketFactory {
             .persistent()
             .set(&Symbol::new(&env, MARKET_COUNT_KEY), &(market_count + 1));
 
-        // Charge creation fee (1 USDC = 10^7 stroops, assuming 7 decimals)
-        let creation_fee: i128 = 10_000_000; // 1 USDC
+        // Append to the market index used by ranked reads
+        let mut market_ids: Vec<BytesN<32>> = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, MARKET_IDS_KEY))
+            .unwrap_or(Vec::new(&env));
+        market_ids.push_back(market_id.clone());
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, MARKET_IDS_KEY), &market_ids);
+
+        // Charge creation fee (1 USDC in the token's base units)
+        let creation_fee = Self::get_creation_fee(env.clone());
         let treasury_address: Address = env
             .storage()
             .persistent()
             .get(&Symbol::new(&env, TREASURY_KEY))
             .expect("Treasury address not set");
 
+        // Collect fee from creator, then deposit it as the factory (treasury only
+        // accepts deposits from the factory or registered markets)
+        let usdc: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, USDC_KEY))
+            .expect("USDC not set");
+        let factory_address = env.current_contract_address();
+        let token_client = token::Client::new(&env, &usdc);
+        token_client.transfer(&creator, &factory_address, &creation_fee);
+
         // Route fee to treasury
+        Self::deposit_to_treasury(&env, creation_fee);
         let treasury_client = crate::treasury::TreasuryClient::new(&env, &treasury_address);
-        treasury_client.deposit_fees(&creator, &creation_fee);
+
+        // Escrow the creator bond until the market resolves or is cancelled
+        let creator_bond = Self::get_creator_bond(env.clone());
+        if creator_bond > 0 {
+            token_client.transfer(&creator, &factory_address, &creator_bond);
+            env.storage().persistent().set(
+                &(Symbol::new(&env, "market_bond"), market_id.clone()),
+                &creator_bond,
+            );
+            let escrowed: i128 = env
+                .storage()
+                .persistent()
+                .get(&Symbol::new(&env, ESCROWED_BONDS_KEY))
+                .unwrap_or(0);
+            env.storage().persistent().set(
+                &Symbol::new(&env, ESCROWED_BONDS_KEY),
+                &(escrowed + creator_bond),
+            );
+        }
+
+        // Deploy and initialize the market contract (reverts everything above on failure)
+        let wasm_hash: Option<BytesN<32>> = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, MARKET_WASM_HASH_KEY));
+        if let Some(wasm_hash) = wasm_hash {
+            let oracle: Address = env
+                .storage()
+                .persistent()
+                .get(&Symbol::new(&env, ORACLE_KEY))
+                .expect("Oracle not set");
+
+            let market_address = env
+                .deployer()
+                .with_current_contract(market_id.clone())
+                .deploy_v2(wasm_hash, ());
+
+            let market_client = crate::market::PredictionMarketClient::new(&env, &market_address);
+            market_client.initialize(
+                &market_id,
+                &creator,
+                &factory_address,
+                &usdc,
+                &oracle,
+                &closing_time,
+                &resolution_time,
+                &crate::market::MarketConfig {
+                    num_outcomes: crate::helpers::BINARY_OUTCOMES,
+                    ..Default::default()
+                },
+            );
+
+            env.storage().persistent().set(
+                &(Symbol::new(&env, "market_addr"), market_id.clone()),
+                &market_address,
+            );
+
+            // Allow the new market to route its fees into the treasury
+            treasury_client.register_market_depositor(&factory_address, &market_address);
+
+            // Let oracles discover the market; best effort, since resolution only
+            // needs the oracle's consensus
+            let _ = crate::oracle::OracleManagerClient::new(&env, &oracle).try_request_resolution(
+                &factory_address,
+                &market_address,
+                &market_id,
+                &resolution_time,
+            );
+        }
 
         // Emit MarketCreated event
         env.events().publish(
@@ -163,14 +712,377 @@ impl MarketFactory {
         todo!("See get active markets TODO above")
     }
 
+    /// Get a market's factory metadata together with its live on-chain state
+    pub fn get_market_summary(env: Env, market_id: BytesN<32>) -> MarketSummaryFull {
+        let metadata_key = (Symbol::new(&env, "market_meta"), market_id.clone());
+        let (creator, title, description, category, closing_time, resolution_time): (
+            Address,
+            Symbol,
+            Symbol,
+            Symbol,
+            u64,
+            u64,
+        ) = env
+            .storage()
+            .persistent()
+            .get(&metadata_key)
+            .expect("Market not found");
+
+        let market_address: Address = env
+            .storage()
+            .persistent()
+            .get(&(Symbol::new(&env, "market_addr"), market_id.clone()))
+            .expect("Market not deployed");
+
+        let market_client = crate::market::PredictionMarketClient::new(&env, &market_address);
+        let (yes_pool, no_pool) = market_client.get_pool_sizes();
+        let (yes_odds, no_odds) = market_client.get_odds();
+
+        MarketSummaryFull {
+            market_id,
+            creator,
+            title,
+            description,
+            category,
+            closing_time,
+            resolution_time,
+            state: market_client.get_market_state_value().unwrap_or(0),
+            phase: market_client.get_market_phase(&env.ledger().timestamp()),
+            yes_pool,
+            no_pool,
+            total_volume: market_client.get_total_volume(),
+            yes_odds,
+            no_odds,
+            market_address,
+        }
+    }
+
+    /// Get up to `limit` markets ranked by total volume, highest first
+    ///
+    /// Reads the ranking markets keep current through `report_market_volume`;
+    /// only the top MAX_RANKED_MARKETS are tracked.
+    pub fn get_top_markets_by_volume(env: Env, limit: u32) -> Vec<(BytesN<32>, i128)> {
+        let mut ranked: Vec<(BytesN<32>, i128)> = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, TOP_VOLUME_KEY))
+            .unwrap_or(Vec::new(&env));
+        while ranked.len() > limit {
+            ranked.pop_back();
+        }
+        ranked
+    }
+
+    /// Market: Update a deployed market's place in the volume ranking
+    pub fn report_market_volume(env: Env, market_id: BytesN<32>, total_volume: i128) {
+        let market_address: Address = env
+            .storage()
+            .persistent()
+            .get(&(Symbol::new(&env, "market_addr"), market_id.clone()))
+            .expect("Market not deployed");
+        market_address.require_auth();
+
+        let ranking_key = Symbol::new(&env, TOP_VOLUME_KEY);
+        let mut ranked: Vec<(BytesN<32>, i128)> = env
+            .storage()
+            .persistent()
+            .get(&ranking_key)
+            .unwrap_or(Vec::new(&env));
+        if let Some(index) = ranked.iter().position(|(id, _)| id == market_id) {
+            ranked.remove(index as u32);
+        }
+
+        // Insert in descending volume order; ties keep the earlier entry first
+        let mut index = ranked.len();
+        while index > 0 && ranked.get(index - 1).unwrap().1 < total_volume {
+            index -= 1;
+        }
+        if index < MAX_RANKED_MARKETS {
+            ranked.insert(index, (market_id, total_volume));
+            while ranked.len() > MAX_RANKED_MARKETS {
+                ranked.pop_back();
+            }
+        }
+        env.storage().persistent().set(&ranking_key, &ranked);
+    }
+
+    /// Get markets currently in `state` (paginated, in creation order)
+    ///
+    /// Scans `limit` markets (at most `MAX_STATE_SCAN`) starting at `offset`
+    /// and returns those whose cached state matches, so callers page with
+    /// `offset += limit`. Markets without a deployed contract are skipped.
+    pub fn get_markets_by_state(env: Env, state: u32, offset: u32, limit: u32) -> Vec<BytesN<32>> {
+        let market_ids: Vec<BytesN<32>> = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, MARKET_IDS_KEY))
+            .unwrap_or(Vec::new(&env));
+
+        let mut matches = Vec::new(&env);
+        let end = offset
+            .saturating_add(limit.min(MAX_STATE_SCAN))
+            .min(market_ids.len());
+        for i in offset..end {
+            let market_id = market_ids.get(i).unwrap();
+            let deployed = env
+                .storage()
+                .persistent()
+                .has(&(Symbol::new(&env, "market_addr"), market_id.clone()));
+            if deployed && Self::get_cached_state(env.clone(), market_id.clone()) == state {
+                matches.push_back(market_id);
+            }
+        }
+        matches
+    }
+
     /// Get user's created markets
     pub fn get_creator_markets(env: Env, creator: Address) {
         todo!("See get creator markets TODO above")
     }
 
+    /// Market: Cache the resolved outcome reported by a deployed market
+    pub fn report_resolution(env: Env, market_id: BytesN<32>, outcome: u32) {
+        let market_address: Address = env
+            .storage()
+            .persistent()
+            .get(&(Symbol::new(&env, "market_addr"), market_id.clone()))
+            .expect("Market not deployed");
+        market_address.require_auth();
+
+        env.storage().persistent().set(
+            &(Symbol::new(&env, "market_resolution"), market_id.clone()),
+            &outcome,
+        );
+
+        env.events().publish(
+            (Symbol::new(&env, "MarketResolutionReported"),),
+            (market_id, outcome),
+        );
+    }
+
+    /// Market: Cache a deployed market's state after each transition
+    pub fn update_market_state(env: Env, market_id: BytesN<32>, new_state: u32) {
+        let market_address: Address = env
+            .storage()
+            .persistent()
+            .get(&(Symbol::new(&env, "market_addr"), market_id.clone()))
+            .expect("Market not deployed");
+        market_address.require_auth();
+
+        env.storage().persistent().set(
+            &(Symbol::new(&env, "market_state"), market_id.clone()),
+            &new_state,
+        );
+
+        env.events().publish(
+            (Symbol::new(&env, "MarketStateUpdated"),),
+            (market_id, new_state),
+        );
+    }
+
+    /// Get a market's state as last reported by the market (OPEN until its first transition)
+    pub fn get_cached_state(env: Env, market_id: BytesN<32>) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&(Symbol::new(&env, "market_state"), market_id))
+            .unwrap_or(0)
+    }
+
+    /// Market: Index a market under a user's first bet in it
+    pub fn report_user_bet(env: Env, market_id: BytesN<32>, user: Address) {
+        let market_address: Address = env
+            .storage()
+            .persistent()
+            .get(&(Symbol::new(&env, "market_addr"), market_id.clone()))
+            .expect("Market not deployed");
+        market_address.require_auth();
+
+        let user_markets_key = (Symbol::new(&env, "user_markets"), user);
+        let mut user_markets: Vec<BytesN<32>> = env
+            .storage()
+            .persistent()
+            .get(&user_markets_key)
+            .unwrap_or(Vec::new(&env));
+        if !user_markets.contains(&market_id) {
+            user_markets.push_back(market_id);
+            env.storage()
+                .persistent()
+                .set(&user_markets_key, &user_markets);
+        }
+    }
+
+    /// Get the markets a user has bet on, in order of first bet
+    pub fn get_user_markets(env: Env, user: Address) -> Vec<BytesN<32>> {
+        env.storage()
+            .persistent()
+            .get(&(Symbol::new(&env, "user_markets"), user))
+            .unwrap_or(Vec::new(&env))
+    }
+
     /// Get market resolution
-    pub fn get_market_resolution(env: Env, market_id: BytesN<32>) -> Symbol {
-        todo!("See get market resolution TODO above")
+    ///
+    /// Reads the cached outcome first and falls back to querying the deployed
+    /// market. Returns None while the market is unresolved or not deployed.
+    pub fn get_market_resolution(env: Env, market_id: BytesN<32>) -> Option<u32> {
+        let cached: Option<u32> = env
+            .storage()
+            .persistent()
+            .get(&(Symbol::new(&env, "market_resolution"), market_id.clone()));
+        if cached.is_some() {
+            return cached;
+        }
+
+        let market_address: Option<Address> = env
+            .storage()
+            .persistent()
+            .get(&(Symbol::new(&env, "market_addr"), market_id));
+        match market_address {
+            Some(address) => {
+                crate::market::PredictionMarketClient::new(&env, &address).get_winning_outcome()
+            }
+            None => None,
+        }
+    }
+
+    /// Keeper: Resolve a market and settle its AMM pool in one call
+    ///
+    /// A deployed market is resolved unless it already has been, and the pool is
+    /// settled to the market's winning outcome only once the market actually
+    /// resolved. Cancelled and disputed markets are skipped and leave their pool
+    /// alone. Markets without a deployed contract settle their pool straight
+    /// from the factory's oracle; markets without a pool skip settlement.
+    /// Returns (market_resolved, pool_settled) as of the end of the call.
+    pub fn finalize_market(env: Env, market_id: BytesN<32>) -> (bool, bool) {
+        let market_address: Option<Address> = env
+            .storage()
+            .persistent()
+            .get(&(Symbol::new(&env, "market_addr"), market_id.clone()));
+
+        // The market's own outcome wins over the oracle consensus: plurality
+        // timeouts and upheld disputes can differ from it
+        let (market_resolved, market_outcome) = match &market_address {
+            Some(address) => {
+                let market_client = crate::market::PredictionMarketClient::new(&env, address);
+                let state = market_client.get_market_state_value();
+                let unresolvable = [
+                    Some(crate::market::STATE_RESOLVED),
+                    Some(crate::market::STATE_CANCELLED),
+                    Some(crate::market::STATE_DISPUTED),
+                ];
+                if !unresolvable.contains(&state) {
+                    market_client.resolve_market(&market_id);
+                }
+                let resolved =
+                    market_client.get_market_state_value() == Some(crate::market::STATE_RESOLVED);
+                let outcome = if resolved {
+                    market_client.get_winning_outcome()
+                } else {
+                    None
+                };
+                (resolved, outcome)
+            }
+            None => (false, None),
+        };
+
+        let amm: Option<Address> = env.storage().persistent().get(&Symbol::new(&env, AMM_KEY));
+        let pool_settled = match amm {
+            Some(amm) if Self::market_has_pool(env.clone(), market_id.clone()) => {
+                let amm_client = crate::amm::AMMClient::new(&env, &amm);
+                let factory_address = env.current_contract_address();
+                if !amm_client.is_pool_settled(&market_id) {
+                    if let Some(outcome) = market_outcome {
+                        amm_client.settle_pool(&factory_address, &market_id, &outcome);
+                    } else if market_address.is_none() {
+                        let oracle: Address = env
+                            .storage()
+                            .persistent()
+                            .get(&Symbol::new(&env, ORACLE_KEY))
+                            .expect("Oracle not set");
+                        amm_client.settle_pool_from_market(&factory_address, &market_id, &oracle);
+                    }
+                }
+                amm_client.is_pool_settled(&market_id)
+            }
+            _ => false,
+        };
+
+        env.events().publish(
+            (Symbol::new(&env, "MarketFinalized"),),
+            (market_id, market_resolved, pool_settled),
+        );
+
+        (market_resolved, pool_settled)
+    }
+
+    /// Admin: Settle a deployed market's open dispute
+    ///
+    /// Forwards to the market's `resolve_dispute`, which only accepts the factory.
+    /// Upholding the dispute forfeits the creator bond to the treasury.
+    pub fn resolve_market_dispute(
+        env: Env,
+        admin: Address,
+        market_id: BytesN<32>,
+        upheld: bool,
+        corrected_outcome: Option<u32>,
+    ) {
+        admin.require_auth();
+        let stored_admin: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ADMIN_KEY))
+            .expect("Admin not set");
+        if admin != stored_admin {
+            panic!("Unauthorized: only admin can resolve disputes");
+        }
+
+        let market_address: Address = env
+            .storage()
+            .persistent()
+            .get(&(Symbol::new(&env, "market_addr"), market_id.clone()))
+            .expect("Market not deployed");
+        crate::market::PredictionMarketClient::new(&env, &market_address).resolve_dispute(
+            &env.current_contract_address(),
+            &market_id,
+            &upheld,
+            &corrected_outcome,
+        );
+
+        if upheld && Self::get_market_bond(env.clone(), market_id.clone()) > 0 {
+            let bond = Self::take_market_bond(&env, &market_id);
+            Self::deposit_to_treasury(&env, bond);
+            env.events().publish(
+                (Symbol::new(&env, "CreatorBondForfeited"),),
+                (market_id, bond),
+            );
+        }
+    }
+
+    /// Admin: Sweep a deployed market's retained claim fees into its fee recipient
+    ///
+    /// Returns the amount swept.
+    pub fn sweep_market_retained_fees(env: Env, admin: Address, market_id: BytesN<32>) -> i128 {
+        admin.require_auth();
+        let stored_admin: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ADMIN_KEY))
+            .expect("Admin not set");
+        if admin != stored_admin {
+            panic!("Unauthorized: only admin can sweep retained fees");
+        }
+
+        let market_address: Address = env
+            .storage()
+            .persistent()
+            .get(&(Symbol::new(&env, "market_addr"), market_id))
+            .expect("Market not deployed");
+        let treasury: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, TREASURY_KEY))
+            .expect("Treasury address not set");
+        crate::market::PredictionMarketClient::new(&env, &market_address)
+            .sweep_retained_fees(&treasury)
     }
 
     /// Admin: Pause market creation (emergency)
@@ -188,6 +1100,52 @@ impl MarketFactory {
         todo!("See get collected fees TODO above")
     }
 
+    /// Admin: Sweep unaccounted USDC held by the factory to the treasury
+    ///
+    /// Creation fees are routed straight to the treasury, so any balance above
+    /// the escrowed creator bonds is dust or a stray transfer. The excess is
+    /// deposited as fees so the treasury's totals include it. Returns the swept amount.
+    pub fn reconcile_fees(env: Env, admin: Address) -> i128 {
+        // Require admin authentication
+        admin.require_auth();
+        let stored_admin: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ADMIN_KEY))
+            .expect("Admin not set");
+        if admin != stored_admin {
+            panic!("Unauthorized: only admin can reconcile fees");
+        }
+
+        let usdc: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, USDC_KEY))
+            .expect("USDC not set");
+
+        // Escrowed creator bonds are owed back to creators, not swept
+        let balance = token::Client::new(&env, &usdc).balance(&env.current_contract_address());
+        let escrowed: i128 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ESCROWED_BONDS_KEY))
+            .unwrap_or(0);
+
+        let excess = balance - escrowed;
+        if excess <= 0 {
+            return 0;
+        }
+
+        // Sweep excess into the treasury's fee pools
+        Self::deposit_to_treasury(&env, excess);
+
+        // Emit FeesReconciled event
+        env.events()
+            .publish((Symbol::new(&env, "FeesReconciled"),), (excess,));
+
+        excess
+    }
+
     /// Admin function: Withdraw collected fees to treasury
     pub fn withdraw_fees(env: Env, amount: i128) {
         todo!("See withdraw fees TODO above")
@@ -1,6 +1,9 @@
 // File for resuable helper functions
 
-use soroban_sdk::{token::StellarAssetClient, Address, BytesN, Env, Symbol};
+use soroban_sdk::{
+    token::{StellarAssetClient, TokenClient},
+    Address, BytesN, Env, Symbol,
+};
 // use crate::helpers::*;
 
 const POOL_YES_RESERVE: &str = "pool_yes_reserve";
@@ -8,15 +11,55 @@ const POOL_NO_RESERVE: &str = "pool_no_reserve";
 const POOL_K: &str = "pool_k";
 const POOL_EXISTS: &str = "pool_exists";
 const TRADE_COUNT: &str = "trade_count";
+const POOL_VOLUME: &str = "pool_volume";
+const PRICE_HISTORY: &str = "price_history";
+const PRICE_HISTORY_LEN: &str = "price_history_len";
 const USER_SHARES_YES: &str = "user_shares_yes";
 const USER_SHARES_NO: &str = "user_shares_no";
 
+/// Outcome count of a YES/NO market
+pub const BINARY_OUTCOMES: u32 = 2;
+/// Largest outcome count any contract (factory, market, AMM, oracle) accepts
+pub const MAX_OUTCOMES: u32 = 8;
+/// Decimals assumed when the token does not report them (Stellar USDC)
+pub const DEFAULT_TOKEN_DECIMALS: u32 = 7;
+
+#[cfg(test)]
 pub fn create_test_env() -> Env {
     let env = Env::default();
     env.mock_all_auths();
     env
 }
 
+/// Validate a market's outcome count is within the range all contracts support
+/// Panics if fewer than two outcomes or more than MAX_OUTCOMES
+pub fn validate_outcome_count(num_outcomes: u32) {
+    if !(BINARY_OUTCOMES..=MAX_OUTCOMES).contains(&num_outcomes) {
+        panic!("invalid outcome count");
+    }
+}
+
+/// Check an outcome index is within a market's outcome count
+pub fn is_valid_outcome(outcome: u32, num_outcomes: u32) -> bool {
+    outcome < num_outcomes
+}
+
+/// Read a token's decimals, falling back to DEFAULT_TOKEN_DECIMALS if the
+/// token contract cannot be queried
+pub fn read_token_decimals(env: &Env, token: &Address) -> u32 {
+    match TokenClient::new(env, token).try_decimals() {
+        Ok(Ok(decimals)) => decimals,
+        _ => DEFAULT_TOKEN_DECIMALS,
+    }
+}
+
+/// Convert whole token units into base units for a token with `decimals`
+pub fn whole_units(decimals: u32, units: i128) -> i128 {
+    units
+        .checked_mul(10i128.checked_pow(decimals).expect("decimals too large"))
+        .expect("amount overflow")
+}
+
 /// Get pool reserves for a market
 pub fn get_pool_reserves(env: &Env, market_id: &BytesN<32>) -> (u128, u128) {
     let yes_reserve: u128 = env
@@ -116,6 +159,53 @@ pub fn increment_trade_count(env: &Env, market_id: &BytesN<32>) -> u32 {
     count
 }
 
+/// Get cumulative USDC traded (buy amounts plus net sell payouts) for a market
+pub fn get_pool_volume(env: &Env, market_id: &BytesN<32>) -> u128 {
+    env.storage()
+        .persistent()
+        .get(&(Symbol::new(env, POOL_VOLUME), market_id.clone()))
+        .unwrap_or(0)
+}
+
+/// Add a trade's USDC size to the market's cumulative volume
+pub fn add_pool_volume(env: &Env, market_id: &BytesN<32>, amount: u128) {
+    let volume = get_pool_volume(env, market_id) + amount;
+    env.storage()
+        .persistent()
+        .set(&(Symbol::new(env, POOL_VOLUME), market_id.clone()), &volume);
+}
+
+/// Get the number of price samples recorded for a pool
+pub fn get_price_history_len(env: &Env, market_id: &BytesN<32>) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&(Symbol::new(env, PRICE_HISTORY_LEN), market_id.clone()))
+        .unwrap_or(0)
+}
+
+/// Get the (timestamp, yes_odds_bps) sample at `index`, oldest first
+pub fn get_price_sample(env: &Env, market_id: &BytesN<32>, index: u32) -> Option<(u64, u32)> {
+    env.storage()
+        .persistent()
+        .get(&(Symbol::new(env, PRICE_HISTORY), market_id.clone(), index))
+}
+
+/// Append a YES odds sample at the current ledger time
+///
+/// Each sample has its own entry, so recording costs the same however many
+/// trades came before.
+pub fn record_price_sample(env: &Env, market_id: &BytesN<32>, yes_odds: u32) {
+    let len = get_price_history_len(env, market_id);
+    env.storage().persistent().set(
+        &(Symbol::new(env, PRICE_HISTORY), market_id.clone(), len),
+        &(env.ledger().timestamp(), yes_odds),
+    );
+    env.storage().persistent().set(
+        &(Symbol::new(env, PRICE_HISTORY_LEN), market_id.clone()),
+        &(len + 1),
+    );
+}
+
 /// Calculate shares out using CPMM => x * y = k (constant product)
 /// When buying YES: input goes to NO reserve, output from YES reserve
 /// When buying NO: input goes to YES reserve, output from NO reserve
@@ -165,3 +255,17 @@ pub fn calculate_payout(
         yes_reserve - new_yes_reserve
     }
 }
+
+/// Integer square root, rounded down (Newton's method)
+pub fn integer_sqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+    let mut x = n;
+    let mut y = x.div_ceil(2);
+    while y < x {
+        x = y;
+        y = (x + n / x) / 2;
+    }
+    x
+}
@@ -7,13 +7,16 @@
 // NOTE: Only one contract can be compiled at a time for WASM
 // To build different contracts, comment/uncomment the appropriate module
 
+// Shared helpers (reserves, outcome bounds, token units)
+pub mod helpers;
+
 // AMM CONTRACT (currently active for get_odds implementation)
 mod amm;
 pub use amm::*;
 
 // FACTORY CONTRACT
-// mod factory;
-// pub use factory::*;
+mod factory;
+pub use factory::*;
 
 // MARKET CONTRACT (for prediction market logic)
 mod market;
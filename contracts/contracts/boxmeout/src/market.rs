@@ -2,7 +2,9 @@
 // Handles predictions, bet commitment/reveal, market resolution, and winnings claims
 
 use soroban_sdk::{
-    contract, contracterror, contractimpl, contracttype, token, Address, BytesN, Env, Symbol, Vec,
+    auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation},
+    contract, contracterror, contractimpl, contracttype, token, vec, Address, Bytes, BytesN, Env,
+    IntoVal, Symbol, Vec,
 };
 
 // Storage keys
@@ -23,11 +25,61 @@ const PREDICTION_PREFIX: &str = "prediction";
 const WINNING_OUTCOME_KEY: &str = "winning_outcome";
 const WINNER_SHARES_KEY: &str = "winner_shares";
 const LOSER_SHARES_KEY: &str = "loser_shares";
+const LOSER_REFUND_BPS_KEY: &str = "loser_refund_bps";
+const LOSER_REFUND_RESERVE_KEY: &str = "loser_refund_reserve";
+const NUM_OUTCOMES_KEY: &str = "num_outcomes";
+const TOKEN_DECIMALS_KEY: &str = "token_decimals";
+const MAX_ATTESTATION_AGE_KEY: &str = "max_attestation_age";
+const CONSENSUS_TIMEOUT_KEY: &str = "consensus_timeout";
+const PLURALITY_ON_TIMEOUT_KEY: &str = "plurality_on_timeout";
+const REVEAL_WINDOW_OPEN_KEY: &str = "reveal_window_open";
+const REVEAL_WINDOW_OPENED_AT_KEY: &str = "reveal_window_opened_at";
+const REVEAL_DEADLINE_KEY: &str = "reveal_deadline";
+const PROBABILITY_HISTORY_KEY: &str = "probability_history";
+const PROBABILITY_HISTORY_LEN_KEY: &str = "probability_history_len";
+const MIN_PARTICIPANTS_KEY: &str = "min_participants";
+const PARTICIPANT_COUNT_KEY: &str = "participant_count";
+const TOTAL_PAID_OUT_KEY: &str = "total_paid_out";
+const MAX_BET_VS_POOL_BPS_KEY: &str = "max_bet_vs_pool_bps";
+const FEE_RECIPIENT_KEY: &str = "fee_recipient";
+const CLAIMED_TOTAL_KEY: &str = "claimed_total";
+const CREATOR_FEE_EXEMPT_KEY: &str = "creator_fee_exempt";
+const DISPUTE_KEY: &str = "dispute";
+const DISPUTE_SETTLED_KEY: &str = "dispute_settled";
+const MAX_RESOLUTION_EXTENSION_KEY: &str = "max_resolution_extension";
+const ORIGINAL_RESOLUTION_TIME_KEY: &str = "original_resolution_time";
+const RETAINED_FEES_KEY: &str = "retained_fees";
+const MIN_BET_KEY: &str = "min_bet";
+const MAX_BET_KEY: &str = "max_bet";
+const DISPUTE_WINDOW_KEY: &str = "dispute_window";
+const RESOLVED_AT_KEY: &str = "resolved_at";
+const MAX_BETS_PER_USER_KEY: &str = "max_bets_per_user";
+const USER_BET_COUNT_PREFIX: &str = "user_bet_count";
 
 /// Market states
 const STATE_OPEN: u32 = 0;
 const STATE_CLOSED: u32 = 1;
-const STATE_RESOLVED: u32 = 2;
+pub(crate) const STATE_RESOLVED: u32 = 2;
+pub(crate) const STATE_CANCELLED: u32 = 3;
+pub(crate) const STATE_DISPUTED: u32 = 4;
+
+/// Default window after the market resolves during which the outcome can be disputed (7 days)
+const DEFAULT_DISPUTE_WINDOW_SECONDS: u64 = 7 * 24 * 60 * 60;
+/// Shortest configurable dispute window (1 hour)
+const MIN_DISPUTE_WINDOW_SECONDS: u64 = 60 * 60;
+/// Longest configurable dispute window (30 days)
+const MAX_DISPUTE_WINDOW_SECONDS: u64 = 30 * 24 * 60 * 60;
+/// Default wait after resolution_time before a market without consensus can be force-resolved (7 days)
+const DEFAULT_CONSENSUS_TIMEOUT_SECONDS: u64 = 7 * 24 * 60 * 60;
+/// Default cap on how far oracles may push resolution_time past its original value
+const DEFAULT_MAX_RESOLUTION_EXTENSION_SECONDS: u64 = 7 * 24 * 60 * 60;
+/// Claim period after the dispute window; past it the market is reported as SETTLED (30 days)
+const CLAIM_PERIOD_SECONDS: u64 = 30 * 24 * 60 * 60;
+
+/// Upper bound for the losing-bet consolation refund (20%), reserved from the losing pool
+const MAX_LOSER_REFUND_BPS: u32 = 2000;
+/// Protocol fee taken from gross winnings, in basis points (10%)
+const PROTOCOL_FEE_BPS: u32 = 1000;
 
 /// Error codes following Soroban best practices
 #[contracterror]
@@ -54,6 +106,14 @@ pub enum MarketError {
     NotWinner = 9,
     /// Market not yet resolved
     MarketNotResolved = 10,
+    /// Bet is below the market's minimum
+    BetBelowMinimum = 11,
+    /// Bet is above the market's maximum
+    BetAboveMaximum = 12,
+    /// Revealed outcome, amount and salt do not hash to the commitment
+    InvalidRevelation = 13,
+    /// User has reached the market's per-user bet cap
+    BetLimitReached = 14,
 }
 
 /// Commitment record for commit-reveal scheme
@@ -66,6 +126,79 @@ pub struct Commitment {
     pub timestamp: u64,
 }
 
+/// Optional per-market parameters supplied at initialization
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct MarketConfig {
+    /// Consolation refund for losing bets in basis points (0 = refunds disabled)
+    pub loser_refund_bps: u32,
+    /// Number of possible outcomes (0 = binary YES/NO; only 2 until per-outcome pools exist)
+    pub num_outcomes: u32,
+    /// Oldest a consensus attestation may be, in seconds before resolution_time (0 = no limit)
+    pub max_attestation_age: u64,
+    /// Wait after resolution_time before force-resolving without consensus (0 = 7 days)
+    pub consensus_timeout: u64,
+    /// On timeout, resolve on a plurality of oracle votes instead of cancelling
+    pub plurality_on_timeout: bool,
+    /// Revealed predictions required to resolve; fewer cancels the market (0 = no minimum)
+    pub min_participants: u32,
+    /// Largest single reveal as a share of the pool revealed so far, in basis points (0 = no cap)
+    pub max_bet_vs_pool_bps: u32,
+    /// Treasury receiving the protocol fee on claims (None = the factory's treasury)
+    pub fee_recipient: Option<Address>,
+    /// Waive the protocol fee on the creator's own winnings
+    pub creator_fee_exempt: bool,
+    /// Furthest oracles may push resolution_time past its original value (0 = 7 days)
+    pub max_resolution_extension: u64,
+    /// Smallest amount a single commitment may stake (0 = no minimum)
+    pub min_bet: i128,
+    /// Largest amount a single commitment may stake (0 = no maximum)
+    pub max_bet: i128,
+    /// Time after the market resolves during which the outcome can be disputed (0 = 7 days)
+    pub dispute_window_seconds: u64,
+    /// Most commitments a single user may make (0 = no cap)
+    pub max_bets_per_user: u32,
+}
+
+/// Market summary with countdowns relative to the current ledger time
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MarketState {
+    pub market_id: BytesN<32>,
+    pub creator: Address,
+    pub state: u32,
+    pub closing_time: u64,
+    pub resolution_time: u64,
+    /// Seconds until closing_time (negative once elapsed)
+    pub time_remaining_to_close: i64,
+    /// Seconds until resolution_time (negative once elapsed)
+    pub time_remaining_to_resolution: i64,
+    pub yes_pool: i128,
+    pub no_pool: i128,
+    pub total_volume: i128,
+    pub winning_outcome: Option<u32>,
+}
+
+/// Market state with one user's position overlaid
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MarketStateForUser {
+    pub state: u32,
+    pub closing_time: u64,
+    pub resolution_time: u64,
+    pub yes_pool: i128,
+    pub no_pool: i128,
+    pub total_volume: i128,
+    pub winning_outcome: Option<u32>,
+    /// Whether the user has a prediction; the fields below are zero if not
+    pub has_prediction: bool,
+    pub outcome: u32,
+    pub amount: i128,
+    pub claimed: bool,
+    /// Potential payout before resolution; actual payout or refund after
+    pub payout: i128,
+}
+
 /// Revealed prediction record
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -77,6 +210,31 @@ pub struct UserPrediction {
     pub timestamp: u64,
 }
 
+/// A user's revealed prediction with its payout outlook
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UserPredictionView {
+    pub user: Address,
+    pub outcome: u32,
+    pub amount: i128,
+    pub claimed: bool,
+    pub timestamp: u64,
+    /// Net payout if this outcome wins at current pool sizes; actual net
+    /// winnings once resolved (0 for a losing prediction)
+    pub potential_payout: i128,
+    /// True only once resolved in the user's favour
+    pub is_winner: bool,
+}
+
+/// Open dispute against a market's resolution
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DisputeRecord {
+    pub user: Address,
+    pub reason: Symbol,
+    pub timestamp: u64,
+}
+
 /// PREDICTION MARKET - Manages individual market logic
 #[contract]
 pub struct PredictionMarket;
@@ -93,10 +251,28 @@ impl PredictionMarket {
         oracle: Address,
         closing_time: u64,
         resolution_time: u64,
+        config: MarketConfig,
     ) {
         // Verify creator signature
         creator.require_auth();
 
+        // Validate loser refund stays within the bound reserved from the losing pool
+        if config.loser_refund_bps > MAX_LOSER_REFUND_BPS {
+            panic!("Loser refund exceeds maximum");
+        }
+
+        // Validate outcome count against the range every contract supports
+        let num_outcomes = if config.num_outcomes == 0 {
+            crate::helpers::BINARY_OUTCOMES
+        } else {
+            config.num_outcomes
+        };
+        crate::helpers::validate_outcome_count(num_outcomes);
+        // Pools are YES/NO only; wider markets need per-outcome pool storage first
+        if num_outcomes != crate::helpers::BINARY_OUTCOMES {
+            panic!("multi-outcome markets not supported");
+        }
+
         // Store market_id reference
         env.storage()
             .persistent()
@@ -116,6 +292,11 @@ impl PredictionMarket {
             .persistent()
             .set(&Symbol::new(&env, USDC_KEY), &usdc_token);
 
+        env.storage().persistent().set(
+            &Symbol::new(&env, TOKEN_DECIMALS_KEY),
+            &crate::helpers::read_token_decimals(&env, &usdc_token),
+        );
+
         // Store oracle address
         env.storage()
             .persistent()
@@ -153,6 +334,88 @@ impl PredictionMarket {
             .persistent()
             .set(&Symbol::new(&env, PENDING_COUNT_KEY), &0u32);
 
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, NUM_OUTCOMES_KEY), &num_outcomes);
+
+        env.storage().persistent().set(
+            &Symbol::new(&env, MAX_ATTESTATION_AGE_KEY),
+            &config.max_attestation_age,
+        );
+
+        let consensus_timeout = if config.consensus_timeout == 0 {
+            DEFAULT_CONSENSUS_TIMEOUT_SECONDS
+        } else {
+            config.consensus_timeout
+        };
+        env.storage().persistent().set(
+            &Symbol::new(&env, CONSENSUS_TIMEOUT_KEY),
+            &consensus_timeout,
+        );
+        env.storage().persistent().set(
+            &Symbol::new(&env, PLURALITY_ON_TIMEOUT_KEY),
+            &config.plurality_on_timeout,
+        );
+        env.storage().persistent().set(
+            &Symbol::new(&env, MIN_PARTICIPANTS_KEY),
+            &config.min_participants,
+        );
+        env.storage().persistent().set(
+            &Symbol::new(&env, MAX_BET_VS_POOL_BPS_KEY),
+            &config.max_bet_vs_pool_bps,
+        );
+        env.storage().persistent().set(
+            &Symbol::new(&env, CREATOR_FEE_EXEMPT_KEY),
+            &config.creator_fee_exempt,
+        );
+        if config.min_bet < 0 || config.max_bet < 0 {
+            panic!("bet limits must not be negative");
+        }
+        if config.max_bet > 0 && config.min_bet > config.max_bet {
+            panic!("min bet exceeds max bet");
+        }
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, MIN_BET_KEY), &config.min_bet);
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, MAX_BET_KEY), &config.max_bet);
+        env.storage().persistent().set(
+            &Symbol::new(&env, MAX_BETS_PER_USER_KEY),
+            &config.max_bets_per_user,
+        );
+        let dispute_window = if config.dispute_window_seconds == 0 {
+            DEFAULT_DISPUTE_WINDOW_SECONDS
+        } else {
+            config.dispute_window_seconds
+        };
+        if !(MIN_DISPUTE_WINDOW_SECONDS..=MAX_DISPUTE_WINDOW_SECONDS).contains(&dispute_window) {
+            panic!("dispute window out of range");
+        }
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, DISPUTE_WINDOW_KEY), &dispute_window);
+        let max_resolution_extension = if config.max_resolution_extension == 0 {
+            DEFAULT_MAX_RESOLUTION_EXTENSION_SECONDS
+        } else {
+            config.max_resolution_extension
+        };
+        env.storage().persistent().set(
+            &Symbol::new(&env, MAX_RESOLUTION_EXTENSION_KEY),
+            &max_resolution_extension,
+        );
+        if let Some(fee_recipient) = config.fee_recipient {
+            env.storage()
+                .persistent()
+                .set(&Symbol::new(&env, FEE_RECIPIENT_KEY), &fee_recipient);
+        }
+
+        // Store loser refund percentage
+        env.storage().persistent().set(
+            &Symbol::new(&env, LOSER_REFUND_BPS_KEY),
+            &config.loser_refund_bps,
+        );
+
         // Emit initialization event
         env.events().publish(
             (Symbol::new(&env, "market_initialized"),),
@@ -216,9 +479,28 @@ impl PredictionMarket {
             return Err(MarketError::InvalidAmount);
         }
 
-        // Check for duplicate commit per user
+        // Enforce the market's per-bet limits
+        let (min_bet, max_bet) = Self::get_bet_limits(env.clone());
+        if amount < min_bet {
+            return Err(MarketError::BetBelowMinimum);
+        }
+        if amount > max_bet {
+            return Err(MarketError::BetAboveMaximum);
+        }
+
+        // Enforce the market's per-user bet cap
+        let max_bets_per_user = Self::get_max_bets_per_user(env.clone());
+        let bet_count = Self::get_user_bet_count(env.clone(), user.clone());
+        if max_bets_per_user > 0 && bet_count >= max_bets_per_user {
+            return Err(MarketError::BetLimitReached);
+        }
+
+        // Check for duplicate commit per user (pending or already revealed)
         let commit_key = Self::get_commit_key(&env, &user);
-        if env.storage().persistent().has(&commit_key) {
+        let prediction_key = (Symbol::new(&env, PREDICTION_PREFIX), user.clone());
+        if env.storage().persistent().has(&commit_key)
+            || env.storage().persistent().has(&prediction_key)
+        {
             return Err(MarketError::DuplicateCommit);
         }
 
@@ -240,17 +522,39 @@ impl PredictionMarket {
         let contract_address = env.current_contract_address();
 
         // Transfer tokens - will panic if insufficient balance or approval
+        let balance_before = token_client.balance(&contract_address);
         token_client.transfer(&user, &contract_address, &amount);
 
+        // Escrow what actually arrived; fee-on-transfer tokens deliver short
+        let received = token_client.balance(&contract_address) - balance_before;
+        if received < amount {
+            return Err(MarketError::TransferFailed);
+        }
+
+        // Update market total volume
+        let total_volume: i128 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, TOTAL_VOLUME_KEY))
+            .unwrap_or(0);
+        env.storage().persistent().set(
+            &Symbol::new(&env, TOTAL_VOLUME_KEY),
+            &(total_volume + received),
+        );
+
         // Create and store commitment record
         let commitment = Commitment {
             user: user.clone(),
             commit_hash: commit_hash.clone(),
-            amount,
+            amount: received,
             timestamp: current_time,
         };
 
         env.storage().persistent().set(&commit_key, &commitment);
+        env.storage().persistent().set(
+            &(Symbol::new(&env, USER_BET_COUNT_PREFIX), user.clone()),
+            &(bet_count + 1),
+        );
 
         // Update pending count
         let pending_count: u32 = env
@@ -263,675 +567,3229 @@ impl PredictionMarket {
             .persistent()
             .set(&Symbol::new(&env, PENDING_COUNT_KEY), &(pending_count + 1));
 
+        // Index the user's bet in the factory; best effort, like resolution reports
+        let factory: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, FACTORY_KEY))
+            .ok_or(MarketError::NotInitialized)?;
+        let factory_client = crate::factory::MarketFactoryClient::new(&env, &factory);
+        let _ = factory_client.try_report_user_bet(&market_id, &user);
+        let _ = factory_client.try_report_market_volume(&market_id, &(total_volume + received));
+
         // Emit CommitmentMade event
         env.events().publish(
             (Symbol::new(&env, "CommitmentMade"),),
-            (user, market_id, amount),
+            (user, market_id, received),
         );
 
         Ok(())
     }
 
-    /// Helper: Generate storage key for user commitment
-    fn get_commit_key(env: &Env, user: &Address) -> (Symbol, Address) {
-        (Symbol::new(env, COMMIT_PREFIX), user.clone())
+    /// Helper: Require the stored factory's authorization for factory-only operations
+    fn require_factory(env: &Env) -> Address {
+        let factory: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, FACTORY_KEY))
+            .expect("Factory not set");
+        factory.require_auth();
+        factory
     }
 
-    /// Helper: Get user commitment (for testing and reveal phase)
-    pub fn get_commitment(env: Env, user: Address) -> Option<Commitment> {
-        let commit_key = Self::get_commit_key(&env, &user);
-        env.storage().persistent().get(&commit_key)
+    /// Helper: Store a state transition and mirror it in the factory's cache
+    ///
+    /// The factory report is best effort; the market remains the source of truth.
+    fn set_market_state(env: &Env, state: u32) {
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(env, MARKET_STATE_KEY), &state);
+
+        let market_id: BytesN<32> = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, MARKET_ID_KEY))
+            .expect("Market not initialized");
+        let factory: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, FACTORY_KEY))
+            .expect("Factory not set");
+        let factory_client = crate::factory::MarketFactoryClient::new(env, &factory);
+        let _ = factory_client.try_update_market_state(&market_id, &state);
     }
 
-    /// Helper: Get pending commit count
-    pub fn get_pending_count(env: Env) -> u32 {
+    /// Get decimals of the market's USDC token
+    pub fn get_token_decimals(env: Env) -> u32 {
         env.storage()
             .persistent()
-            .get(&Symbol::new(&env, PENDING_COUNT_KEY))
-            .unwrap_or(0)
+            .get(&Symbol::new(&env, TOKEN_DECIMALS_KEY))
+            .unwrap_or(crate::helpers::DEFAULT_TOKEN_DECIMALS)
     }
 
-    /// Helper: Get market state
-    pub fn get_market_state_value(env: Env) -> Option<u32> {
+    /// Get factory address that deployed this market
+    pub fn get_factory(env: Env) -> Address {
         env.storage()
             .persistent()
-            .get(&Symbol::new(&env, MARKET_STATE_KEY))
+            .get(&Symbol::new(&env, FACTORY_KEY))
+            .expect("Factory not set")
     }
 
-    /// Phase 2: User reveals their committed prediction
-    ///
-    /// TODO: Reveal Prediction
-    /// - Require user authentication
-    /// - Validate market state still OPEN (revelation period)
-    /// - Validate user has prior commit record for this market
-    /// - Reconstruct commit hash from: outcome + amount + salt provided
-    /// - Compare reconstructed hash with stored commit hash
-    /// - If hashes don't match: reject with "Invalid revelation"
-    /// - Lock in prediction: outcome and amount
-    /// - Mark commit as revealed
-    /// - Update prediction pool: if outcome==YES: yes_pool+=amount, else: no_pool+=amount
-    /// - Calculate odds: yes_odds = yes_pool / (yes_pool + no_pool)
-    /// - Store prediction record in user_predictions map
-    /// - Remove from pending_commits
-    /// - Emit PredictionRevealed(user, market_id, outcome, amount, timestamp)
-    /// - Update market total_volume += amount
-    pub fn reveal_prediction(
-        env: Env,
-        user: Address,
-        market_id: BytesN<32>,
-        outcome: u32,
-        amount: i128,
-        salt: BytesN<32>,
-    ) {
-        todo!("See reveal prediction TODO above")
+    /// Get the address that created this market
+    pub fn get_creator(env: Env) -> Address {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, CREATOR_KEY))
+            .expect("Creator not found")
     }
 
-    /// Close market for new predictions (auto-trigger at closing_time)
-    pub fn close_market(env: Env, market_id: BytesN<32>) {
-        // Get current timestamp
-        let current_time = env.ledger().timestamp();
-
-        // Load closing time
-        let closing_time: u64 = env
-            .storage()
+    /// Get oracle address this market resolves against
+    pub fn get_oracle(env: Env) -> Address {
+        env.storage()
             .persistent()
-            .get(&Symbol::new(&env, CLOSING_TIME_KEY))
-            .expect("Closing time not found");
+            .get(&Symbol::new(&env, ORACLE_KEY))
+            .expect("Oracle address not found")
+    }
 
-        // Validate current timestamp >= closing_time
-        if current_time < closing_time {
-            panic!("Cannot close market before closing time");
-        }
+    /// Factory: Replace the oracle this market resolves against
+    ///
+    /// Only allowed before the market is resolved.
+    pub fn set_oracle(env: Env, oracle: Address) {
+        Self::require_factory(&env);
 
-        // Load current state
-        let current_state: u32 = env
+        let state: u32 = env
             .storage()
             .persistent()
             .get(&Symbol::new(&env, MARKET_STATE_KEY))
-            .expect("Market state not found");
-
-        // Validate market state is OPEN
-        if current_state != STATE_OPEN {
-            panic!("Market not in OPEN state");
+            .expect("Market not initialized");
+        if state == STATE_RESOLVED {
+            panic!("Market already resolved");
         }
 
-        // Change market state to CLOSED
         env.storage()
             .persistent()
-            .set(&Symbol::new(&env, MARKET_STATE_KEY), &STATE_CLOSED);
+            .set(&Symbol::new(&env, ORACLE_KEY), &oracle);
 
-        // Emit MarketClosed Event
         env.events().publish(
-            (Symbol::new(&env, "market_closed"),),
-            (market_id, current_time),
+            (Symbol::new(&env, "OracleUpdated"),),
+            (oracle, env.ledger().timestamp()),
         );
     }
 
-    /// Resolve market based on oracle consensus result
-    ///
-    /// This function finalizes the market outcome based on oracle consensus.
-    /// It validates timing, checks oracle consensus, updates market state,
-    /// calculates winner/loser pools, and emits resolution event.
-    ///
-    /// # Panics
-    /// * If current time < resolution_time
-    /// * If market state is not CLOSED
-    /// * If oracle consensus has not been reached
-    /// * If market is already RESOLVED
-    pub fn resolve_market(env: Env, market_id: BytesN<32>) {
-        // Get current timestamp
-        let current_time = env.ledger().timestamp();
+    /// Helper: Generate storage key for user commitment
+    fn get_commit_key(env: &Env, user: &Address) -> (Symbol, Address) {
+        (Symbol::new(env, COMMIT_PREFIX), user.clone())
+    }
 
-        // Load resolution time from storage
-        let resolution_time: u64 = env
-            .storage()
-            .persistent()
-            .get(&Symbol::new(&env, RESOLUTION_TIME_KEY))
-            .expect("Resolution time not found");
+    /// Helper: Get user commitment (for testing and reveal phase)
+    pub fn get_commitment(env: Env, user: Address) -> Option<Commitment> {
+        let commit_key = Self::get_commit_key(&env, &user);
+        env.storage().persistent().get(&commit_key)
+    }
 
-        // Validate: current timestamp >= resolution_time
-        if current_time < resolution_time {
-            panic!("Cannot resolve market before resolution time");
-        }
+    /// Check whether a user has committed, without loading the commitment
+    pub fn has_committed(env: Env, user: Address) -> bool {
+        let commit_key = Self::get_commit_key(&env, &user);
+        env.storage().persistent().has(&commit_key)
+    }
 
-        // Load current market state
-        let current_state: u32 = env
-            .storage()
+    /// Helper: Get pending commit count
+    pub fn get_pending_count(env: Env) -> u32 {
+        env.storage()
             .persistent()
-            .get(&Symbol::new(&env, MARKET_STATE_KEY))
-            .expect("Market state not found");
-
-        // Validate: market state is CLOSED (not OPEN or already RESOLVED)
-        if current_state == STATE_OPEN {
-            panic!("Cannot resolve market that is still OPEN");
-        }
-
-        if current_state == STATE_RESOLVED {
-            panic!("Market already resolved");
-        }
+            .get(&Symbol::new(&env, PENDING_COUNT_KEY))
+            .unwrap_or(0)
+    }
 
-        // Load oracle address
-        let oracle_address: Address = env
+    /// Get revealed (yes_pool, no_pool) sizes
+    pub fn get_pool_sizes(env: Env) -> (i128, i128) {
+        let yes_pool: i128 = env
             .storage()
             .persistent()
-            .get(&Symbol::new(&env, ORACLE_KEY))
-            .expect("Oracle address not found");
-
-        // Create oracle client to check consensus
-        let oracle_client = crate::oracle::OracleManagerClient::new(&env, &oracle_address);
-
-        // Check if oracle consensus has been reached
-        let (consensus_reached, final_outcome) = oracle_client.check_consensus(&market_id);
+            .get(&Symbol::new(&env, YES_POOL_KEY))
+            .unwrap_or(0);
+        let no_pool: i128 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, NO_POOL_KEY))
+            .unwrap_or(0);
+        (yes_pool, no_pool)
+    }
 
-        if !consensus_reached {
-            panic!("Oracle consensus not reached");
-        }
+    /// Get the winning outcome, or None if the market is not resolved
+    pub fn get_winning_outcome(env: Env) -> Option<u32> {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, WINNING_OUTCOME_KEY))
+    }
 
-        // Validate outcome is binary (0 or 1)
-        if final_outcome > 1 {
-            panic!("Invalid oracle outcome");
-        }
+    /// Get total USDC committed to this market
+    pub fn get_total_volume(env: Env) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, TOTAL_VOLUME_KEY))
+            .unwrap_or(0)
+    }
 
-        // Store winning outcome
+    /// Helper: Get market state
+    pub fn get_market_state_value(env: Env) -> Option<u32> {
         env.storage()
             .persistent()
-            .set(&Symbol::new(&env, WINNING_OUTCOME_KEY), &final_outcome);
+            .get(&Symbol::new(&env, MARKET_STATE_KEY))
+    }
 
-        // Load pool sizes
+    /// Get the pooled market's implied (yes_odds, no_odds) in basis points
+    /// Returns (5000, 5000) when nothing has been staked yet
+    pub fn get_odds(env: Env) -> (u32, u32) {
         let yes_pool: i128 = env
             .storage()
             .persistent()
             .get(&Symbol::new(&env, YES_POOL_KEY))
             .unwrap_or(0);
-
         let no_pool: i128 = env
             .storage()
             .persistent()
             .get(&Symbol::new(&env, NO_POOL_KEY))
             .unwrap_or(0);
 
-        // Calculate winner and loser shares
-        let (winner_shares, loser_shares) = if final_outcome == 1 {
-            // YES won
-            (yes_pool, no_pool)
-        } else {
-            // NO won
-            (no_pool, yes_pool)
-        };
+        let total_pool = yes_pool + no_pool;
+        if total_pool == 0 {
+            return (5000, 5000);
+        }
 
-        // Store winner and loser shares for payout calculations
-        env.storage()
-            .persistent()
-            .set(&Symbol::new(&env, WINNER_SHARES_KEY), &winner_shares);
+        let yes_odds = ((yes_pool * 10000) / total_pool) as u32;
+        (yes_odds, 10000 - yes_odds)
+    }
 
-        env.storage()
-            .persistent()
-            .set(&Symbol::new(&env, LOSER_SHARES_KEY), &loser_shares);
+    /// Get (timestamp, yes_pool_bps) samples recorded on each reveal, oldest first
+    pub fn get_probability_history(
+        env: Env,
+        market_id: BytesN<32>,
+        offset: u32,
+        limit: u32,
+    ) -> Vec<(u64, u32)> {
+        let mut page = Vec::new(&env);
+        let end = offset
+            .saturating_add(limit)
+            .min(Self::get_probability_history_len(env.clone(), market_id));
+        for i in offset..end {
+            let sample: (u64, u32) = env
+                .storage()
+                .persistent()
+                .get(&(Symbol::new(&env, PROBABILITY_HISTORY_KEY), i))
+                .expect("Probability sample not found");
+            page.push_back(sample);
+        }
+        page
+    }
 
-        // Update market state to RESOLVED
+    /// Get the number of probability samples recorded so far
+    pub fn get_probability_history_len(env: Env, _market_id: BytesN<32>) -> u32 {
         env.storage()
             .persistent()
-            .set(&Symbol::new(&env, MARKET_STATE_KEY), &STATE_RESOLVED);
-
-        // Emit MarketResolved event
-        env.events().publish(
-            (Symbol::new(&env, "MarketResolved"),),
-            (market_id, final_outcome, current_time),
-        );
+            .get(&Symbol::new(&env, PROBABILITY_HISTORY_LEN_KEY))
+            .unwrap_or(0)
     }
 
-    /// Dispute market resolution within 7-day window
+    /// Get the signed divergence between an AMM pool's YES odds and this
+    /// market's pooled YES odds, in basis points
     ///
-    /// TODO: Dispute Market
-    /// - Require user authentication and user participated in market
-    /// - Validate market state is RESOLVED
-    /// - Validate current timestamp < resolution_time + 7 days
-    /// - Store dispute record: { user, reason, timestamp }
-    /// - Change market state to DISPUTED
-    /// - Freeze all payouts until dispute resolved
-    /// - Increment dispute counter
-    /// - Emit MarketDisputed(user, reason, market_id, timestamp)
-    /// - Notify admin of dispute
-    pub fn dispute_market(env: Env, user: Address, market_id: BytesN<32>, dispute_reason: Symbol) {
-        todo!("See dispute market TODO above")
+    /// Positive means the AMM prices YES higher than the pooled market.
+    pub fn get_odds_divergence(env: Env, market_id: BytesN<32>, amm_address: Address) -> i32 {
+        let amm_client = crate::amm::AMMClient::new(&env, &amm_address);
+        let (amm_yes_odds, _) = amm_client.get_odds(&market_id);
+        let (market_yes_odds, _) = Self::get_odds(env);
+
+        amm_yes_odds as i32 - market_yes_odds as i32
     }
 
-    /// Claim winnings after market resolution
+    /// Get the user-facing market phase at timestamp `now`
     ///
-    /// This function allows users to claim their winnings after a market has been resolved.
+    /// Combines the stored state with closing/resolution timing into one of:
+    /// BETTING, REVEAL, AWAITING_RESOLUTION, DISPUTE_WINDOW, DISPUTED, CLAIMABLE, SETTLED,
+    /// CANCELLED
+    pub fn get_market_phase(env: Env, now: u64) -> Symbol {
+        let state: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, MARKET_STATE_KEY))
+            .expect("Market not initialized");
+        let closing_time: u64 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, CLOSING_TIME_KEY))
+            .expect("Closing time not found");
+        let resolution_time: u64 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, RESOLUTION_TIME_KEY))
+            .expect("Resolution time not found");
+
+        let phase = match state {
+            STATE_CANCELLED => "CANCELLED",
+            STATE_DISPUTED => "DISPUTED",
+            STATE_RESOLVED => {
+                let dispute_end = Self::dispute_window_end(&env);
+                if now < dispute_end {
+                    "DISPUTE_WINDOW"
+                } else if now < dispute_end + CLAIM_PERIOD_SECONDS {
+                    "CLAIMABLE"
+                } else {
+                    "SETTLED"
+                }
+            }
+            // OPEN or CLOSED: driven purely by timing
+            _ => {
+                if state == STATE_OPEN && now < closing_time {
+                    "BETTING"
+                } else if now < resolution_time {
+                    "REVEAL"
+                } else {
+                    "AWAITING_RESOLUTION"
+                }
+            }
+        };
+
+        Symbol::new(&env, phase)
+    }
+
+    /// Compute the commit hash for a prediction
     ///
-    /// # Requirements
-    /// - Market must be in RESOLVED state
-    /// - User must have a prediction matching the final_outcome
-    /// - User must not have already claimed
+    /// sha256(market_id || outcome || amount || salt), with integers big-endian
+    pub fn compute_commit_hash(
+        env: Env,
+        market_id: BytesN<32>,
+        outcome: u32,
+        amount: i128,
+        salt: BytesN<32>,
+    ) -> BytesN<32> {
+        let mut preimage = Bytes::new(&env);
+        preimage.extend_from_array(&market_id.to_array());
+        preimage.extend_from_array(&outcome.to_be_bytes());
+        preimage.extend_from_array(&amount.to_be_bytes());
+        preimage.extend_from_array(&salt.to_array());
+        env.crypto().sha256(&preimage).into()
+    }
+
+    /// Phase 2: User reveals their committed prediction
     ///
-    /// # Payout Calculation
-    /// - Payout = (user_amount / winner_shares) * total_pool
-    /// - 10% protocol fee is deducted from the gross payout
+    /// The revealed outcome, amount and salt must hash to the stored commitment.
+    /// The amount must also equal the escrowed commitment amount, so a reveal
+    /// can never claim a larger pool share than was actually deposited.
     ///
-    /// # Events
-    /// - Emits WinningsClaimed(user, market_id, amount)
+    /// The salt should be 32 random bytes; a predictable salt lets anyone
+    /// brute-force the committed outcome. An all-zero salt is rejected.
     ///
-    /// # Panics
-    /// * If market is not resolved
-    /// * If user has no prediction
-    /// * If user already claimed
-    /// * If user did not predict winning outcome
-    pub fn claim_winnings(env: Env, user: Address, market_id: BytesN<32>) -> i128 {
+    /// Returns `InvalidRevelation` if the revealed values don't match the commitment.
+    pub fn reveal_prediction(
+        env: Env,
+        user: Address,
+        market_id: BytesN<32>,
+        outcome: u32,
+        amount: i128,
+        salt: BytesN<32>,
+    ) -> Result<(), MarketError> {
         // Require user authentication
         user.require_auth();
 
-        // 1. Validate market state is RESOLVED
+        // Validate market is still taking reveals (not resolved or cancelled)
         let state: u32 = env
             .storage()
             .persistent()
             .get(&Symbol::new(&env, MARKET_STATE_KEY))
             .expect("Market not initialized");
-
-        if state != STATE_RESOLVED {
-            panic!("Market not resolved");
+        if state != STATE_OPEN && state != STATE_CLOSED {
+            panic!("Market not accepting reveals");
         }
 
-        // 2. Get User Prediction
-        let prediction_key = (Symbol::new(&env, PREDICTION_PREFIX), user.clone());
-        let mut prediction: UserPrediction = env
+        // Reveals start once close_market opens the reveal window
+        let reveal_window_open: bool = env
             .storage()
             .persistent()
-            .get(&prediction_key)
-            .expect("No prediction found for user");
+            .get(&Symbol::new(&env, REVEAL_WINDOW_OPEN_KEY))
+            .unwrap_or(false);
+        if !reveal_window_open {
+            panic!("Reveal window not open");
+        }
 
-        // 3. Check if already claimed (idempotent - return early if already claimed)
-        if prediction.claimed {
-            panic!("Winnings already claimed");
+        if env.ledger().timestamp() >= Self::get_reveal_deadline(env.clone()) {
+            panic!("Reveal period ended");
         }
 
-        // 4. Validate outcome matches winning outcome
-        let winning_outcome: u32 = env
+        // Validate outcome
+        let num_outcomes: u32 = env
             .storage()
             .persistent()
-            .get(&Symbol::new(&env, WINNING_OUTCOME_KEY))
-            .expect("Winning outcome not found");
+            .get(&Symbol::new(&env, NUM_OUTCOMES_KEY))
+            .unwrap_or(crate::helpers::BINARY_OUTCOMES);
+        if outcome >= num_outcomes {
+            panic!("Invalid outcome");
+        }
 
-        if prediction.outcome != winning_outcome {
-            panic!("User did not predict winning outcome");
+        // Commit already rejects non-positive amounts; re-check before touching pools
+        if amount <= 0 {
+            panic!("amount must be positive");
         }
 
-        // 5. Calculate Payout
-        // Payout = (UserAmount / WinnerPool) * TotalPool
-        // Apply 10% Protocol Fee
-        let winner_shares: i128 = env
-            .storage()
-            .persistent()
-            .get(&Symbol::new(&env, WINNER_SHARES_KEY))
-            .expect("Winner shares not found");
+        if salt.to_array() == [0u8; 32] {
+            panic!("invalid salt");
+        }
 
-        let loser_shares: i128 = env
+        // Validate user has a prior commit record
+        let commit_key = Self::get_commit_key(&env, &user);
+        let commitment: Commitment = env
             .storage()
             .persistent()
-            .get(&Symbol::new(&env, LOSER_SHARES_KEY))
-            .unwrap_or(0);
+            .get(&commit_key)
+            .expect("No commitment found");
 
-        let total_pool = winner_shares + loser_shares;
+        // Revealed amount must match what was escrowed at commit time
+        if amount != commitment.amount {
+            panic!("amount mismatch");
+        }
 
-        if winner_shares == 0 {
-            panic!("No winners to claim");
+        // Reconstruct and compare the commit hash
+        let expected_hash =
+            Self::compute_commit_hash(env.clone(), market_id.clone(), outcome, amount, salt);
+        if expected_hash != commitment.commit_hash {
+            return Err(MarketError::InvalidRevelation);
         }
 
-        // Calculate gross payout using integer arithmetic
-        // (amount * total_pool) / winner_shares
-        let gross_payout = prediction
-            .amount
-            .checked_mul(total_pool)
-            .expect("Overflow in payout calculation")
-            .checked_div(winner_shares)
-            .expect("Division by zero in payout calculation");
+        // A single late reveal may only add a bounded share of the existing pool
+        let max_bet_vs_pool_bps = Self::get_max_bet_vs_pool_bps(env.clone());
+        if max_bet_vs_pool_bps > 0 {
+            let (yes_pool, no_pool) = Self::get_pool_sizes(env.clone());
+            let total_pool = yes_pool + no_pool;
+            if total_pool > 0 && amount > total_pool * max_bet_vs_pool_bps as i128 / 10000 {
+                panic!("bet exceeds pool share cap");
+            }
+        }
 
-        // 10% Fee
-        let fee = gross_payout / 10;
-        let net_payout = gross_payout - fee;
+        // Lock in prediction
+        let prediction = UserPrediction {
+            user: user.clone(),
+            outcome,
+            amount,
+            claimed: false,
+            timestamp: env.ledger().timestamp(),
+        };
+        let prediction_key = (Symbol::new(&env, PREDICTION_PREFIX), user.clone());
+        env.storage().persistent().set(&prediction_key, &prediction);
 
-        if net_payout == 0 {
-            panic!("Payout amount is zero");
-        }
+        // Update prediction pool
+        let pool_key = if outcome == 1 {
+            Symbol::new(&env, YES_POOL_KEY)
+        } else {
+            Symbol::new(&env, NO_POOL_KEY)
+        };
+        let pool: i128 = env.storage().persistent().get(&pool_key).unwrap_or(0);
+        let new_pool = pool.checked_add(amount).expect("Overflow in pool total");
+        env.storage().persistent().set(&pool_key, &new_pool);
 
-        // 6. Transfer Payout from market escrow to user
+        // Revealed pools must stay backed by the USDC escrowed at commit time
+        let (yes_pool, no_pool) = Self::get_pool_sizes(env.clone());
         let usdc_token: Address = env
             .storage()
             .persistent()
             .get(&Symbol::new(&env, USDC_KEY))
             .expect("USDC token not found");
+        let escrow =
+            token::TokenClient::new(&env, &usdc_token).balance(&env.current_contract_address());
+        if escrow < yes_pool + no_pool {
+            panic!("escrow underflow");
+        }
 
-        let token_client = token::TokenClient::new(&env, &usdc_token);
-        let contract_address = env.current_contract_address();
+        // Sample the implied YES probability for charting, one entry per reveal
+        let history_len_key = Symbol::new(&env, PROBABILITY_HISTORY_LEN_KEY);
+        let history_len: u32 = env
+            .storage()
+            .persistent()
+            .get(&history_len_key)
+            .unwrap_or(0);
+        env.storage().persistent().set(
+            &(Symbol::new(&env, PROBABILITY_HISTORY_KEY), history_len),
+            &(env.ledger().timestamp(), Self::get_odds(env.clone()).0),
+        );
+        env.storage()
+            .persistent()
+            .set(&history_len_key, &(history_len + 1));
 
-        token_client.transfer(&contract_address, &user, &net_payout);
+        // Remove from pending commits
+        env.storage().persistent().remove(&commit_key);
+        let pending_count: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, PENDING_COUNT_KEY))
+            .unwrap_or(0);
+        env.storage().persistent().set(
+            &Symbol::new(&env, PENDING_COUNT_KEY),
+            &pending_count.saturating_sub(1),
+        );
 
-        // 7. Route Fee to Treasury
-        if fee > 0 {
-            let factory_address: Address = env
-                .storage()
-                .persistent()
-                .get(&Symbol::new(&env, FACTORY_KEY))
-                .expect("Factory address not set");
-            
-            let factory_client = crate::factory::MarketFactoryClient::new(&env, &factory_address);
-            let treasury_address = factory_client.get_treasury();
-            
-            let treasury_client = crate::treasury::TreasuryClient::new(&env, &treasury_address);
-            // Market contract is the source of the fee
-            treasury_client.deposit_fees(&contract_address, &fee);
+        // Count revealed participants toward the resolution minimum
+        let participant_count = Self::get_participant_count(env.clone());
+        env.storage().persistent().set(
+            &Symbol::new(&env, PARTICIPANT_COUNT_KEY),
+            &(participant_count + 1),
+        );
+
+        // Emit PredictionRevealed event
+        env.events().publish(
+            (Symbol::new(&env, "PredictionRevealed"),),
+            (user, market_id, outcome, amount, env.ledger().timestamp()),
+        );
+
+        Ok(())
+    }
+
+    /// Close market for new predictions (auto-trigger at closing_time)
+    pub fn close_market(env: Env, market_id: BytesN<32>) {
+        // Get current timestamp
+        let current_time = env.ledger().timestamp();
+
+        // Load closing time
+        let closing_time: u64 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, CLOSING_TIME_KEY))
+            .expect("Closing time not found");
+
+        // Validate current timestamp >= closing_time
+        if current_time < closing_time {
+            panic!("Cannot close market before closing time");
         }
 
-        // 8. Mark as claimed (idempotent - prevents double-claim)
-        prediction.claimed = true;
-        env.storage().persistent().set(&prediction_key, &prediction);
+        // Load current state
+        let current_state: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, MARKET_STATE_KEY))
+            .expect("Market state not found");
 
-        // 9. Emit WinningsClaimed Event
+        // Validate market state is OPEN
+        if current_state != STATE_OPEN {
+            panic!("Market not in OPEN state");
+        }
+
+        // Change market state to CLOSED
+        Self::set_market_state(&env, STATE_CLOSED);
+
+        // Open the reveal window in the same transition
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, REVEAL_WINDOW_OPEN_KEY), &true);
+        env.storage().persistent().set(
+            &Symbol::new(&env, REVEAL_WINDOW_OPENED_AT_KEY),
+            &current_time,
+        );
+
+        // Emit MarketClosed Event
         env.events().publish(
-            (Symbol::new(&env, "WinningsClaimed"),),
-            (user, market_id.clone(), net_payout),
+            (Symbol::new(&env, "market_closed"),),
+            (market_id.clone(), current_time),
         );
 
-        net_payout
+        // Emit RevealWindowOpened event so clients can prompt users to reveal
+        env.events().publish(
+            (Symbol::new(&env, "RevealWindowOpened"),),
+            (market_id, current_time),
+        );
     }
 
-    /// Refund users if their prediction failed (optional opt-in)
-    ///
-    /// TODO: Refund Losing Bet
-    /// - Require user authentication
-    /// - Validate market state is RESOLVED
-    /// - Query user's prediction for this market
-    /// - Validate user's outcome != winning_outcome (they lost)
-    /// - Validate hasn't already been refunded
-    /// - Calculate partial refund (e.g., 5% back to incentivize)
-    /// - Transfer refund from treasury to user
-    /// - Mark as refunded
-    /// - Emit LosingBetRefunded(user, market_id, refund_amount, timestamp)
-    pub fn refund_losing_bet(env: Env, user: Address, market_id: BytesN<32>) -> i128 {
-        todo!("See refund losing bet TODO above")
+    /// Get the number of revealed predictions
+    pub fn get_participant_count(env: Env) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, PARTICIPANT_COUNT_KEY))
+            .unwrap_or(0)
     }
 
-    /// Get market summary data
-    ///
-    /// TODO: Get Market State
-    /// - Query market metadata from storage
-    /// - Return: market_id, creator, category, title, description
-    /// - Include timing: creation_time, closing_time, resolution_time, time_remaining
-    /// - Include current state: OPEN/CLOSED/RESOLVED/DISPUTED
-    /// - Include pools: yes_volume, no_volume, total_volume
-    /// - Include odds: yes_odds, no_odds
-    /// - Include resolution: winning_outcome (if resolved), timestamp
-    /// - Include user-specific data if user provided: their prediction, potential winnings
-    pub fn get_market_state(env: Env, market_id: BytesN<32>) -> Symbol {
-        todo!("See get market state TODO above")
-    }
-
-    /// Get prediction records for a user in this market
-    ///
-    /// TODO: Get User Prediction
-    /// - Query user_predictions map by user + market_id
-    /// - Return prediction data: outcome, amount, committed, revealed, claimed
-    /// - Include: commit timestamp, reveal timestamp, claim timestamp
-    /// - Include potential payout if market is unresolved
-    /// - Handle: user has no prediction (return error)
-    pub fn get_user_prediction(env: Env, user: Address, market_id: BytesN<32>) -> Symbol {
-        todo!("See get user prediction TODO above")
+    /// Get when the reveal window opened, or None while the market is OPEN
+    pub fn get_reveal_window_opened_at(env: Env) -> Option<u64> {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, REVEAL_WINDOW_OPENED_AT_KEY))
     }
 
-    /// Get all predictions in market (for governance/audits)
-    ///
-    /// TODO: Get All Predictions
-    /// - Require admin or oracle role
-    /// - Return list of all user predictions
-    /// - Include: user address, outcome, amount for each
-    /// - Include participation count and total_volume
-    /// - Exclude: user private data (privacy-preserving)
-    pub fn get_all_predictions(env: Env, market_id: BytesN<32>) -> Vec<Symbol> {
-        todo!("See get all predictions TODO above")
+    /// Get the time reveals end: resolution_time unless the window was extended
+    pub fn get_reveal_deadline(env: Env) -> u64 {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, REVEAL_DEADLINE_KEY))
+            .unwrap_or_else(|| {
+                env.storage()
+                    .persistent()
+                    .get(&Symbol::new(&env, RESOLUTION_TIME_KEY))
+                    .expect("Resolution time not found")
+            })
     }
 
-    /// Get market leaderboard (top predictors by winnings)
+    /// Creator/factory: Push out the reveal deadline so stuck commitments can be revealed
     ///
-    /// TODO: Get Market Leaderboard
-    /// - Collect all winners for this market
-    /// - Sort by payout amount descending
-    /// - Limit top 100
-    /// - Return: user address, prediction, payout, accuracy
-    /// - For display on frontend
-    pub fn get_market_leaderboard(env: Env, market_id: BytesN<32>) -> Vec<Symbol> {
-        todo!("See get market leaderboard TODO above")
-    }
+    /// For exceptional cases where the reveal window proved too short. Only
+    /// allowed before the market is resolved or cancelled, and the new deadline
+    /// must be later than the current one.
+    pub fn extend_reveal_window(
+        env: Env,
+        admin: Address,
+        market_id: BytesN<32>,
+        new_deadline: u64,
+    ) {
+        admin.require_auth();
 
-    /// Get total volume and liquidity for market
-    ///
-    /// TODO: Get Market Liquidity
-    /// - Query yes_pool, no_pool, total_volume
-    /// - Calculate current odds for YES and NO
-    /// - Return depth: how much can be bought at current price
-    /// - Include slippage estimates for trades
-    pub fn get_market_liquidity(env: Env, market_id: BytesN<32>) -> i128 {
-        todo!("See get market liquidity TODO above")
-    }
+        let creator: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, CREATOR_KEY))
+            .expect("Market not initialized");
+        let factory: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, FACTORY_KEY))
+            .expect("Factory not set");
+        if admin != creator && admin != factory {
+            panic!("Unauthorized: only creator or factory can extend reveal window");
+        }
 
-    /// Emergency function: Market creator can cancel unresolved market
-    ///
-    /// TODO: Cancel Market (Creator Only)
-    /// - Require market creator authentication
-    /// - Validate market state is OPEN or CLOSED (not resolved)
-    /// - Return all user USDC balances (full refund)
-    /// - Loop through all users with predictions
-    /// - Transfer their full amounts back from escrow
-    /// - Handle any transfer failures (log but continue)
-    /// - Set market state to CANCELLED
-    /// - Emit MarketCancelled(market_id, reason, creator, timestamp)
-    pub fn cancel_market(env: Env, creator: Address, market_id: BytesN<32>) {
-        todo!("See cancel market TODO above")
-    }
+        let state: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, MARKET_STATE_KEY))
+            .expect("Market not initialized");
+        if state == STATE_RESOLVED {
+            panic!("Market already resolved");
+        }
+        if state == STATE_CANCELLED {
+            panic!("Market already cancelled");
+        }
 
-    // --- TEST HELPERS (Not for production use, but exposed for integration tests) ---
-    // In a real production contract, these would be removed or gated behind a feature flag.
+        let current_deadline = Self::get_reveal_deadline(env.clone());
+        if new_deadline <= current_deadline {
+            panic!("New deadline must be after current reveal deadline");
+        }
 
-    /// Test helper: Set a user's prediction directly (bypasses commit/reveal)
-    pub fn test_set_prediction(env: Env, user: Address, outcome: u32, amount: i128) {
-        let prediction = UserPrediction {
-            user: user.clone(),
-            outcome,
-            amount,
-            claimed: false,
-            timestamp: env.ledger().timestamp(),
-        };
-        let key = (Symbol::new(&env, PREDICTION_PREFIX), user);
-        env.storage().persistent().set(&key, &prediction);
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, REVEAL_DEADLINE_KEY), &new_deadline);
+
+        env.events().publish(
+            (Symbol::new(&env, "RevealWindowExtended"),),
+            (market_id, current_deadline, new_deadline),
+        );
     }
 
-    /// Test helper: Setup market resolution state directly
-    pub fn test_setup_resolution(
+    /// Oracle: Push out resolution_time while evidence for the outcome is pending
+    ///
+    /// Callable by any oracle active in the market's oracle contract, before the
+    /// market is resolved. The new time must be later than the current one and
+    /// at most `max_resolution_extension` past the original resolution_time.
+    pub fn request_resolution_extension(
         env: Env,
-        _market_id: BytesN<32>,
-        outcome: u32,
-        winner_shares: i128,
-        loser_shares: i128,
+        oracle: Address,
+        market_id: BytesN<32>,
+        new_resolution_time: u64,
     ) {
-        env.storage()
+        oracle.require_auth();
+
+        let oracle_address: Address = env
+            .storage()
             .persistent()
-            .set(&Symbol::new(&env, MARKET_STATE_KEY), &STATE_RESOLVED);
-        env.storage()
+            .get(&Symbol::new(&env, ORACLE_KEY))
+            .expect("Oracle address not found");
+        let oracle_client = crate::oracle::OracleManagerClient::new(&env, &oracle_address);
+        if !oracle_client.get_active_oracles().contains(&oracle) {
+            panic!("Unauthorized: only a registered oracle can extend resolution");
+        }
+
+        let state: u32 = env
+            .storage()
             .persistent()
-            .set(&Symbol::new(&env, WINNING_OUTCOME_KEY), &outcome);
-        env.storage()
+            .get(&Symbol::new(&env, MARKET_STATE_KEY))
+            .expect("Market not initialized");
+        if state == STATE_RESOLVED || state == STATE_DISPUTED {
+            panic!("Market already resolved");
+        }
+        if state == STATE_CANCELLED {
+            panic!("Market already cancelled");
+        }
+
+        let resolution_time: u64 = env
+            .storage()
             .persistent()
-            .set(&Symbol::new(&env, WINNER_SHARES_KEY), &winner_shares);
+            .get(&Symbol::new(&env, RESOLUTION_TIME_KEY))
+            .expect("Resolution time not found");
+        if new_resolution_time <= resolution_time {
+            panic!("New resolution time must be after current resolution time");
+        }
+
+        let original_key = Symbol::new(&env, ORIGINAL_RESOLUTION_TIME_KEY);
+        let original_resolution_time: u64 = env
+            .storage()
+            .persistent()
+            .get(&original_key)
+            .unwrap_or(resolution_time);
+        let max_extension: u64 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, MAX_RESOLUTION_EXTENSION_KEY))
+            .unwrap_or(DEFAULT_MAX_RESOLUTION_EXTENSION_SECONDS);
+        if new_resolution_time - original_resolution_time > max_extension {
+            panic!("Extension exceeds maximum");
+        }
+
         env.storage()
             .persistent()
-            .set(&Symbol::new(&env, LOSER_SHARES_KEY), &loser_shares);
-    }
+            .set(&original_key, &original_resolution_time);
+        env.storage().persistent().set(
+            &Symbol::new(&env, RESOLUTION_TIME_KEY),
+            &new_resolution_time,
+        );
+        // Keep the oracle's discovery list in step with the new time
+        let _ = oracle_client.try_update_resolution_time(
+            &env.current_contract_address(),
+            &market_id,
+            &new_resolution_time,
+        );
 
-    /// Test helper: Get user's prediction
-    pub fn test_get_prediction(env: Env, user: Address) -> Option<UserPrediction> {
-        let key = (Symbol::new(&env, PREDICTION_PREFIX), user);
-        env.storage().persistent().get(&key)
+        env.events().publish(
+            (Symbol::new(&env, "ResolutionExtended"),),
+            (market_id, oracle, resolution_time, new_resolution_time),
+        );
     }
 
-    /// Test helper: Get winning outcome
-    pub fn test_get_winning_outcome(env: Env) -> Option<u32> {
-        env.storage()
+    /// Resolve market based on oracle consensus result
+    ///
+    /// This function finalizes the market outcome based on oracle consensus.
+    /// It validates timing, checks oracle consensus, updates market state,
+    /// calculates winner/loser pools, and emits resolution event.
+    ///
+    /// # Panics
+    /// * If current time < resolution_time
+    /// * If market state is not CLOSED
+    /// * If oracle consensus has not been reached
+    /// * If market is already RESOLVED
+    pub fn resolve_market(env: Env, market_id: BytesN<32>) {
+        // Get current timestamp
+        let current_time = env.ledger().timestamp();
+
+        // Load resolution time from storage
+        let resolution_time: u64 = env
+            .storage()
             .persistent()
-            .get(&Symbol::new(&env, WINNING_OUTCOME_KEY))
-    }
-}
+            .get(&Symbol::new(&env, RESOLUTION_TIME_KEY))
+            .expect("Resolution time not found");
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use soroban_sdk::{
-        testutils::{Address as _, Ledger},
-        Address, BytesN, Env,
-    };
+        // Validate: current timestamp >= resolution_time
+        if current_time < resolution_time {
+            panic!("Cannot resolve market before resolution time");
+        }
 
-    // Mock Oracle for testing
-    #[contract]
-    pub struct MockOracle;
+        // An extended reveal window holds off resolution until it ends
+        if current_time < Self::get_reveal_deadline(env.clone()) {
+            panic!("Reveal window still open");
+        }
 
-    #[contractimpl]
-    impl MockOracle {
-        pub fn initialize(_env: Env) {}
+        // Load current market state
+        let current_state: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, MARKET_STATE_KEY))
+            .expect("Market state not found");
 
-        pub fn check_consensus(env: Env, _market_id: BytesN<32>) -> (bool, u32) {
-            let reached = env
-                .storage()
-                .instance()
-                .get(&Symbol::new(&env, "consensus"))
-                .unwrap_or(true);
-            let outcome = env
-                .storage()
-                .instance()
-                .get(&Symbol::new(&env, "outcome"))
-                .unwrap_or(1u32);
-            (reached, outcome)
+        // Validate: market state is CLOSED (not OPEN or already RESOLVED)
+        if current_state == STATE_OPEN {
+            panic!("Cannot resolve market that is still OPEN");
         }
 
-        pub fn get_consensus_result(env: Env, _market_id: BytesN<32>) -> u32 {
-            env.storage()
-                .instance()
-                .get(&Symbol::new(&env, "outcome"))
-                .unwrap_or(1u32)
+        if current_state == STATE_RESOLVED {
+            panic!("Market already resolved");
         }
 
-        // Test helpers to configure the mock
-        pub fn set_consensus_status(env: Env, reachable: bool) {
-            env.storage()
-                .instance()
-                .set(&Symbol::new(&env, "consensus"), &reachable);
+        if current_state == STATE_CANCELLED {
+            panic!("Market already cancelled");
         }
 
-        pub fn set_outcome_value(env: Env, outcome: u32) {
-            env.storage()
-                .instance()
-                .set(&Symbol::new(&env, "outcome"), &outcome);
+        if current_state == STATE_DISPUTED {
+            panic!("Market under dispute");
+        }
+
+        // Load oracle address
+        let oracle_address: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ORACLE_KEY))
+            .expect("Oracle address not found");
+
+        // Create oracle client to check consensus
+        let oracle_client = crate::oracle::OracleManagerClient::new(&env, &oracle_address);
+
+        // A misconfigured address or uninitialized oracle would otherwise fail cryptically
+        let oracle_ready = matches!(
+            oracle_client.try_get_required_consensus(),
+            Ok(Ok(required_consensus)) if required_consensus > 0
+        );
+        if !oracle_ready {
+            panic!("oracle not reachable");
+        }
+
+        // Check if oracle consensus has been reached
+        let (consensus_reached, final_outcome) = oracle_client.check_consensus(&market_id);
+
+        if !consensus_reached {
+            panic!("Oracle consensus not reached");
+        }
+
+        // Reject consensus formed from attestations made too long before resolution
+        let max_attestation_age: u64 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, MAX_ATTESTATION_AGE_KEY))
+            .unwrap_or(0);
+        if max_attestation_age > 0 {
+            let oldest_attestation = oracle_client.get_consensus_attestation_time(&market_id);
+            if resolution_time.saturating_sub(oldest_attestation) > max_attestation_age {
+                panic!("stale attestations");
+            }
         }
+
+        // Validate outcome is within the market's outcome count
+        let num_outcomes: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, NUM_OUTCOMES_KEY))
+            .unwrap_or(crate::helpers::BINARY_OUTCOMES);
+        if final_outcome >= num_outcomes {
+            panic!("invalid oracle outcome");
+        }
+
+        Self::finalize_resolution(&env, market_id, final_outcome);
     }
 
-    // Helper to create token contract for tests
-    fn create_token_contract<'a>(env: &Env, admin: &Address) -> token::StellarAssetClient<'a> {
-        let token_address = env
-            .register_stellar_asset_contract_v2(admin.clone())
-            .address();
-        token::StellarAssetClient::new(env, &token_address)
+    /// Resolve or cancel a market whose oracles never reached consensus
+    ///
+    /// Callable by anyone once `now > resolution_time + consensus_timeout`. With
+    /// `plurality_on_timeout` set and a strict plurality among the votes cast,
+    /// the market resolves on that outcome; otherwise it is cancelled and every
+    /// participant can reclaim their full stake via `claim_refund`.
+    pub fn force_resolve_after_timeout(env: Env, market_id: BytesN<32>) {
+        let current_state: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, MARKET_STATE_KEY))
+            .expect("Market state not found");
+        if current_state == STATE_RESOLVED {
+            panic!("Market already resolved");
+        }
+        if current_state == STATE_CANCELLED {
+            panic!("Market already cancelled");
+        }
+        if current_state == STATE_DISPUTED {
+            panic!("Market under dispute");
+        }
+
+        let resolution_time: u64 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, RESOLUTION_TIME_KEY))
+            .expect("Resolution time not found");
+        let consensus_timeout: u64 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, CONSENSUS_TIMEOUT_KEY))
+            .unwrap_or(DEFAULT_CONSENSUS_TIMEOUT_SECONDS);
+        if env.ledger().timestamp() <= resolution_time + consensus_timeout {
+            panic!("Consensus timeout not reached");
+        }
+
+        let oracle_address: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ORACLE_KEY))
+            .expect("Oracle address not found");
+        let oracle_client = crate::oracle::OracleManagerClient::new(&env, &oracle_address);
+
+        // Oracles may have reached consensus in the meantime
+        let (consensus_reached, final_outcome) = oracle_client.check_consensus(&market_id);
+        if consensus_reached {
+            Self::finalize_resolution(&env, market_id, final_outcome);
+            return;
+        }
+
+        let plurality_on_timeout: bool = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, PLURALITY_ON_TIMEOUT_KEY))
+            .unwrap_or(false);
+        if plurality_on_timeout {
+            let (yes_votes, no_votes) = oracle_client.get_vote_counts(&market_id);
+            if yes_votes != no_votes {
+                let outcome = if yes_votes > no_votes { 1 } else { 0 };
+                Self::finalize_resolution(&env, market_id, outcome);
+                return;
+            }
+        }
+
+        Self::cancel_with_reason(&env, market_id, "consensus_timeout");
+    }
+
+    /// Helper: Mark the market CANCELLED so every stake can be refunded
+    fn cancel_with_reason(env: &Env, market_id: BytesN<32>, reason: &str) {
+        Self::set_market_state(env, STATE_CANCELLED);
+
+        // Emit MarketCancelled event
+        env.events().publish(
+            (Symbol::new(env, "MarketCancelled"),),
+            (
+                market_id,
+                Symbol::new(env, reason),
+                env.ledger().timestamp(),
+            ),
+        );
+    }
+
+    /// Reclaim the full stake from a cancelled market
+    ///
+    /// Covers both revealed predictions and commitments never revealed.
+    pub fn claim_refund(env: Env, user: Address, market_id: BytesN<32>) -> i128 {
+        user.require_auth();
+
+        let state: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, MARKET_STATE_KEY))
+            .expect("Market not initialized");
+        if state != STATE_CANCELLED {
+            panic!("Market not cancelled");
+        }
+
+        let prediction_key = (Symbol::new(&env, PREDICTION_PREFIX), user.clone());
+        let commit_key = Self::get_commit_key(&env, &user);
+        let refund_amount = if let Some(mut prediction) = env
+            .storage()
+            .persistent()
+            .get::<_, UserPrediction>(&prediction_key)
+        {
+            if prediction.claimed {
+                panic!("Refund already claimed");
+            }
+            prediction.claimed = true;
+            env.storage().persistent().set(&prediction_key, &prediction);
+            prediction.amount
+        } else if let Some(commitment) =
+            env.storage().persistent().get::<_, Commitment>(&commit_key)
+        {
+            env.storage().persistent().remove(&commit_key);
+            commitment.amount
+        } else {
+            panic!("Nothing to refund");
+        };
+
+        let usdc_token: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, USDC_KEY))
+            .expect("USDC token not found");
+        let token_client = token::TokenClient::new(&env, &usdc_token);
+        token_client.transfer(&env.current_contract_address(), &user, &refund_amount);
+        Self::record_payout(&env, refund_amount);
+
+        // Emit RefundClaimed event
+        env.events().publish(
+            (Symbol::new(&env, "RefundClaimed"),),
+            (user, market_id, refund_amount),
+        );
+
+        refund_amount
+    }
+
+    /// Helper: Record the winning outcome, split pools and mark the market RESOLVED
+    ///
+    /// A market with no revealed stake on the winning side has nobody to pay
+    /// out, so it is cancelled and all stakes become refundable instead.
+    fn finalize_resolution(env: &Env, market_id: BytesN<32>, final_outcome: u32) {
+        // Too few revealed participants: refund everyone instead of resolving
+        let min_participants: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, MIN_PARTICIPANTS_KEY))
+            .unwrap_or(0);
+        if Self::get_participant_count(env.clone()) < min_participants {
+            Self::cancel_with_reason(env, market_id, "below_min_participants");
+            return;
+        }
+
+        // Load pool sizes
+        let yes_pool: i128 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, YES_POOL_KEY))
+            .unwrap_or(0);
+
+        let no_pool: i128 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, NO_POOL_KEY))
+            .unwrap_or(0);
+
+        // Calculate winner and loser shares
+        let (winner_shares, loser_shares) = if final_outcome == 1 {
+            // YES won
+            (yes_pool, no_pool)
+        } else {
+            // NO won
+            (no_pool, yes_pool)
+        };
+
+        // Claims compute winner_shares + loser_shares; refuse pools that can't be summed
+        if winner_shares.checked_add(loser_shares).is_none() {
+            panic!("Overflow in pool total");
+        }
+
+        // Payouts divide by winner_shares; refund instead of resolving to an empty side
+        if winner_shares == 0 {
+            Self::cancel_with_reason(env, market_id, "empty_winning_pool");
+            return;
+        }
+
+        // Store winning outcome
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(env, WINNING_OUTCOME_KEY), &final_outcome);
+
+        // Store winner and loser shares for payout calculations
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(env, WINNER_SHARES_KEY), &winner_shares);
+
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(env, LOSER_SHARES_KEY), &loser_shares);
+        Self::reserve_loser_refunds(env, loser_shares);
+
+        // Disputes are timed from when the market actually resolved
+        env.storage().persistent().set(
+            &Symbol::new(env, RESOLVED_AT_KEY),
+            &env.ledger().timestamp(),
+        );
+
+        // Update market state to RESOLVED
+        Self::set_market_state(env, STATE_RESOLVED);
+
+        // Cache the outcome in the factory; best effort, since the market
+        // remains the source of truth
+        let factory: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, FACTORY_KEY))
+            .expect("Factory not set");
+        let factory_client = crate::factory::MarketFactoryClient::new(env, &factory);
+        let _ = factory_client.try_report_resolution(&market_id, &final_outcome);
+
+        // Emit MarketResolved event
+        env.events().publish(
+            (Symbol::new(env, "MarketResolved"),),
+            (market_id, final_outcome, env.ledger().timestamp()),
+        );
+    }
+
+    /// Dispute market resolution within the dispute window
+    ///
+    /// Any participant may dispute a RESOLVED market within
+    /// `dispute_window_seconds` (7 days by default) of it resolving. The market moves to DISPUTED, which freezes claims and loser
+    /// refunds until the factory calls `resolve_dispute`.
+    ///
+    /// Claims and loser refunds stay frozen until the window closes, so disputes
+    /// are filed before any payout: a flipped outcome cannot claw back winnings
+    /// already paid.
+    ///
+    /// A market can be disputed once; after the factory settles that dispute
+    /// its outcome is final.
+    pub fn dispute_market(env: Env, user: Address, market_id: BytesN<32>, dispute_reason: Symbol) {
+        user.require_auth();
+
+        let state: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, MARKET_STATE_KEY))
+            .expect("Market not initialized");
+        if state != STATE_RESOLVED {
+            panic!("Market not resolved");
+        }
+        if env
+            .storage()
+            .persistent()
+            .has(&Symbol::new(&env, DISPUTE_SETTLED_KEY))
+        {
+            panic!("Market already disputed");
+        }
+
+        if env.ledger().timestamp() >= Self::dispute_window_end(&env) {
+            panic!("Dispute window closed");
+        }
+
+        let prediction_key = (Symbol::new(&env, PREDICTION_PREFIX), user.clone());
+        if !env.storage().persistent().has(&prediction_key) {
+            panic!("No prediction found for user");
+        }
+
+        let paid_out: i128 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, TOTAL_PAID_OUT_KEY))
+            .unwrap_or(0);
+        if paid_out > 0 {
+            panic!("Payouts already started");
+        }
+
+        let record = DisputeRecord {
+            user: user.clone(),
+            reason: dispute_reason.clone(),
+            timestamp: env.ledger().timestamp(),
+        };
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, DISPUTE_KEY), &record);
+        Self::set_market_state(&env, STATE_DISPUTED);
+
+        env.events().publish(
+            (Symbol::new(&env, "MarketDisputed"),),
+            (user, dispute_reason, market_id, record.timestamp),
+        );
+    }
+
+    /// Get how long after the market resolves the outcome can be disputed, in seconds
+    pub fn get_dispute_window(env: Env) -> u64 {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, DISPUTE_WINDOW_KEY))
+            .unwrap_or(DEFAULT_DISPUTE_WINDOW_SECONDS)
+    }
+
+    /// Get when the dispute window closes, if the market has resolved
+    ///
+    /// The window runs from the ledger time the market resolved, not its
+    /// scheduled resolution_time, so late resolutions can still be disputed.
+    pub fn get_dispute_window_end(env: Env) -> Option<u64> {
+        let resolved_at: Option<u64> = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, RESOLVED_AT_KEY));
+        resolved_at.map(|resolved_at| resolved_at + Self::get_dispute_window(env.clone()))
+    }
+
+    /// Get whether the market's dispute was upheld, once the factory settled it
+    pub fn get_dispute_upheld(env: Env) -> Option<bool> {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, DISPUTE_SETTLED_KEY))
+    }
+
+    /// Get the open dispute, if the market is DISPUTED
+    pub fn get_dispute(env: Env) -> Option<DisputeRecord> {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, DISPUTE_KEY))
+    }
+
+    /// Factory: Settle an open dispute and unfreeze claims
+    ///
+    /// A rejected dispute restores the original resolution. An upheld dispute
+    /// re-resolves the market on `corrected_outcome`, recomputing winner and
+    /// loser shares from the pools (or cancelling it if nobody backed that
+    /// outcome).
+    pub fn resolve_dispute(
+        env: Env,
+        admin: Address,
+        market_id: BytesN<32>,
+        upheld: bool,
+        corrected_outcome: Option<u32>,
+    ) {
+        admin.require_auth();
+
+        let factory: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, FACTORY_KEY))
+            .expect("Factory not set");
+        if admin != factory {
+            panic!("Unauthorized: only factory can resolve disputes");
+        }
+
+        let state: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, MARKET_STATE_KEY))
+            .expect("Market not initialized");
+        if state != STATE_DISPUTED {
+            panic!("Market not disputed");
+        }
+
+        env.storage()
+            .persistent()
+            .remove(&Symbol::new(&env, DISPUTE_KEY));
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, DISPUTE_SETTLED_KEY), &upheld);
+
+        if upheld {
+            let outcome = corrected_outcome.expect("corrected outcome required");
+            let num_outcomes: u32 = env
+                .storage()
+                .persistent()
+                .get(&Symbol::new(&env, NUM_OUTCOMES_KEY))
+                .unwrap_or(crate::helpers::BINARY_OUTCOMES);
+            if outcome >= num_outcomes {
+                panic!("invalid outcome");
+            }
+            Self::finalize_resolution(&env, market_id.clone(), outcome);
+        } else {
+            Self::set_market_state(&env, STATE_RESOLVED);
+        }
+
+        env.events().publish(
+            (Symbol::new(&env, "DisputeResolved"),),
+            (market_id, upheld, Self::get_winning_outcome(env.clone())),
+        );
+    }
+
+    /// Claim winnings after market resolution
+    ///
+    /// This function allows users to claim their winnings after a market has been resolved.
+    ///
+    /// # Requirements
+    /// - Market must be in RESOLVED state
+    /// - The dispute window must have closed, or a dispute settled the outcome
+    /// - User must have a prediction matching the final_outcome
+    /// - User must not have already claimed
+    ///
+    /// # Payout Calculation
+    /// - Payout = (user_amount / winner_shares) * total_pool
+    /// - 10% protocol fee is deducted from the gross payout and deposited into
+    ///   the fee recipient's platform pool; if that deposit fails the fee stays
+    ///   in escrow for `sweep_retained_fees`
+    ///
+    /// # Events
+    /// - Emits WinningsClaimed(user, market_id, amount)
+    /// - Emits FeeRetainedLocally(market_id, fee) if the treasury deposit fails
+    ///
+    /// # Panics
+    /// * If market is not resolved
+    /// * If the dispute window is still open and no dispute has settled
+    /// * If user has no prediction
+    /// * If user already claimed
+    /// * If user did not predict winning outcome
+    pub fn claim_winnings(env: Env, user: Address, market_id: BytesN<32>) -> i128 {
+        // Require user authentication
+        user.require_auth();
+
+        // 1. Validate market state is RESOLVED
+        let state: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, MARKET_STATE_KEY))
+            .expect("Market not initialized");
+
+        if state != STATE_RESOLVED {
+            panic!("Market not resolved");
+        }
+        Self::require_outcome_final(&env);
+
+        // 2. Get User Prediction
+        let prediction_key = (Symbol::new(&env, PREDICTION_PREFIX), user.clone());
+        let mut prediction: UserPrediction = env
+            .storage()
+            .persistent()
+            .get(&prediction_key)
+            .expect("No prediction found for user");
+
+        // 3. Check if already claimed (idempotent - return early if already claimed)
+        if prediction.claimed {
+            panic!("Winnings already claimed");
+        }
+
+        // 4. Validate outcome matches winning outcome
+        let winning_outcome: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, WINNING_OUTCOME_KEY))
+            .expect("Winning outcome not found");
+
+        if prediction.outcome != winning_outcome {
+            panic!("User did not predict winning outcome");
+        }
+
+        // 5. Calculate Payout
+        let (net_payout, fee) = Self::calculate_payout(&env, &user, prediction.amount);
+
+        if net_payout == 0 {
+            panic!("Payout amount is zero");
+        }
+
+        // 6. Transfer Payout from market escrow to user
+        let usdc_token: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, USDC_KEY))
+            .expect("USDC token not found");
+
+        let token_client = token::TokenClient::new(&env, &usdc_token);
+        let contract_address = env.current_contract_address();
+
+        token_client.transfer(&contract_address, &user, &net_payout);
+
+        // 7. Route Fee to the treasury's platform pool
+        if fee > 0 {
+            let treasury_address = Self::get_fee_recipient(env.clone());
+            let treasury_client = crate::treasury::TreasuryClient::new(&env, &treasury_address);
+            // Market contract is the source of the fee
+            Self::authorize_fee_pull(&env, &usdc_token, &treasury_address, fee);
+            let deposit = treasury_client.try_deposit_category_fees(
+                &contract_address,
+                &Symbol::new(&env, "platform"),
+                &fee,
+            );
+            if matches!(deposit, Ok(Ok(()))) {
+                Self::record_payout(&env, fee);
+            } else {
+                // Never block the winner on the treasury: keep the fee in
+                // escrow until swept with `sweep_retained_fees`
+                let retained = Self::get_retained_fees(env.clone());
+                env.storage()
+                    .persistent()
+                    .set(&Symbol::new(&env, RETAINED_FEES_KEY), &(retained + fee));
+                env.events().publish(
+                    (Symbol::new(&env, "FeeRetainedLocally"),),
+                    (market_id.clone(), fee),
+                );
+            }
+        }
+        Self::record_payout(&env, net_payout);
+
+        // Track gross winnings claimed (net payout plus its fee)
+        let claimed_total = Self::get_claimed_total(env.clone());
+        env.storage().persistent().set(
+            &Symbol::new(&env, CLAIMED_TOTAL_KEY),
+            &(claimed_total + net_payout + fee),
+        );
+
+        // 8. Mark as claimed (idempotent - prevents double-claim)
+        prediction.claimed = true;
+        env.storage().persistent().set(&prediction_key, &prediction);
+
+        // 9. Emit WinningsClaimed Event
+        env.events().publish(
+            (Symbol::new(&env, "WinningsClaimed"),),
+            (user, market_id.clone(), net_payout),
+        );
+
+        net_payout
+    }
+
+    /// Get the (winner_shares, loser_shares, protocol_fee_bps) that determine payouts
+    ///
+    /// Winners split winner_shares + loser_shares pro rata to their stake, less
+    /// the loser refund reserve and the protocol fee.
+    pub fn get_resolution_details(env: Env, _market_id: BytesN<32>) -> (i128, i128, u32) {
+        let winner_shares: i128 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, WINNER_SHARES_KEY))
+            .expect("Market not resolved");
+        let loser_shares: i128 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, LOSER_SHARES_KEY))
+            .unwrap_or(0);
+        (winner_shares, loser_shares, PROTOCOL_FEE_BPS)
+    }
+
+    /// Get protocol fees kept in escrow because the treasury deposit failed
+    pub fn get_retained_fees(env: Env) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, RETAINED_FEES_KEY))
+            .unwrap_or(0)
+    }
+
+    /// Factory: Deposit fees retained during claims into the fee recipient's platform pool
+    ///
+    /// The factory passes its own treasury, since the market cannot call back
+    /// into the factory mid-sweep; a `fee_recipient` override takes precedence.
+    /// Returns the amount swept.
+    pub fn sweep_retained_fees(env: Env, factory_treasury: Address) -> i128 {
+        Self::require_factory(&env);
+
+        let retained = Self::get_retained_fees(env.clone());
+        if retained == 0 {
+            panic!("No retained fees");
+        }
+
+        let treasury_address: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, FEE_RECIPIENT_KEY))
+            .unwrap_or(factory_treasury);
+        let usdc_token: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, USDC_KEY))
+            .expect("USDC token not found");
+        Self::authorize_fee_pull(&env, &usdc_token, &treasury_address, retained);
+        crate::treasury::TreasuryClient::new(&env, &treasury_address).deposit_category_fees(
+            &env.current_contract_address(),
+            &Symbol::new(&env, "platform"),
+            &retained,
+        );
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, RETAINED_FEES_KEY), &0i128);
+        Self::record_payout(&env, retained);
+
+        env.events().publish(
+            (Symbol::new(&env, "RetainedFeesSwept"),),
+            (treasury_address, retained),
+        );
+        retained
+    }
+
+    /// Get the gross winnings claimed so far (payouts before the protocol fee)
+    pub fn get_claimed_total(env: Env) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, CLAIMED_TOTAL_KEY))
+            .unwrap_or(0)
+    }
+
+    /// Get the treasury that receives this market's protocol fees
+    ///
+    /// The override set at initialization, else the factory's treasury.
+    pub fn get_fee_recipient(env: Env) -> Address {
+        if let Some(fee_recipient) = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, FEE_RECIPIENT_KEY))
+        {
+            return fee_recipient;
+        }
+
+        let factory_address: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, FACTORY_KEY))
+            .expect("Factory address not set");
+        crate::factory::MarketFactoryClient::new(&env, &factory_address).get_treasury()
+    }
+
+    /// Helper: Authorize the treasury to pull `amount` of USDC from this market's escrow
+    fn authorize_fee_pull(env: &Env, usdc_token: &Address, treasury: &Address, amount: i128) {
+        let market_address = env.current_contract_address();
+        env.authorize_as_current_contract(vec![
+            env,
+            InvokerContractAuthEntry::Contract(SubContractInvocation {
+                context: ContractContext {
+                    contract: usdc_token.clone(),
+                    fn_name: Symbol::new(env, "transfer"),
+                    args: (market_address, treasury.clone(), amount).into_val(env),
+                },
+                sub_invocations: vec![env],
+            }),
+        ]);
+    }
+
+    /// Helper: Get when the dispute window of a resolved market closes
+    fn dispute_window_end(env: &Env) -> u64 {
+        Self::get_dispute_window_end(env.clone()).expect("Market not resolved")
+    }
+
+    /// Helper: Panic while a resolved outcome can still be disputed
+    ///
+    /// Payouts wait for the dispute window to close, unless a dispute has
+    /// already settled the outcome.
+    fn require_outcome_final(env: &Env) {
+        let settled = env
+            .storage()
+            .persistent()
+            .has(&Symbol::new(env, DISPUTE_SETTLED_KEY));
+        if !settled && env.ledger().timestamp() < Self::dispute_window_end(env) {
+            panic!("Dispute window open");
+        }
+    }
+
+    /// Helper: Add USDC sent out of escrow (payouts, fees, refunds) to the running total
+    fn record_payout(env: &Env, amount: i128) {
+        let key = Symbol::new(env, TOTAL_PAID_OUT_KEY);
+        let paid_out: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+        env.storage().persistent().set(&key, &(paid_out + amount));
+    }
+
+    /// Helper: Compute a winning stake's (net_payout, protocol_fee)
+    ///
+    /// Payout = (UserAmount / WinnerPool) * (TotalPool - LoserRefundReserve), less
+    /// the 10% protocol fee. A fee-exempt creator keeps the fee as part of their payout.
+    fn calculate_payout(env: &Env, user: &Address, amount: i128) -> (i128, i128) {
+        let winner_shares: i128 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, WINNER_SHARES_KEY))
+            .expect("Winner shares not found");
+
+        let loser_shares: i128 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, LOSER_SHARES_KEY))
+            .unwrap_or(0);
+
+        if winner_shares == 0 {
+            panic!("No winners to claim");
+        }
+
+        // Loser refunds are set aside before winners split the pool
+        let total_pool = winner_shares
+            .checked_add(loser_shares)
+            .expect("Overflow in pool total")
+            - Self::get_loser_refund_reserve(env.clone());
+        let (net_payout, fee) = Self::payout_from_pools(amount, winner_shares, total_pool);
+
+        let creator_fee_exempt: bool = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, CREATOR_FEE_EXEMPT_KEY))
+            .unwrap_or(false);
+        if creator_fee_exempt && *user == Self::get_creator(env.clone()) {
+            return (net_payout + fee, 0);
+        }
+        (net_payout, fee)
+    }
+
+    /// Helper: Set aside `loser_refund_bps` of the losing pool for loser refunds
+    ///
+    /// Every refund rounds down, so the reserve covers all of them.
+    fn reserve_loser_refunds(env: &Env, loser_shares: i128) {
+        let refund_bps: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, LOSER_REFUND_BPS_KEY))
+            .unwrap_or(0);
+        let reserve = loser_shares
+            .checked_mul(refund_bps as i128)
+            .expect("Overflow in refund calculation")
+            / 10000;
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(env, LOSER_REFUND_RESERVE_KEY), &reserve);
+    }
+
+    /// Helper: Split the pooled payout for `amount` into (net_payout, protocol_fee)
+    fn payout_from_pools(amount: i128, winner_shares: i128, total_pool: i128) -> (i128, i128) {
+        // Calculate gross payout using integer arithmetic
+        // (amount * total_pool) / winner_shares
+        let gross_payout = amount
+            .checked_mul(total_pool)
+            .expect("Overflow in payout calculation")
+            .checked_div(winner_shares)
+            .expect("Division by zero in payout calculation");
+
+        let fee = (gross_payout * PROTOCOL_FEE_BPS as i128) / 10000;
+        (gross_payout - fee, fee)
+    }
+
+    /// Get a user's net profit or loss on a resolved market
+    ///
+    /// Winners get (net_payout - amount) after the protocol fee; losers get
+    /// -(amount - consolation_refund). Non-participants return 0.
+    pub fn get_user_pnl(env: Env, user: Address, _market_id: BytesN<32>) -> i128 {
+        let state: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, MARKET_STATE_KEY))
+            .expect("Market not initialized");
+        if state != STATE_RESOLVED {
+            panic!("Market not resolved");
+        }
+
+        let prediction_key = (Symbol::new(&env, PREDICTION_PREFIX), user);
+        let prediction: UserPrediction = match env.storage().persistent().get(&prediction_key) {
+            Some(prediction) => prediction,
+            None => return 0,
+        };
+
+        let winning_outcome: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, WINNING_OUTCOME_KEY))
+            .expect("Winning outcome not found");
+
+        if prediction.outcome == winning_outcome {
+            let (net_payout, _) = Self::calculate_payout(&env, &prediction.user, prediction.amount);
+            net_payout - prediction.amount
+        } else {
+            let refund_bps: u32 = env
+                .storage()
+                .persistent()
+                .get(&Symbol::new(&env, LOSER_REFUND_BPS_KEY))
+                .unwrap_or(0);
+            let refund_amount = prediction.amount * refund_bps as i128 / 10000;
+            refund_amount - prediction.amount
+        }
+    }
+
+    /// Refund users if their prediction failed (optional opt-in)
+    ///
+    /// Pays back `loser_refund_bps` of the losing stake as a consolation. Refunds come
+    /// out of a reserve taken from the losing pool at resolution, before winners split
+    /// it, and are only available when the market was initialized with a nonzero
+    /// `loser_refund_bps`.
+    ///
+    /// # Panics
+    /// * If loser refunds are disabled for this market
+    /// * If market is not resolved
+    /// * If the dispute window is still open and no dispute has settled
+    /// * If user has no prediction
+    /// * If user predicted the winning outcome
+    /// * If the bet was already refunded
+    pub fn refund_losing_bet(env: Env, user: Address, market_id: BytesN<32>) -> i128 {
+        // Require user authentication
+        user.require_auth();
+
+        // Validate refunds are enabled for this market
+        let refund_bps: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, LOSER_REFUND_BPS_KEY))
+            .unwrap_or(0);
+
+        if refund_bps == 0 {
+            panic!("Loser refunds disabled");
+        }
+
+        // Validate market state is RESOLVED
+        let state: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, MARKET_STATE_KEY))
+            .expect("Market not initialized");
+
+        if state != STATE_RESOLVED {
+            panic!("Market not resolved");
+        }
+        Self::require_outcome_final(&env);
+
+        // Query user's prediction for this market
+        let prediction_key = (Symbol::new(&env, PREDICTION_PREFIX), user.clone());
+        let mut prediction: UserPrediction = env
+            .storage()
+            .persistent()
+            .get(&prediction_key)
+            .expect("No prediction found for user");
+
+        // Validate user's outcome != winning_outcome (they lost)
+        let winning_outcome: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, WINNING_OUTCOME_KEY))
+            .expect("Winning outcome not found");
+
+        if prediction.outcome == winning_outcome {
+            panic!("User predicted winning outcome");
+        }
+
+        // Validate hasn't already been refunded (losers reuse the claimed flag)
+        if prediction.claimed {
+            panic!("Bet already refunded");
+        }
+
+        // Calculate partial refund
+        let refund_amount = prediction
+            .amount
+            .checked_mul(refund_bps as i128)
+            .expect("Overflow in refund calculation")
+            / 10000;
+
+        if refund_amount == 0 {
+            panic!("Refund amount is zero");
+        }
+
+        // Transfer refund from market escrow to user
+        let usdc_token: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, USDC_KEY))
+            .expect("USDC token not found");
+
+        let token_client = token::TokenClient::new(&env, &usdc_token);
+        token_client.transfer(&env.current_contract_address(), &user, &refund_amount);
+        Self::record_payout(&env, refund_amount);
+
+        // Mark as refunded
+        prediction.claimed = true;
+        env.storage().persistent().set(&prediction_key, &prediction);
+
+        // Emit LosingBetRefunded event
+        env.events().publish(
+            (Symbol::new(&env, "LosingBetRefunded"),),
+            (user, market_id, refund_amount, env.ledger().timestamp()),
+        );
+
+        refund_amount
+    }
+
+    /// Get the USDC set aside at resolution for loser refunds
+    pub fn get_loser_refund_reserve(env: Env) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, LOSER_REFUND_RESERVE_KEY))
+            .unwrap_or(0)
+    }
+
+    /// Get the configured loser refund percentage in basis points
+    pub fn get_loser_refund_bps(env: Env) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, LOSER_REFUND_BPS_KEY))
+            .unwrap_or(0)
+    }
+
+    /// Get the (min_bet, max_bet) a commitment must fall within (0 and i128::MAX when unset)
+    pub fn get_bet_limits(env: Env) -> (i128, i128) {
+        let min_bet: i128 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, MIN_BET_KEY))
+            .unwrap_or(0);
+        let max_bet: i128 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, MAX_BET_KEY))
+            .unwrap_or(0);
+        (min_bet, if max_bet == 0 { i128::MAX } else { max_bet })
+    }
+
+    /// Get the most commitments a single user may make (0 = no cap)
+    pub fn get_max_bets_per_user(env: Env) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, MAX_BETS_PER_USER_KEY))
+            .unwrap_or(0)
+    }
+
+    /// Get how many commitments a user has made in this market
+    pub fn get_user_bet_count(env: Env, user: Address) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&(Symbol::new(&env, USER_BET_COUNT_PREFIX), user))
+            .unwrap_or(0)
+    }
+
+    /// Get the per-reveal cap relative to the current pool in basis points (0 = no cap)
+    pub fn get_max_bet_vs_pool_bps(env: Env) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, MAX_BET_VS_POOL_BPS_KEY))
+            .unwrap_or(0)
+    }
+
+    /// Get market summary data
+    ///
+    /// `time_remaining_to_close` and `time_remaining_to_resolution` are computed
+    /// from the current ledger timestamp and go negative once the time has passed.
+    pub fn get_market_state(env: Env, _market_id: BytesN<32>) -> MarketState {
+        let market_id: BytesN<32> = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, MARKET_ID_KEY))
+            .expect("Market not initialized");
+        let closing_time: u64 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, CLOSING_TIME_KEY))
+            .unwrap_or(0);
+        let resolution_time: u64 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, RESOLUTION_TIME_KEY))
+            .unwrap_or(0);
+        let now = env.ledger().timestamp() as i64;
+
+        MarketState {
+            market_id,
+            creator: env
+                .storage()
+                .persistent()
+                .get(&Symbol::new(&env, CREATOR_KEY))
+                .expect("Creator not found"),
+            state: env
+                .storage()
+                .persistent()
+                .get(&Symbol::new(&env, MARKET_STATE_KEY))
+                .expect("Market not initialized"),
+            closing_time,
+            resolution_time,
+            time_remaining_to_close: closing_time as i64 - now,
+            time_remaining_to_resolution: resolution_time as i64 - now,
+            yes_pool: env
+                .storage()
+                .persistent()
+                .get(&Symbol::new(&env, YES_POOL_KEY))
+                .unwrap_or(0),
+            no_pool: env
+                .storage()
+                .persistent()
+                .get(&Symbol::new(&env, NO_POOL_KEY))
+                .unwrap_or(0),
+            total_volume: env
+                .storage()
+                .persistent()
+                .get(&Symbol::new(&env, TOTAL_VOLUME_KEY))
+                .unwrap_or(0),
+            winning_outcome: env
+                .storage()
+                .persistent()
+                .get(&Symbol::new(&env, WINNING_OUTCOME_KEY)),
+        }
+    }
+
+    /// Get market state with the user's prediction and payout overlaid
+    ///
+    /// Before resolution `payout` is what the user would receive if their
+    /// outcome won at current pool sizes; after resolution it is their winnings
+    /// (or consolation refund if they lost). Non-participants get the base
+    /// state with no prediction and zero payout.
+    pub fn get_market_state_for_user(
+        env: Env,
+        _market_id: BytesN<32>,
+        user: Address,
+    ) -> MarketStateForUser {
+        let state: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, MARKET_STATE_KEY))
+            .expect("Market not initialized");
+        let yes_pool: i128 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, YES_POOL_KEY))
+            .unwrap_or(0);
+        let no_pool: i128 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, NO_POOL_KEY))
+            .unwrap_or(0);
+        let winning_outcome: Option<u32> = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, WINNING_OUTCOME_KEY));
+
+        let prediction_key = (Symbol::new(&env, PREDICTION_PREFIX), user);
+        let prediction: Option<UserPrediction> = env.storage().persistent().get(&prediction_key);
+
+        let payout = match (&prediction, winning_outcome) {
+            (None, _) => 0,
+            (Some(p), Some(winning)) if state == STATE_RESOLVED => {
+                if p.outcome == winning {
+                    Self::calculate_payout(&env, &p.user, p.amount).0
+                } else {
+                    let refund_bps: u32 = env
+                        .storage()
+                        .persistent()
+                        .get(&Symbol::new(&env, LOSER_REFUND_BPS_KEY))
+                        .unwrap_or(0);
+                    p.amount * refund_bps as i128 / 10000
+                }
+            }
+            (Some(p), _) => {
+                let (outcome_pool, other_pool) = if p.outcome == 1 {
+                    (yes_pool, no_pool)
+                } else {
+                    (no_pool, yes_pool)
+                };
+                if outcome_pool == 0 {
+                    0
+                } else {
+                    let refund_bps: u32 = env
+                        .storage()
+                        .persistent()
+                        .get(&Symbol::new(&env, LOSER_REFUND_BPS_KEY))
+                        .unwrap_or(0);
+                    let reserve = other_pool * refund_bps as i128 / 10000;
+                    Self::payout_from_pools(p.amount, outcome_pool, yes_pool + no_pool - reserve).0
+                }
+            }
+        };
+
+        MarketStateForUser {
+            state,
+            closing_time: env
+                .storage()
+                .persistent()
+                .get(&Symbol::new(&env, CLOSING_TIME_KEY))
+                .unwrap_or(0),
+            resolution_time: env
+                .storage()
+                .persistent()
+                .get(&Symbol::new(&env, RESOLUTION_TIME_KEY))
+                .unwrap_or(0),
+            yes_pool,
+            no_pool,
+            total_volume: env
+                .storage()
+                .persistent()
+                .get(&Symbol::new(&env, TOTAL_VOLUME_KEY))
+                .unwrap_or(0),
+            winning_outcome,
+            has_prediction: prediction.is_some(),
+            outcome: prediction.as_ref().map_or(0, |p| p.outcome),
+            amount: prediction.as_ref().map_or(0, |p| p.amount),
+            claimed: prediction.as_ref().is_some_and(|p| p.claimed),
+            payout,
+        }
+    }
+
+    /// Get a user's revealed prediction in this market
+    ///
+    /// Once resolved, `potential_payout` is the net winnings `claim_winnings`
+    /// pays (after the protocol fee). Returns None if the user never revealed.
+    pub fn get_user_prediction(
+        env: Env,
+        user: Address,
+        _market_id: BytesN<32>,
+    ) -> Option<UserPredictionView> {
+        let prediction_key = (Symbol::new(&env, PREDICTION_PREFIX), user);
+        let prediction: UserPrediction = env.storage().persistent().get(&prediction_key)?;
+
+        let state: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, MARKET_STATE_KEY))
+            .expect("Market not initialized");
+        let winning_outcome: Option<u32> = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, WINNING_OUTCOME_KEY));
+
+        let (potential_payout, is_winner) = match winning_outcome {
+            Some(winning) if state == STATE_RESOLVED => {
+                if prediction.outcome == winning {
+                    let (net_payout, _) =
+                        Self::calculate_payout(&env, &prediction.user, prediction.amount);
+                    (net_payout, true)
+                } else {
+                    (0, false)
+                }
+            }
+            _ => {
+                let yes_pool: i128 = env
+                    .storage()
+                    .persistent()
+                    .get(&Symbol::new(&env, YES_POOL_KEY))
+                    .unwrap_or(0);
+                let no_pool: i128 = env
+                    .storage()
+                    .persistent()
+                    .get(&Symbol::new(&env, NO_POOL_KEY))
+                    .unwrap_or(0);
+                let outcome_pool = if prediction.outcome == 1 {
+                    yes_pool
+                } else {
+                    no_pool
+                };
+                if outcome_pool == 0 {
+                    (0, false)
+                } else {
+                    let (net_payout, _) = Self::payout_from_pools(
+                        prediction.amount,
+                        outcome_pool,
+                        yes_pool + no_pool,
+                    );
+                    (net_payout, false)
+                }
+            }
+        };
+
+        Some(UserPredictionView {
+            user: prediction.user,
+            outcome: prediction.outcome,
+            amount: prediction.amount,
+            claimed: prediction.claimed,
+            timestamp: prediction.timestamp,
+            potential_payout,
+            is_winner,
+        })
+    }
+
+    /// Get all predictions in market (for governance/audits)
+    ///
+    /// TODO: Get All Predictions
+    /// - Require admin or oracle role
+    /// - Return list of all user predictions
+    /// - Include: user address, outcome, amount for each
+    /// - Include participation count and total_volume
+    /// - Exclude: user private data (privacy-preserving)
+    pub fn get_all_predictions(env: Env, market_id: BytesN<32>) -> Vec<Symbol> {
+        todo!("See get all predictions TODO above")
+    }
+
+    /// Get market leaderboard (top predictors by winnings)
+    ///
+    /// TODO: Get Market Leaderboard
+    /// - Collect all winners for this market
+    /// - Sort by payout amount descending
+    /// - Limit top 100
+    /// - Return: user address, prediction, payout, accuracy
+    /// - For display on frontend
+    pub fn get_market_leaderboard(env: Env, market_id: BytesN<32>) -> Vec<Symbol> {
+        todo!("See get market leaderboard TODO above")
+    }
+
+    /// Get total volume and liquidity for market
+    ///
+    /// TODO: Get Market Liquidity
+    /// - Query yes_pool, no_pool, total_volume
+    /// - Calculate current odds for YES and NO
+    /// - Return depth: how much can be bought at current price
+    /// - Include slippage estimates for trades
+    pub fn get_market_liquidity(env: Env, market_id: BytesN<32>) -> i128 {
+        todo!("See get market liquidity TODO above")
+    }
+
+    /// Audit view: compare the USDC escrow against what the market's books expect
+    ///
+    /// Returns (actual_escrow, expected_escrow, difference). Expected escrow is
+    /// every stake taken in at commit (revealed pools plus unrevealed commitments)
+    /// minus all winnings, protocol fees and refunds paid out since. A nonzero
+    /// difference means funds arrived or left outside the market's accounting.
+    pub fn reconcile_market(env: Env, _market_id: BytesN<32>) -> (i128, i128, i128) {
+        let usdc_token: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, USDC_KEY))
+            .expect("USDC token not found");
+        let actual_escrow =
+            token::TokenClient::new(&env, &usdc_token).balance(&env.current_contract_address());
+
+        let total_deposited = Self::get_total_volume(env.clone());
+        let total_paid_out: i128 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, TOTAL_PAID_OUT_KEY))
+            .unwrap_or(0);
+        let expected_escrow = total_deposited - total_paid_out;
+
+        (
+            actual_escrow,
+            expected_escrow,
+            actual_escrow - expected_escrow,
+        )
+    }
+
+    /// Emergency function: Market creator can cancel unresolved market
+    ///
+    /// TODO: Cancel Market (Creator Only)
+    /// - Require market creator authentication
+    /// - Validate market state is OPEN or CLOSED (not resolved)
+    /// - Return all user USDC balances (full refund)
+    /// - Loop through all users with predictions
+    /// - Transfer their full amounts back from escrow
+    /// - Handle any transfer failures (log but continue)
+    /// - Set market state to CANCELLED
+    /// - Emit MarketCancelled(market_id, reason, creator, timestamp)
+    pub fn cancel_market(env: Env, creator: Address, market_id: BytesN<32>) {
+        todo!("See cancel market TODO above")
+    }
+
+    // --- TEST HELPERS (Not for production use, but exposed for integration tests) ---
+    // In a real production contract, these would be removed or gated behind a feature flag.
+
+    /// Test helper: Set a user's prediction directly (bypasses commit/reveal)
+    pub fn test_set_prediction(env: Env, user: Address, outcome: u32, amount: i128) {
+        let prediction = UserPrediction {
+            user: user.clone(),
+            outcome,
+            amount,
+            claimed: false,
+            timestamp: env.ledger().timestamp(),
+        };
+        let key = (Symbol::new(&env, PREDICTION_PREFIX), user);
+        env.storage().persistent().set(&key, &prediction);
+    }
+
+    /// Test helper: Setup market resolution state directly
+    pub fn test_setup_resolution(
+        env: Env,
+        _market_id: BytesN<32>,
+        outcome: u32,
+        winner_shares: i128,
+        loser_shares: i128,
+    ) {
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, MARKET_STATE_KEY), &STATE_RESOLVED);
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, WINNING_OUTCOME_KEY), &outcome);
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, WINNER_SHARES_KEY), &winner_shares);
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, LOSER_SHARES_KEY), &loser_shares);
+        Self::reserve_loser_refunds(&env, loser_shares);
+        env.storage().persistent().set(
+            &Symbol::new(&env, RESOLVED_AT_KEY),
+            &env.ledger().timestamp(),
+        );
+    }
+
+    /// Test helper: Get user's prediction
+    pub fn test_get_prediction(env: Env, user: Address) -> Option<UserPrediction> {
+        let key = (Symbol::new(&env, PREDICTION_PREFIX), user);
+        env.storage().persistent().get(&key)
+    }
+
+    /// Test helper: Get winning outcome
+    pub fn test_get_winning_outcome(env: Env) -> Option<u32> {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, WINNING_OUTCOME_KEY))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger, MockAuth, MockAuthInvoke},
+        Address, BytesN, Env, IntoVal,
+    };
+
+    // Mock Oracle for testing
+    #[contract]
+    pub struct MockOracle;
+
+    #[contractimpl]
+    impl MockOracle {
+        pub fn initialize(_env: Env) {}
+
+        pub fn get_required_consensus(_env: Env) -> u32 {
+            1
+        }
+
+        pub fn check_consensus(env: Env, _market_id: BytesN<32>) -> (bool, u32) {
+            let reached = env
+                .storage()
+                .instance()
+                .get(&Symbol::new(&env, "consensus"))
+                .unwrap_or(true);
+            let outcome = env
+                .storage()
+                .instance()
+                .get(&Symbol::new(&env, "outcome"))
+                .unwrap_or(1u32);
+            (reached, outcome)
+        }
+
+        pub fn get_consensus_result(env: Env, _market_id: BytesN<32>) -> u32 {
+            env.storage()
+                .instance()
+                .get(&Symbol::new(&env, "outcome"))
+                .unwrap_or(1u32)
+        }
+
+        // Test helpers to configure the mock
+        pub fn set_consensus_status(env: Env, reachable: bool) {
+            env.storage()
+                .instance()
+                .set(&Symbol::new(&env, "consensus"), &reachable);
+        }
+
+        pub fn set_outcome_value(env: Env, outcome: u32) {
+            env.storage()
+                .instance()
+                .set(&Symbol::new(&env, "outcome"), &outcome);
+        }
+
+        pub fn get_consensus_attestation_time(env: Env, _market_id: BytesN<32>) -> u64 {
+            env.storage()
+                .instance()
+                .get(&Symbol::new(&env, "attested_at"))
+                .unwrap_or(0)
+        }
+
+        pub fn get_vote_counts(env: Env, _market_id: BytesN<32>) -> (u32, u32) {
+            env.storage()
+                .instance()
+                .get(&Symbol::new(&env, "votes"))
+                .unwrap_or((0u32, 0u32))
+        }
+
+        pub fn set_vote_counts(env: Env, yes_votes: u32, no_votes: u32) {
+            env.storage()
+                .instance()
+                .set(&Symbol::new(&env, "votes"), &(yes_votes, no_votes));
+        }
+
+        pub fn set_attested_at(env: Env, timestamp: u64) {
+            env.storage()
+                .instance()
+                .set(&Symbol::new(&env, "attested_at"), &timestamp);
+        }
+    }
+
+    // Helper to seed revealed pool sizes directly
+    fn seed_pools(env: &Env, market: &Address, yes_pool: i128, no_pool: i128) {
+        env.as_contract(market, || {
+            env.storage()
+                .persistent()
+                .set(&Symbol::new(env, YES_POOL_KEY), &yes_pool);
+            env.storage()
+                .persistent()
+                .set(&Symbol::new(env, NO_POOL_KEY), &no_pool);
+        });
+    }
+
+    /// Move the ledger to the end of a resolved market's dispute window
+    fn close_dispute_window(env: &Env, market_client: &PredictionMarketClient) {
+        let window_end = market_client.get_dispute_window_end().unwrap();
+        env.ledger().with_mut(|li| {
+            li.timestamp = window_end;
+        });
+    }
+
+    // Helper to create token contract for tests
+    fn create_token_contract<'a>(env: &Env, admin: &Address) -> token::StellarAssetClient<'a> {
+        let token_address = env
+            .register_stellar_asset_contract_v2(admin.clone())
+            .address();
+        token::StellarAssetClient::new(env, &token_address)
+    }
+
+    // ============================================================================
+    // CLAIM WINNINGS TESTS
+    // ============================================================================
+
+    #[test]
+    fn test_claim_winnings_happy_path() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let market_id_bytes = BytesN::from_array(&env, &[0; 32]);
+        let market_contract_id = env.register(PredictionMarket, ());
+        let market_client = PredictionMarketClient::new(&env, &market_contract_id);
+        let oracle_contract_id = env.register(MockOracle, ());
+
+        let token_admin = Address::generate(&env);
+        let usdc_client = create_token_contract(&env, &token_admin);
+        let usdc_address = usdc_client.address.clone();
+
+        let creator = Address::generate(&env);
+        let user = Address::generate(&env);
+
+        market_client.initialize(
+            &market_id_bytes,
+            &creator,
+            &Address::generate(&env),
+            &usdc_address,
+            &oracle_contract_id,
+            &2000,
+            &3000,
+            &MarketConfig::default(),
+        );
+
+        // Mint USDC to contract to simulate pot
+        usdc_client.mint(&market_contract_id, &1000);
+
+        // Setup State manually (Simulate Resolution)
+        market_client.test_setup_resolution(
+            &market_id_bytes,
+            &1u32,     // Winning outcome YES
+            &1000i128, // Winner shares
+            &0i128,    // Loser shares
+        );
+        close_dispute_window(&env, &market_client);
+
+        // Setup User Prediction
+        market_client.test_set_prediction(
+            &user, &1u32,     // Voted YES
+            &1000i128, // Amount
+        );
+
+        // Claim
+        let payout = market_client.claim_winnings(&user, &market_id_bytes);
+
+        // Expect 900 (1000 - 10% fee)
+        assert_eq!(payout, 900);
+
+        // Verify transfer happened
+        assert_eq!(usdc_client.balance(&user), 900);
+    }
+
+    #[test]
+    #[should_panic(expected = "User did not predict winning outcome")]
+    fn test_claim_winnings_loser_cannot_claim() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let market_id_bytes = BytesN::from_array(&env, &[0; 32]);
+        let market_contract_id = env.register(PredictionMarket, ());
+        let market_client = PredictionMarketClient::new(&env, &market_contract_id);
+        let oracle_contract_id = env.register(MockOracle, ());
+        let token_admin = Address::generate(&env);
+        let usdc_client = create_token_contract(&env, &token_admin);
+
+        market_client.initialize(
+            &market_id_bytes,
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &usdc_client.address,
+            &oracle_contract_id,
+            &2000,
+            &3000,
+            &MarketConfig::default(),
+        );
+
+        market_client.test_setup_resolution(&market_id_bytes, &1u32, &1000, &1000);
+        close_dispute_window(&env, &market_client);
+
+        let user = Address::generate(&env);
+        // User predicted NO (0), Winner is YES (1)
+        market_client.test_set_prediction(&user, &0u32, &500);
+
+        market_client.claim_winnings(&user, &market_id_bytes);
+    }
+
+    #[test]
+    #[should_panic(expected = "Market not resolved")]
+    fn test_cannot_claim_before_resolution() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let market_id_bytes = BytesN::from_array(&env, &[0; 32]);
+        let market_contract_id = env.register(PredictionMarket, ());
+        let market_client = PredictionMarketClient::new(&env, &market_contract_id);
+        let oracle_contract_id = env.register(MockOracle, ());
+        let token_admin = Address::generate(&env);
+        let usdc_client = create_token_contract(&env, &token_admin);
+
+        market_client.initialize(
+            &market_id_bytes,
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &usdc_client.address,
+            &oracle_contract_id,
+            &2000,
+            &3000,
+            &MarketConfig::default(),
+        );
+
+        let user = Address::generate(&env);
+        market_client.test_set_prediction(&user, &1u32, &500);
+
+        // Market is still OPEN (not resolved) - should fail
+        market_client.claim_winnings(&user, &market_id_bytes);
+    }
+
+    #[test]
+    #[should_panic(expected = "Winnings already claimed")]
+    fn test_cannot_double_claim() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let market_id_bytes = BytesN::from_array(&env, &[0; 32]);
+        let market_contract_id = env.register(PredictionMarket, ());
+        let market_client = PredictionMarketClient::new(&env, &market_contract_id);
+        let oracle_contract_id = env.register(MockOracle, ());
+        let token_admin = Address::generate(&env);
+        let usdc_client = create_token_contract(&env, &token_admin);
+
+        market_client.initialize(
+            &market_id_bytes,
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &usdc_client.address,
+            &oracle_contract_id,
+            &2000,
+            &3000,
+            &MarketConfig::default(),
+        );
+        usdc_client.mint(&market_contract_id, &2000);
+
+        market_client.test_setup_resolution(&market_id_bytes, &1u32, &1000, &0);
+        close_dispute_window(&env, &market_client);
+
+        let user = Address::generate(&env);
+        market_client.test_set_prediction(&user, &1u32, &1000);
+
+        market_client.claim_winnings(&user, &market_id_bytes);
+        market_client.claim_winnings(&user, &market_id_bytes); // Should fail
+    }
+
+    #[test]
+    #[should_panic(expected = "Overflow in pool total")]
+    fn test_claim_winnings_pool_overflow_is_graceful() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let market_id_bytes = BytesN::from_array(&env, &[0; 32]);
+        let market_contract_id = env.register(PredictionMarket, ());
+        let market_client = PredictionMarketClient::new(&env, &market_contract_id);
+
+        market_client.initialize(
+            &market_id_bytes,
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &2000,
+            &3000,
+            &MarketConfig::default(),
+        );
+
+        let huge = i128::MAX / 2 + 1;
+        market_client.test_setup_resolution(&market_id_bytes, &1u32, &huge, &huge);
+        close_dispute_window(&env, &market_client);
+
+        let user = Address::generate(&env);
+        market_client.test_set_prediction(&user, &1u32, &500);
+        market_client.claim_winnings(&user, &market_id_bytes);
+    }
+
+    #[test]
+    #[should_panic(expected = "Overflow in pool total")]
+    fn test_resolve_market_pool_overflow_is_graceful() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let market_id_bytes = BytesN::from_array(&env, &[0; 32]);
+        let market_contract_id = env.register(PredictionMarket, ());
+        let market_client = PredictionMarketClient::new(&env, &market_contract_id);
+        let oracle_contract_id = env.register(MockOracle, ());
+
+        market_client.initialize(
+            &market_id_bytes,
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &oracle_contract_id,
+            &2000,
+            &3000,
+            &MarketConfig::default(),
+        );
+
+        let huge = i128::MAX / 2 + 1;
+        env.as_contract(&market_contract_id, || {
+            env.storage()
+                .persistent()
+                .set(&Symbol::new(&env, YES_POOL_KEY), &huge);
+            env.storage()
+                .persistent()
+                .set(&Symbol::new(&env, NO_POOL_KEY), &huge);
+        });
+
+        env.ledger().with_mut(|li| {
+            li.timestamp = 2010;
+        });
+        market_client.close_market(&market_id_bytes);
+        env.ledger().with_mut(|li| {
+            li.timestamp = 3010;
+        });
+        market_client.resolve_market(&market_id_bytes);
+    }
+
+    #[test]
+    fn test_correct_payout_calculation() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let market_id_bytes = BytesN::from_array(&env, &[0; 32]);
+        let market_contract_id = env.register(PredictionMarket, ());
+        let market_client = PredictionMarketClient::new(&env, &market_contract_id);
+        let oracle_contract_id = env.register(MockOracle, ());
+        let token_admin = Address::generate(&env);
+        let usdc_client = create_token_contract(&env, &token_admin);
+
+        market_client.initialize(
+            &market_id_bytes,
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &usdc_client.address,
+            &oracle_contract_id,
+            &2000,
+            &3000,
+            &MarketConfig::default(),
+        );
+
+        // Total pool: 1000 (winners) + 500 (losers) = 1500
+        // User has 500 of 1000 winner shares
+        // Gross payout = (500 / 1000) * 1500 = 750
+        // Net payout (after 10% fee) = 750 - 75 = 675
+        usdc_client.mint(&market_contract_id, &1500);
+
+        market_client.test_setup_resolution(&market_id_bytes, &1u32, &1000, &500);
+        close_dispute_window(&env, &market_client);
+
+        let user = Address::generate(&env);
+        market_client.test_set_prediction(&user, &1u32, &500);
+
+        let payout = market_client.claim_winnings(&user, &market_id_bytes);
+        assert_eq!(payout, 675);
+        assert_eq!(usdc_client.balance(&user), 675);
+    }
+
+    #[test]
+    fn test_multiple_winners_correct_payout() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let market_id_bytes = BytesN::from_array(&env, &[0; 32]);
+        let market_contract_id = env.register(PredictionMarket, ());
+        let market_client = PredictionMarketClient::new(&env, &market_contract_id);
+        let oracle_contract_id = env.register(MockOracle, ());
+        let token_admin = Address::generate(&env);
+        let usdc_client = create_token_contract(&env, &token_admin);
+
+        market_client.initialize(
+            &market_id_bytes,
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &usdc_client.address,
+            &oracle_contract_id,
+            &2000,
+            &3000,
+            &MarketConfig::default(),
+        );
+
+        // Total pool: 1000 (winners) + 1000 (losers) = 2000
+        // User1 has 600, User2 has 400 of 1000 winner shares
+        usdc_client.mint(&market_contract_id, &2000);
+
+        market_client.test_setup_resolution(&market_id_bytes, &1u32, &1000, &1000);
+        close_dispute_window(&env, &market_client);
+
+        let user1 = Address::generate(&env);
+        let user2 = Address::generate(&env);
+        market_client.test_set_prediction(&user1, &1u32, &600);
+        market_client.test_set_prediction(&user2, &1u32, &400);
+
+        // User1: (600 / 1000) * 2000 = 1200, minus 10% = 1080
+        let payout1 = market_client.claim_winnings(&user1, &market_id_bytes);
+        assert_eq!(payout1, 1080);
+
+        // User2: (400 / 1000) * 2000 = 800, minus 10% = 720
+        let payout2 = market_client.claim_winnings(&user2, &market_id_bytes);
+        assert_eq!(payout2, 720);
+    }
+
+    #[test]
+    #[should_panic(expected = "No prediction found for user")]
+    fn test_no_prediction_cannot_claim() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let market_id_bytes = BytesN::from_array(&env, &[0; 32]);
+        let market_contract_id = env.register(PredictionMarket, ());
+        let market_client = PredictionMarketClient::new(&env, &market_contract_id);
+        let oracle_contract_id = env.register(MockOracle, ());
+        let token_admin = Address::generate(&env);
+        let usdc_client = create_token_contract(&env, &token_admin);
+
+        market_client.initialize(
+            &market_id_bytes,
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &usdc_client.address,
+            &oracle_contract_id,
+            &2000,
+            &3000,
+            &MarketConfig::default(),
+        );
+
+        market_client.test_setup_resolution(&market_id_bytes, &1u32, &1000, &0);
+        close_dispute_window(&env, &market_client);
+
+        let user = Address::generate(&env);
+        // User has no prediction
+        market_client.claim_winnings(&user, &market_id_bytes);
+    }
+
+    // ============================================================================
+    // REFUND LOSING BET TESTS
+    // ============================================================================
+
+    #[test]
+    fn test_refund_losing_bet_configured_percentage() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let market_id_bytes = BytesN::from_array(&env, &[0; 32]);
+        let market_contract_id = env.register(PredictionMarket, ());
+        let market_client = PredictionMarketClient::new(&env, &market_contract_id);
+        let oracle_contract_id = env.register(MockOracle, ());
+        let token_admin = Address::generate(&env);
+        let usdc_client = create_token_contract(&env, &token_admin);
+
+        market_client.initialize(
+            &market_id_bytes,
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &usdc_client.address,
+            &oracle_contract_id,
+            &2000,
+            &3000,
+            &MarketConfig {
+                loser_refund_bps: 1000, // 10%
+                ..Default::default()
+            },
+        );
+        assert_eq!(market_client.get_loser_refund_bps(), 1000);
+
+        usdc_client.mint(&market_contract_id, &2000);
+        market_client.test_setup_resolution(&market_id_bytes, &1u32, &1000, &1000);
+        close_dispute_window(&env, &market_client);
+
+        let user = Address::generate(&env);
+        // User predicted NO (0), Winner is YES (1)
+        market_client.test_set_prediction(&user, &0u32, &500);
+
+        // 10% of 500 = 50
+        let refund = market_client.refund_losing_bet(&user, &market_id_bytes);
+        assert_eq!(refund, 50);
+        assert_eq!(usdc_client.balance(&user), 50);
+
+        // Second refund is rejected
+        let result = market_client.try_refund_losing_bet(&user, &market_id_bytes);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "Loser refunds disabled")]
+    fn test_refund_losing_bet_disabled_by_default() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let market_id_bytes = BytesN::from_array(&env, &[0; 32]);
+        let market_contract_id = env.register(PredictionMarket, ());
+        let market_client = PredictionMarketClient::new(&env, &market_contract_id);
+        let oracle_contract_id = env.register(MockOracle, ());
+        let token_admin = Address::generate(&env);
+        let usdc_client = create_token_contract(&env, &token_admin);
+
+        market_client.initialize(
+            &market_id_bytes,
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &usdc_client.address,
+            &oracle_contract_id,
+            &2000,
+            &3000,
+            &MarketConfig::default(),
+        );
+
+        usdc_client.mint(&market_contract_id, &2000);
+        market_client.test_setup_resolution(&market_id_bytes, &1u32, &1000, &1000);
+        close_dispute_window(&env, &market_client);
+
+        let user = Address::generate(&env);
+        market_client.test_set_prediction(&user, &0u32, &500);
+
+        market_client.refund_losing_bet(&user, &market_id_bytes);
+    }
+
+    #[test]
+    #[should_panic(expected = "Loser refund exceeds maximum")]
+    fn test_loser_refund_above_maximum_rejected() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let market_contract_id = env.register(PredictionMarket, ());
+        let market_client = PredictionMarketClient::new(&env, &market_contract_id);
+
+        market_client.initialize(
+            &BytesN::from_array(&env, &[0; 32]),
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &2000,
+            &3000,
+            &MarketConfig {
+                loser_refund_bps: 2001,
+                ..Default::default()
+            },
+        );
+    }
+
+    // ============================================================================
+    // USER STATE OVERLAY TESTS
+    // ============================================================================
+
+    #[test]
+    fn test_market_state_for_user_participant_vs_non_participant() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let market_id_bytes = BytesN::from_array(&env, &[0; 32]);
+        let market_contract_id = env.register(PredictionMarket, ());
+        let market_client = PredictionMarketClient::new(&env, &market_contract_id);
+
+        market_client.initialize(
+            &market_id_bytes,
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &2000,
+            &3000,
+            &MarketConfig::default(),
+        );
+        market_client.test_setup_resolution(&market_id_bytes, &1u32, &1000, &1000);
+
+        let winner = Address::generate(&env);
+        market_client.test_set_prediction(&winner, &1u32, &500);
+        let bystander = Address::generate(&env);
+
+        let participant = market_client.get_market_state_for_user(&market_id_bytes, &winner);
+        let outsider = market_client.get_market_state_for_user(&market_id_bytes, &bystander);
+
+        // Shared base state
+        assert_eq!(participant.state, STATE_RESOLVED);
+        assert_eq!(participant.winning_outcome, Some(1));
+        assert_eq!(participant.resolution_time, outsider.resolution_time);
+        assert_eq!(participant.winning_outcome, outsider.winning_outcome);
+
+        // Winner overlay: 500/1000 of a 2000 pool, less 10% fee
+        assert!(participant.has_prediction);
+        assert_eq!(participant.outcome, 1);
+        assert_eq!(participant.amount, 500);
+        assert!(!participant.claimed);
+        assert_eq!(participant.payout, 900);
+
+        // Non-participant gets base state only
+        assert!(!outsider.has_prediction);
+        assert_eq!(outsider.amount, 0);
+        assert_eq!(outsider.payout, 0);
+    }
+
+    // ============================================================================
+    // ODDS DIVERGENCE TESTS
+    // ============================================================================
+
+    // Mock AMM reporting fixed odds
+    #[contract]
+    pub struct MockAmm;
+
+    #[contractimpl]
+    impl MockAmm {
+        pub fn get_odds(_env: Env, _market_id: BytesN<32>) -> (u32, u32) {
+            (7000, 3000)
+        }
+    }
+
+    #[test]
+    fn test_get_odds_divergence_against_amm() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let market_id_bytes = BytesN::from_array(&env, &[0; 32]);
+        let market_contract_id = env.register(PredictionMarket, ());
+        let market_client = PredictionMarketClient::new(&env, &market_contract_id);
+        let amm_contract_id = env.register(MockAmm, ());
+
+        market_client.initialize(
+            &market_id_bytes,
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &2000,
+            &3000,
+            &MarketConfig::default(),
+        );
+
+        // Empty pools price at 50/50
+        assert_eq!(
+            market_client.get_odds_divergence(&market_id_bytes, &amm_contract_id),
+            2000
+        );
+
+        // Pooled market at 60% YES vs AMM at 70% YES
+        env.as_contract(&market_contract_id, || {
+            env.storage()
+                .persistent()
+                .set(&Symbol::new(&env, YES_POOL_KEY), &600i128);
+            env.storage()
+                .persistent()
+                .set(&Symbol::new(&env, NO_POOL_KEY), &400i128);
+        });
+        assert_eq!(market_client.get_odds(), (6000, 4000));
+        assert_eq!(
+            market_client.get_odds_divergence(&market_id_bytes, &amm_contract_id),
+            1000
+        );
+    }
+
+    // ============================================================================
+    // USER P&L TESTS
+    // ============================================================================
+
+    #[test]
+    fn test_get_user_pnl_winner_and_loser() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let market_id_bytes = BytesN::from_array(&env, &[0; 32]);
+        let market_contract_id = env.register(PredictionMarket, ());
+        let market_client = PredictionMarketClient::new(&env, &market_contract_id);
+
+        market_client.initialize(
+            &market_id_bytes,
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &2000,
+            &3000,
+            &MarketConfig {
+                loser_refund_bps: 1000, // 10%
+                ..Default::default()
+            },
+        );
+        market_client.test_setup_resolution(&market_id_bytes, &1u32, &1000, &1000);
+
+        // Winner: 500 of 1000 winning shares of the 1900 left after the 100
+        // refund reserve -> gross 950, net 855 after 10% fee
+        let winner = Address::generate(&env);
+        market_client.test_set_prediction(&winner, &1u32, &500);
+        assert_eq!(market_client.get_user_pnl(&winner, &market_id_bytes), 355);
+
+        // Loser: loses 500 minus 10% consolation refund
+        let loser = Address::generate(&env);
+        market_client.test_set_prediction(&loser, &0u32, &500);
+        assert_eq!(market_client.get_user_pnl(&loser, &market_id_bytes), -450);
+
+        // Non-participant
+        let bystander = Address::generate(&env);
+        assert_eq!(market_client.get_user_pnl(&bystander, &market_id_bytes), 0);
+    }
+
+    // ============================================================================
+    // FACTORY GATING TESTS
+    // ============================================================================
+
+    #[test]
+    fn test_set_oracle_factory_only() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let market_id_bytes = BytesN::from_array(&env, &[0; 32]);
+        let market_contract_id = env.register(PredictionMarket, ());
+        let market_client = PredictionMarketClient::new(&env, &market_contract_id);
+
+        let factory = Address::generate(&env);
+        market_client.initialize(
+            &market_id_bytes,
+            &Address::generate(&env),
+            &factory,
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &2000,
+            &3000,
+            &MarketConfig::default(),
+        );
+        assert_eq!(market_client.get_factory(), factory);
+
+        let new_oracle = Address::generate(&env);
+
+        // Non-factory caller is rejected
+        let stranger = Address::generate(&env);
+        let result = market_client
+            .mock_auths(&[MockAuth {
+                address: &stranger,
+                invoke: &MockAuthInvoke {
+                    contract: &market_contract_id,
+                    fn_name: "set_oracle",
+                    args: (new_oracle.clone(),).into_val(&env),
+                    sub_invokes: &[],
+                },
+            }])
+            .try_set_oracle(&new_oracle);
+        assert!(result.is_err());
+
+        // Factory succeeds
+        market_client
+            .mock_auths(&[MockAuth {
+                address: &factory,
+                invoke: &MockAuthInvoke {
+                    contract: &market_contract_id,
+                    fn_name: "set_oracle",
+                    args: (new_oracle.clone(),).into_val(&env),
+                    sub_invokes: &[],
+                },
+            }])
+            .set_oracle(&new_oracle);
+
+        let stored: Address = env.as_contract(&market_contract_id, || {
+            env.storage()
+                .persistent()
+                .get(&Symbol::new(&env, ORACLE_KEY))
+                .unwrap()
+        });
+        assert_eq!(stored, new_oracle);
+    }
+
+    // ============================================================================
+    // MARKET PHASE TESTS
+    // ============================================================================
+
+    #[test]
+    fn test_market_phase_lifecycle() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let market_id_bytes = BytesN::from_array(&env, &[0; 32]);
+        let market_contract_id = env.register(PredictionMarket, ());
+        let market_client = PredictionMarketClient::new(&env, &market_contract_id);
+        let oracle_contract_id = env.register(MockOracle, ());
+
+        let closing_time = 2000;
+        let resolution_time = 3000;
+
+        env.ledger().with_mut(|li| {
+            li.timestamp = 1000;
+        });
+
+        market_client.initialize(
+            &market_id_bytes,
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &oracle_contract_id,
+            &closing_time,
+            &resolution_time,
+            &MarketConfig::default(),
+        );
+        seed_pools(&env, &market_contract_id, 600, 400);
+
+        let phase = |now: u64| market_client.get_market_phase(&now);
+
+        assert_eq!(phase(1000), Symbol::new(&env, "BETTING"));
+        // Past closing time but not yet closed: reveal window already running
+        assert_eq!(phase(closing_time), Symbol::new(&env, "REVEAL"));
+
+        env.ledger().with_mut(|li| {
+            li.timestamp = closing_time + 10;
+        });
+        market_client.close_market(&market_id_bytes);
+        assert_eq!(phase(closing_time + 10), Symbol::new(&env, "REVEAL"));
+        assert_eq!(
+            phase(resolution_time),
+            Symbol::new(&env, "AWAITING_RESOLUTION")
+        );
+
+        env.ledger().with_mut(|li| {
+            li.timestamp = resolution_time + 10;
+        });
+        market_client.resolve_market(&market_id_bytes);
+
+        let dispute_end = resolution_time + 10 + DEFAULT_DISPUTE_WINDOW_SECONDS;
+        assert_eq!(
+            phase(resolution_time + 10),
+            Symbol::new(&env, "DISPUTE_WINDOW")
+        );
+        assert_eq!(phase(dispute_end), Symbol::new(&env, "CLAIMABLE"));
+        assert_eq!(
+            phase(dispute_end + CLAIM_PERIOD_SECONDS),
+            Symbol::new(&env, "SETTLED")
+        );
+    }
+
+    #[test]
+    fn test_market_phase_cancelled() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let market_id_bytes = BytesN::from_array(&env, &[0; 32]);
+        let market_contract_id = env.register(PredictionMarket, ());
+        let market_client = PredictionMarketClient::new(&env, &market_contract_id);
+
+        market_client.initialize(
+            &market_id_bytes,
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &2000,
+            &3000,
+            &MarketConfig::default(),
+        );
+
+        env.as_contract(&market_contract_id, || {
+            env.storage()
+                .persistent()
+                .set(&Symbol::new(&env, MARKET_STATE_KEY), &STATE_CANCELLED);
+        });
+
+        assert_eq!(
+            market_client.get_market_phase(&1000),
+            Symbol::new(&env, "CANCELLED")
+        );
     }
 
     // ============================================================================
-    // CLAIM WINNINGS TESTS
-    // ============================================================================
+    // RESOLVE MARKET TESTS
+    // ============================================================================
+
+    #[test]
+    fn test_resolve_market_happy_path() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        // Register contracts
+        let market_id_bytes = BytesN::from_array(&env, &[0; 32]);
+        let market_contract_id = env.register(PredictionMarket, ());
+        let market_client = PredictionMarketClient::new(&env, &market_contract_id);
+
+        let oracle_contract_id = env.register(MockOracle, ());
+
+        let creator = Address::generate(&env);
+        let factory = Address::generate(&env);
+        let usdc = Address::generate(&env);
+
+        // Setup times
+        let start_time = 1000;
+        let closing_time = 2000;
+        let resolution_time = 3000;
+
+        env.ledger().with_mut(|li| {
+            li.timestamp = start_time;
+        });
+
+        // Initialize market
+        market_client.initialize(
+            &market_id_bytes,
+            &creator,
+            &factory,
+            &usdc,
+            &oracle_contract_id,
+            &closing_time,
+            &resolution_time,
+            &MarketConfig::default(),
+        );
+        seed_pools(&env, &market_contract_id, 600, 400);
+
+        // Advance time to closing
+        env.ledger().with_mut(|li| {
+            li.timestamp = closing_time + 10;
+        });
+
+        // Close market
+        market_client.close_market(&market_id_bytes);
+
+        // Advance time to resolution
+        env.ledger().with_mut(|li| {
+            li.timestamp = resolution_time + 10;
+        });
+
+        // Resolve market
+        market_client.resolve_market(&market_id_bytes);
+    }
+
+    /// Helper: Resolve a market requiring 2 participants after `participants` reveals
+    fn resolve_with_participants(env: &Env, participants: u32) -> PredictionMarketClient<'_> {
+        env.mock_all_auths();
+
+        let market_id_bytes = BytesN::from_array(env, &[0; 32]);
+        let market_contract_id = env.register(PredictionMarket, ());
+        let market_client = PredictionMarketClient::new(env, &market_contract_id);
+        let oracle_contract_id = env.register(MockOracle, ());
+
+        market_client.initialize(
+            &market_id_bytes,
+            &Address::generate(env),
+            &Address::generate(env),
+            &Address::generate(env),
+            &oracle_contract_id,
+            &2000,
+            &3000,
+            &MarketConfig {
+                min_participants: 2,
+                ..Default::default()
+            },
+        );
+        seed_pools(env, &market_contract_id, 600, 400);
+        env.as_contract(&market_contract_id, || {
+            env.storage()
+                .persistent()
+                .set(&Symbol::new(env, PARTICIPANT_COUNT_KEY), &participants);
+        });
+
+        env.ledger().with_mut(|li| {
+            li.timestamp = 2010;
+        });
+        market_client.close_market(&market_id_bytes);
+
+        env.ledger().with_mut(|li| {
+            li.timestamp = 3010;
+        });
+        market_client.resolve_market(&market_id_bytes);
+        market_client
+    }
+
+    #[test]
+    fn test_resolve_market_below_min_participants_cancels() {
+        let env = Env::default();
+        let market_client = resolve_with_participants(&env, 1);
+        assert_eq!(
+            market_client.get_market_state_value(),
+            Some(STATE_CANCELLED)
+        );
+        assert_eq!(market_client.get_winning_outcome(), None);
+    }
+
+    #[test]
+    fn test_resolve_market_at_min_participants_resolves() {
+        let env = Env::default();
+        let market_client = resolve_with_participants(&env, 2);
+        assert_eq!(market_client.get_market_state_value(), Some(STATE_RESOLVED));
+        assert_eq!(market_client.get_winning_outcome(), Some(1));
+    }
 
     #[test]
-    fn test_claim_winnings_happy_path() {
+    fn test_get_resolution_details_matches_resolved_pools() {
         let env = Env::default();
+        let market_client = resolve_with_participants(&env, 2);
+        let market_id = BytesN::from_array(&env, &[0; 32]);
+
+        // YES won on seeded pools of 600 YES / 400 NO
+        assert_eq!(
+            market_client.get_resolution_details(&market_id),
+            (600, 400, 1000)
+        );
+    }
+
+    /// Helper: Close and advance a binary market to resolution with `outcome` from the oracle
+    fn resolve_binary_market(env: &Env, outcome: u32) -> PredictionMarketClient<'_> {
         env.mock_all_auths();
 
-        let market_id_bytes = BytesN::from_array(&env, &[0; 32]);
+        let market_id_bytes = BytesN::from_array(env, &[0; 32]);
         let market_contract_id = env.register(PredictionMarket, ());
-        let market_client = PredictionMarketClient::new(&env, &market_contract_id);
-        let oracle_contract_id = env.register(MockOracle, ());
-
-        let token_admin = Address::generate(&env);
-        let usdc_client = create_token_contract(&env, &token_admin);
-        let usdc_address = usdc_client.address.clone();
+        let market_client = PredictionMarketClient::new(env, &market_contract_id);
 
-        let creator = Address::generate(&env);
-        let user = Address::generate(&env);
+        let oracle_contract_id = env.register(MockOracle, ());
+        MockOracleClient::new(env, &oracle_contract_id).set_outcome_value(&outcome);
 
         market_client.initialize(
             &market_id_bytes,
-            &creator,
-            &Address::generate(&env),
-            &usdc_address,
+            &Address::generate(env),
+            &Address::generate(env),
+            &Address::generate(env),
             &oracle_contract_id,
             &2000,
             &3000,
+            &MarketConfig::default(),
         );
+        seed_pools(env, &market_contract_id, 600, 400);
 
-        // Mint USDC to contract to simulate pot
-        usdc_client.mint(&market_contract_id, &1000);
+        env.ledger().with_mut(|li| {
+            li.timestamp = 2010;
+        });
+        market_client.close_market(&market_id_bytes);
 
-        // Setup State manually (Simulate Resolution)
-        market_client.test_setup_resolution(
-            &market_id_bytes,
-            &1u32,     // Winning outcome YES
-            &1000i128, // Winner shares
-            &0i128,    // Loser shares
-        );
+        env.ledger().with_mut(|li| {
+            li.timestamp = 3010;
+        });
+        market_client.resolve_market(&market_id_bytes);
+        market_client
+    }
 
-        // Setup User Prediction
-        market_client.test_set_prediction(
-            &user, &1u32,     // Voted YES
-            &1000i128, // Amount
+    #[test]
+    fn test_resolve_market_accepts_outcome_within_num_outcomes() {
+        let env = Env::default();
+        let market_client = resolve_binary_market(&env, 0);
+        assert_eq!(market_client.get_market_state_value(), Some(STATE_RESOLVED));
+        assert_eq!(market_client.get_winning_outcome(), Some(0));
+        // NO pool wins and the YES pool is distributed to it
+        assert_eq!(
+            market_client.get_resolution_details(&BytesN::from_array(&env, &[0; 32])),
+            (400, 600, PROTOCOL_FEE_BPS)
         );
+    }
 
-        // Claim
-        let payout = market_client.claim_winnings(&user, &market_id_bytes);
-
-        // Expect 900 (1000 - 10% fee)
-        assert_eq!(payout, 900);
+    #[test]
+    #[should_panic(expected = "invalid oracle outcome")]
+    fn test_resolve_market_rejects_outcome_beyond_num_outcomes() {
+        let env = Env::default();
+        resolve_binary_market(&env, 2);
+    }
 
-        // Verify transfer happened
-        assert_eq!(usdc_client.balance(&user), 900);
+    #[test]
+    #[should_panic(expected = "multi-outcome markets not supported")]
+    fn test_initialize_rejects_outcomes_without_pools() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let market_client = PredictionMarketClient::new(&env, &env.register(PredictionMarket, ()));
+        market_client.initialize(
+            &BytesN::from_array(&env, &[0; 32]),
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &2000,
+            &3000,
+            &MarketConfig {
+                num_outcomes: 3,
+                ..Default::default()
+            },
+        );
     }
 
     #[test]
-    #[should_panic(expected = "User did not predict winning outcome")]
-    fn test_claim_winnings_loser_cannot_claim() {
+    fn test_resolve_market_rejects_stale_attestations() {
         let env = Env::default();
         env.mock_all_auths();
 
         let market_id_bytes = BytesN::from_array(&env, &[0; 32]);
         let market_contract_id = env.register(PredictionMarket, ());
         let market_client = PredictionMarketClient::new(&env, &market_contract_id);
+
         let oracle_contract_id = env.register(MockOracle, ());
-        let token_admin = Address::generate(&env);
-        let usdc_client = create_token_contract(&env, &token_admin);
+        let oracle_client = MockOracleClient::new(&env, &oracle_contract_id);
 
         market_client.initialize(
             &market_id_bytes,
             &Address::generate(&env),
             &Address::generate(&env),
-            &usdc_client.address,
+            &Address::generate(&env),
             &oracle_contract_id,
             &2000,
             &3000,
+            &MarketConfig {
+                max_attestation_age: 500,
+                ..Default::default()
+            },
         );
+        seed_pools(&env, &market_contract_id, 600, 400);
 
-        market_client.test_setup_resolution(&market_id_bytes, &1u32, &1000, &1000);
+        env.ledger().with_mut(|li| {
+            li.timestamp = 2010;
+        });
+        market_client.close_market(&market_id_bytes);
 
-        let user = Address::generate(&env);
-        // User predicted NO (0), Winner is YES (1)
-        market_client.test_set_prediction(&user, &0u32, &500);
+        env.ledger().with_mut(|li| {
+            li.timestamp = 3010;
+        });
 
-        market_client.claim_winnings(&user, &market_id_bytes);
+        // Consensus formed 1000s before resolution_time
+        oracle_client.set_attested_at(&2000);
+        let result = market_client.try_resolve_market(&market_id_bytes);
+        assert!(result.is_err());
+
+        // Fresh consensus within the allowed age resolves
+        oracle_client.set_attested_at(&2600);
+        market_client.resolve_market(&market_id_bytes);
+        assert_eq!(market_client.get_market_state_value(), Some(STATE_RESOLVED));
     }
 
     #[test]
-    #[should_panic(expected = "Market not resolved")]
-    fn test_cannot_claim_before_resolution() {
+    fn test_force_resolve_after_timeout_cancels_and_refunds() {
         let env = Env::default();
         env.mock_all_auths();
 
@@ -939,6 +3797,8 @@ mod tests {
         let market_contract_id = env.register(PredictionMarket, ());
         let market_client = PredictionMarketClient::new(&env, &market_contract_id);
         let oracle_contract_id = env.register(MockOracle, ());
+        let oracle_client = MockOracleClient::new(&env, &oracle_contract_id);
+        oracle_client.set_consensus_status(&false);
         let token_admin = Address::generate(&env);
         let usdc_client = create_token_contract(&env, &token_admin);
 
@@ -950,18 +3810,42 @@ mod tests {
             &oracle_contract_id,
             &2000,
             &3000,
+            &MarketConfig {
+                consensus_timeout: 1000,
+                ..Default::default()
+            },
         );
 
         let user = Address::generate(&env);
         market_client.test_set_prediction(&user, &1u32, &500);
+        usdc_client.mint(&market_contract_id, &500);
 
-        // Market is still OPEN (not resolved) - should fail
-        market_client.claim_winnings(&user, &market_id_bytes);
+        // Still within the timeout
+        env.ledger().with_mut(|li| {
+            li.timestamp = 4000;
+        });
+        let result = market_client.try_force_resolve_after_timeout(&market_id_bytes);
+        assert!(result.is_err());
+
+        // Past the timeout with no consensus: market cancels
+        env.ledger().with_mut(|li| {
+            li.timestamp = 4001;
+        });
+        market_client.force_resolve_after_timeout(&market_id_bytes);
+        assert_eq!(
+            market_client.get_market_state_value(),
+            Some(STATE_CANCELLED)
+        );
+
+        // Full stake is refundable exactly once
+        assert_eq!(market_client.claim_refund(&user, &market_id_bytes), 500);
+        assert_eq!(usdc_client.balance(&user), 500);
+        let result = market_client.try_claim_refund(&user, &market_id_bytes);
+        assert!(result.is_err());
     }
 
     #[test]
-    #[should_panic(expected = "Winnings already claimed")]
-    fn test_cannot_double_claim() {
+    fn test_force_resolve_after_timeout_uses_plurality() {
         let env = Env::default();
         env.mock_all_auths();
 
@@ -969,31 +3853,38 @@ mod tests {
         let market_contract_id = env.register(PredictionMarket, ());
         let market_client = PredictionMarketClient::new(&env, &market_contract_id);
         let oracle_contract_id = env.register(MockOracle, ());
-        let token_admin = Address::generate(&env);
-        let usdc_client = create_token_contract(&env, &token_admin);
+        let oracle_client = MockOracleClient::new(&env, &oracle_contract_id);
+        oracle_client.set_consensus_status(&false);
+        oracle_client.set_vote_counts(&1u32, &2u32);
 
         market_client.initialize(
             &market_id_bytes,
             &Address::generate(&env),
             &Address::generate(&env),
-            &usdc_client.address,
+            &Address::generate(&env),
             &oracle_contract_id,
             &2000,
             &3000,
+            &MarketConfig {
+                consensus_timeout: 1000,
+                plurality_on_timeout: true,
+                ..Default::default()
+            },
         );
-        usdc_client.mint(&market_contract_id, &2000);
-
-        market_client.test_setup_resolution(&market_id_bytes, &1u32, &1000, &0);
+        seed_pools(&env, &market_contract_id, 600, 400);
 
-        let user = Address::generate(&env);
-        market_client.test_set_prediction(&user, &1u32, &1000);
+        env.ledger().with_mut(|li| {
+            li.timestamp = 4001;
+        });
+        market_client.force_resolve_after_timeout(&market_id_bytes);
 
-        market_client.claim_winnings(&user, &market_id_bytes);
-        market_client.claim_winnings(&user, &market_id_bytes); // Should fail
+        // NO had the plurality of oracle votes
+        assert_eq!(market_client.get_market_state_value(), Some(STATE_RESOLVED));
+        assert_eq!(market_client.test_get_winning_outcome(), Some(0));
     }
 
     #[test]
-    fn test_correct_payout_calculation() {
+    fn test_late_forced_resolution_can_still_be_disputed() {
         let env = Env::default();
         env.mock_all_auths();
 
@@ -1001,37 +3892,46 @@ mod tests {
         let market_contract_id = env.register(PredictionMarket, ());
         let market_client = PredictionMarketClient::new(&env, &market_contract_id);
         let oracle_contract_id = env.register(MockOracle, ());
-        let token_admin = Address::generate(&env);
-        let usdc_client = create_token_contract(&env, &token_admin);
+        let oracle_client = MockOracleClient::new(&env, &oracle_contract_id);
+        oracle_client.set_consensus_status(&false);
+        oracle_client.set_vote_counts(&1u32, &2u32);
 
+        // Consensus timeout longer than the default 7-day dispute window
+        let consensus_timeout = DEFAULT_DISPUTE_WINDOW_SECONDS + 86400;
         market_client.initialize(
             &market_id_bytes,
             &Address::generate(&env),
             &Address::generate(&env),
-            &usdc_client.address,
+            &Address::generate(&env),
             &oracle_contract_id,
             &2000,
             &3000,
+            &MarketConfig {
+                consensus_timeout,
+                plurality_on_timeout: true,
+                ..Default::default()
+            },
         );
-
-        // Total pool: 1000 (winners) + 500 (losers) = 1500
-        // User has 500 of 1000 winner shares
-        // Gross payout = (500 / 1000) * 1500 = 750
-        // Net payout (after 10% fee) = 750 - 75 = 675
-        usdc_client.mint(&market_contract_id, &1500);
-
-        market_client.test_setup_resolution(&market_id_bytes, &1u32, &1000, &500);
-
+        seed_pools(&env, &market_contract_id, 600, 400);
         let user = Address::generate(&env);
-        market_client.test_set_prediction(&user, &1u32, &500);
+        market_client.test_set_prediction(&user, &1u32, &600i128);
 
-        let payout = market_client.claim_winnings(&user, &market_id_bytes);
-        assert_eq!(payout, 675);
-        assert_eq!(usdc_client.balance(&user), 675);
+        let resolved_at = 3000 + consensus_timeout + 1;
+        env.ledger().with_mut(|li| {
+            li.timestamp = resolved_at;
+        });
+        market_client.force_resolve_after_timeout(&market_id_bytes);
+        assert_eq!(
+            market_client.get_dispute_window_end(),
+            Some(resolved_at + DEFAULT_DISPUTE_WINDOW_SECONDS)
+        );
+
+        market_client.dispute_market(&user, &market_id_bytes, &Symbol::new(&env, "wrong"));
+        assert_eq!(market_client.get_market_state_value(), Some(STATE_DISPUTED));
     }
 
     #[test]
-    fn test_multiple_winners_correct_payout() {
+    fn test_resolve_market_empty_pools_cancels() {
         let env = Env::default();
         env.mock_all_auths();
 
@@ -1039,42 +3939,36 @@ mod tests {
         let market_contract_id = env.register(PredictionMarket, ());
         let market_client = PredictionMarketClient::new(&env, &market_contract_id);
         let oracle_contract_id = env.register(MockOracle, ());
-        let token_admin = Address::generate(&env);
-        let usdc_client = create_token_contract(&env, &token_admin);
 
         market_client.initialize(
             &market_id_bytes,
             &Address::generate(&env),
             &Address::generate(&env),
-            &usdc_client.address,
+            &Address::generate(&env),
             &oracle_contract_id,
             &2000,
             &3000,
+            &MarketConfig::default(),
         );
 
-        // Total pool: 1000 (winners) + 1000 (losers) = 2000
-        // User1 has 600, User2 has 400 of 1000 winner shares
-        usdc_client.mint(&market_contract_id, &2000);
-
-        market_client.test_setup_resolution(&market_id_bytes, &1u32, &1000, &1000);
-
-        let user1 = Address::generate(&env);
-        let user2 = Address::generate(&env);
-        market_client.test_set_prediction(&user1, &1u32, &600);
-        market_client.test_set_prediction(&user2, &1u32, &400);
-
-        // User1: (600 / 1000) * 2000 = 1200, minus 10% = 1080
-        let payout1 = market_client.claim_winnings(&user1, &market_id_bytes);
-        assert_eq!(payout1, 1080);
+        env.ledger().with_mut(|li| {
+            li.timestamp = 2010;
+        });
+        market_client.close_market(&market_id_bytes);
+        env.ledger().with_mut(|li| {
+            li.timestamp = 3010;
+        });
+        market_client.resolve_market(&market_id_bytes);
 
-        // User2: (400 / 1000) * 2000 = 800, minus 10% = 720
-        let payout2 = market_client.claim_winnings(&user2, &market_id_bytes);
-        assert_eq!(payout2, 720);
+        assert_eq!(
+            market_client.get_market_state_value(),
+            Some(STATE_CANCELLED)
+        );
+        assert_eq!(market_client.get_winning_outcome(), None);
     }
 
     #[test]
-    #[should_panic(expected = "No prediction found for user")]
-    fn test_no_prediction_cannot_claim() {
+    fn test_resolve_market_empty_winning_side_refunds() {
         let env = Env::default();
         env.mock_all_auths();
 
@@ -1093,70 +3987,30 @@ mod tests {
             &oracle_contract_id,
             &2000,
             &3000,
+            &MarketConfig::default(),
         );
 
-        market_client.test_setup_resolution(&market_id_bytes, &1u32, &1000, &0);
-
+        // Only NO was backed, but the oracle reports YES
         let user = Address::generate(&env);
-        // User has no prediction
-        market_client.claim_winnings(&user, &market_id_bytes);
-    }
-
-    // ============================================================================
-    // RESOLVE MARKET TESTS
-    // ============================================================================
-
-    #[test]
-    fn test_resolve_market_happy_path() {
-        let env = Env::default();
-        env.mock_all_auths();
-
-        // Register contracts
-        let market_id_bytes = BytesN::from_array(&env, &[0; 32]);
-        let market_contract_id = env.register(PredictionMarket, ());
-        let market_client = PredictionMarketClient::new(&env, &market_contract_id);
-
-        let oracle_contract_id = env.register(MockOracle, ());
-
-        let creator = Address::generate(&env);
-        let factory = Address::generate(&env);
-        let usdc = Address::generate(&env);
-
-        // Setup times
-        let start_time = 1000;
-        let closing_time = 2000;
-        let resolution_time = 3000;
-
-        env.ledger().with_mut(|li| {
-            li.timestamp = start_time;
-        });
-
-        // Initialize market
-        market_client.initialize(
-            &market_id_bytes,
-            &creator,
-            &factory,
-            &usdc,
-            &oracle_contract_id,
-            &closing_time,
-            &resolution_time,
-        );
+        market_client.test_set_prediction(&user, &0u32, &500);
+        seed_pools(&env, &market_contract_id, 0, 500);
+        usdc_client.mint(&market_contract_id, &500);
 
-        // Advance time to closing
         env.ledger().with_mut(|li| {
-            li.timestamp = closing_time + 10;
+            li.timestamp = 2010;
         });
-
-        // Close market
         market_client.close_market(&market_id_bytes);
-
-        // Advance time to resolution
         env.ledger().with_mut(|li| {
-            li.timestamp = resolution_time + 10;
+            li.timestamp = 3010;
         });
-
-        // Resolve market
         market_client.resolve_market(&market_id_bytes);
+
+        assert_eq!(
+            market_client.get_market_state_value(),
+            Some(STATE_CANCELLED)
+        );
+        assert_eq!(market_client.claim_refund(&user, &market_id_bytes), 500);
+        assert_eq!(usdc_client.balance(&user), 500);
     }
 
     #[test]
@@ -1179,7 +4033,9 @@ mod tests {
             &oracle_contract_id,
             &2000,
             &3000,
+            &MarketConfig::default(),
         );
+        seed_pools(&env, &market_contract_id, 600, 400);
 
         env.ledger().with_mut(|li| {
             li.timestamp = 2010;
@@ -1218,6 +4074,7 @@ mod tests {
             &oracle_contract_id,
             &2000,
             &resolution_time,
+            &MarketConfig::default(),
         );
 
         // Advance time but NOT enough
@@ -1250,6 +4107,7 @@ mod tests {
             &oracle_contract_id,
             &2000,
             &resolution_time,
+            &MarketConfig::default(),
         );
 
         // Advance time to closing
@@ -1268,4 +4126,34 @@ mod tests {
 
         market_client.resolve_market(&market_id_bytes);
     }
+
+    #[test]
+    #[should_panic(expected = "oracle not reachable")]
+    fn test_resolve_against_non_oracle_address() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let market_id_bytes = BytesN::from_array(&env, &[0; 32]);
+        let market_contract_id = env.register(PredictionMarket, ());
+        let market_client = PredictionMarketClient::new(&env, &market_contract_id);
+
+        // The AMM mock is a live contract, just not an oracle
+        let not_an_oracle = env.register(MockAmm, ());
+        market_client.initialize(
+            &market_id_bytes,
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &not_an_oracle,
+            &2000,
+            &3000,
+            &MarketConfig::default(),
+        );
+
+        env.ledger().with_mut(|li| li.timestamp = 2010);
+        market_client.close_market(&market_id_bytes);
+        env.ledger().with_mut(|li| li.timestamp = 3010);
+
+        market_client.resolve_market(&market_id_bytes);
+    }
 }
@@ -1,12 +1,137 @@
 // contract/src/oracle.rs - Oracle & Market Resolution Contract Implementation
 // Handles multi-source oracle consensus for market resolution
 
-use soroban_sdk::{contract, contractimpl, Address, BytesN, Env, Symbol, Vec};
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, token, Address, BytesN, Env, Symbol, Vec,
+};
 
 // Storage keys
 const ADMIN_KEY: &str = "admin";
 const REQUIRED_CONSENSUS_KEY: &str = "required_consensus";
 const ORACLE_COUNT_KEY: &str = "oracle_count";
+const USDC_KEY: &str = "usdc";
+const TREASURY_KEY: &str = "treasury";
+const CHALLENGE_BOND_KEY: &str = "challenge_bond";
+const MIN_ORACLE_STAKE_KEY: &str = "min_oracle_stake";
+const SLASH_BPS_KEY: &str = "slash_bps";
+const SLASH_TO_CHALLENGER_KEY: &str = "slash_to_challenger";
+const UNIQUE_NAMES_KEY: &str = "unique_oracle_names";
+const ORACLE_LIST_KEY: &str = "oracle_list";
+const CONSENSUS_MODE_KEY: &str = "consensus_mode";
+const THRESHOLD_PCT_KEY: &str = "threshold_pct";
+const MIN_VOTERS_KEY: &str = "min_voters";
+const REQUESTED_MARKETS_KEY: &str = "requested_markets";
+const REQUESTED_MARKET_COUNT_KEY: &str = "requested_market_count";
+const FACTORY_KEY: &str = "factory";
+const CHALLENGE_WINDOW_KEY: &str = "challenge_window";
+
+/// Accuracy points lost by an oracle when a challenge against it is upheld
+const CHALLENGE_ACCURACY_PENALTY: u32 = 10;
+/// Waiting period after deregistration before an oracle can withdraw its stake (7 days)
+const STAKE_WITHDRAWAL_COOLDOWN: u64 = 7 * 24 * 60 * 60;
+/// Default period after consensus during which attestations can be challenged (24 hours)
+const DEFAULT_CHALLENGE_WINDOW_SECONDS: u64 = 24 * 60 * 60;
+
+/// Error codes returned by oracle registration, attestation and consensus
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum OracleError {
+    /// Oracle contract has not been initialized
+    NotInitialized = 1,
+    /// Maximum number of registered oracles reached
+    MaxOraclesReached = 2,
+    /// Oracle is already registered
+    OracleAlreadyRegistered = 3,
+    /// Oracle name is already used by another oracle
+    OracleNameTaken = 4,
+    /// Oracle is not registered or has been deregistered
+    OracleNotRegistered = 5,
+    /// Attestation result must be 0 or 1
+    InvalidAttestationResult = 6,
+    /// Oracle already attested for this market
+    AlreadyAttested = 7,
+    /// No consensus result stored for this market
+    ConsensusNotFound = 8,
+    /// Consensus has not been reached for this market
+    ConsensusNotReached = 9,
+    /// An attestation for this market has an open challenge
+    ChallengeActive = 10,
+    /// Market result has already been finalized
+    AlreadyFinalized = 11,
+    /// Market has already requested resolution
+    ResolutionAlreadyRequested = 12,
+    /// Market has not requested resolution, or was registered by another address
+    ResolutionNotRequested = 13,
+    /// Caller is not the market factory this oracle serves
+    UnauthorizedFactory = 14,
+    /// Consensus is still inside its challenge window
+    ChallengeWindowOpen = 15,
+}
+
+/// How `check_consensus` interprets the consensus threshold
+#[contracttype]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ConsensusMode {
+    /// The winning outcome needs `required_consensus` votes
+    #[default]
+    AbsoluteCount,
+    /// The winning outcome needs `threshold_pct` percent of cast votes
+    Percentage,
+}
+
+/// Optional oracle parameters supplied at initialization
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct OracleConfig {
+    /// USDC token used for challenge bonds
+    pub usdc_token: Option<Address>,
+    /// Treasury receiving forfeited bonds
+    pub treasury: Option<Address>,
+    /// Market factory allowed to register its deployed markets for resolution
+    pub factory: Option<Address>,
+    /// USDC bond a challenger must post (0 = no bond required)
+    pub challenge_bond: i128,
+    /// USDC stake an oracle must lock to register (0 = no stake required)
+    pub min_oracle_stake: i128,
+    /// Share of the stake slashed on an upheld challenge, in basis points (0 = no slashing)
+    pub slash_bps: u32,
+    /// Pay slashed stake to the challenger as a reward instead of the treasury
+    pub slash_to_challenger: bool,
+    /// Reject registrations whose oracle name is already in use
+    pub unique_oracle_names: bool,
+    /// Absolute vote count or percentage-of-votes consensus
+    pub consensus_mode: ConsensusMode,
+    /// Percentage mode: share of cast votes the winner needs (1-100)
+    pub threshold_pct: u32,
+    /// Percentage mode: votes that must be cast before consensus can form
+    pub min_voters: u32,
+    /// Seconds after consensus before it can be finalized (0 = 24 hours)
+    pub challenge_window: u64,
+}
+
+/// Consensus and finality status of a market in one read
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MarketResult {
+    pub consensus_reached: bool,
+    /// Finalized outcome if finalized, else the current consensus outcome
+    pub outcome: u32,
+    pub finalized: bool,
+    pub voter_count: u32,
+    /// Whether any attestation for the market has an open challenge
+    pub challenge_active: bool,
+}
+
+/// Open challenge against an oracle's attestation
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Challenge {
+    pub challenger: Address,
+    pub reason: Symbol,
+    pub bond: i128,
+    pub timestamp: u64,
+}
 
 /// ORACLE MANAGER - Manages oracle consensus
 #[contract]
@@ -15,10 +140,35 @@ pub struct OracleManager;
 #[contractimpl]
 impl OracleManager {
     /// Initialize oracle system with validator set
-    pub fn initialize(env: Env, admin: Address, required_consensus: u32) {
+    pub fn initialize(env: Env, admin: Address, required_consensus: u32, config: OracleConfig) {
         // Verify admin signature
         admin.require_auth();
 
+        // Validate challenge bond configuration
+        if config.challenge_bond < 0 {
+            panic!("Challenge bond cannot be negative");
+        }
+        if config.challenge_bond > 0 && (config.usdc_token.is_none() || config.treasury.is_none()) {
+            panic!("Challenge bond requires usdc token and treasury");
+        }
+
+        // Validate oracle stake configuration
+        if config.min_oracle_stake < 0 {
+            panic!("Oracle stake cannot be negative");
+        }
+        if config.min_oracle_stake > 0 && (config.usdc_token.is_none() || config.treasury.is_none())
+        {
+            panic!("Oracle stake requires usdc token and treasury");
+        }
+        if config.slash_bps > 10000 {
+            panic!("Slash fraction exceeds 100%");
+        }
+        if config.consensus_mode == ConsensusMode::Percentage
+            && (config.threshold_pct == 0 || config.threshold_pct > 100)
+        {
+            panic!("Invalid consensus percentage");
+        }
+
         // Store admin
         env.storage()
             .persistent()
@@ -35,6 +185,60 @@ impl OracleManager {
             .persistent()
             .set(&Symbol::new(&env, ORACLE_COUNT_KEY), &0u32);
 
+        // Store challenge bond settings
+        if let Some(usdc_token) = config.usdc_token {
+            env.storage()
+                .persistent()
+                .set(&Symbol::new(&env, USDC_KEY), &usdc_token);
+        }
+        if let Some(treasury) = config.treasury {
+            env.storage()
+                .persistent()
+                .set(&Symbol::new(&env, TREASURY_KEY), &treasury);
+        }
+        if let Some(factory) = config.factory {
+            env.storage()
+                .persistent()
+                .set(&Symbol::new(&env, FACTORY_KEY), &factory);
+        }
+        env.storage().persistent().set(
+            &Symbol::new(&env, CHALLENGE_BOND_KEY),
+            &config.challenge_bond,
+        );
+        env.storage().persistent().set(
+            &Symbol::new(&env, MIN_ORACLE_STAKE_KEY),
+            &config.min_oracle_stake,
+        );
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, SLASH_BPS_KEY), &config.slash_bps);
+        env.storage().persistent().set(
+            &Symbol::new(&env, SLASH_TO_CHALLENGER_KEY),
+            &config.slash_to_challenger,
+        );
+        env.storage().persistent().set(
+            &Symbol::new(&env, UNIQUE_NAMES_KEY),
+            &config.unique_oracle_names,
+        );
+        env.storage().persistent().set(
+            &Symbol::new(&env, CONSENSUS_MODE_KEY),
+            &config.consensus_mode,
+        );
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, THRESHOLD_PCT_KEY), &config.threshold_pct);
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, MIN_VOTERS_KEY), &config.min_voters);
+        let challenge_window = if config.challenge_window == 0 {
+            DEFAULT_CHALLENGE_WINDOW_SECONDS
+        } else {
+            config.challenge_window
+        };
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, CHALLENGE_WINDOW_KEY), &challenge_window);
+
         // Emit initialization event
         env.events().publish(
             (Symbol::new(&env, "oracle_initialized"),),
@@ -43,13 +247,17 @@ impl OracleManager {
     }
 
     /// Register a new oracle node
-    pub fn register_oracle(env: Env, oracle: Address, oracle_name: Symbol) {
+    pub fn register_oracle(
+        env: Env,
+        oracle: Address,
+        oracle_name: Symbol,
+    ) -> Result<(), OracleError> {
         // Require admin authentication
         let admin: Address = env
             .storage()
             .persistent()
             .get(&Symbol::new(&env, ADMIN_KEY))
-            .unwrap();
+            .ok_or(OracleError::NotInitialized)?;
         admin.require_auth();
 
         // Get current oracle count
@@ -61,7 +269,7 @@ impl OracleManager {
 
         // Validate total_oracles < max_oracles (max 10 oracles)
         if oracle_count >= 10 {
-            panic!("Maximum oracle limit reached");
+            return Err(OracleError::MaxOraclesReached);
         }
 
         // Create storage key for this oracle using the oracle address
@@ -71,7 +279,41 @@ impl OracleManager {
         let is_registered: bool = env.storage().persistent().has(&oracle_key);
 
         if is_registered {
-            panic!("Oracle already registered");
+            return Err(OracleError::OracleAlreadyRegistered);
+        }
+
+        // Reserve the oracle name when uniqueness is enforced
+        let unique_names: bool = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, UNIQUE_NAMES_KEY))
+            .unwrap_or(false);
+        if unique_names {
+            let name_taken_key = (Symbol::new(&env, "oracle_name_taken"), oracle_name.clone());
+            if env.storage().persistent().has(&name_taken_key) {
+                return Err(OracleError::OracleNameTaken);
+            }
+            env.storage().persistent().set(&name_taken_key, &oracle);
+        }
+
+        // Lock the required stake from the oracle into this contract
+        let min_stake: i128 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, MIN_ORACLE_STAKE_KEY))
+            .unwrap_or(0);
+        if min_stake > 0 {
+            oracle.require_auth();
+            let usdc_token: Address = env
+                .storage()
+                .persistent()
+                .get(&Symbol::new(&env, USDC_KEY))
+                .expect("usdc token not set");
+            let token_client = token::Client::new(&env, &usdc_token);
+            token_client.transfer(&oracle, env.current_contract_address(), &min_stake);
+
+            let stake_key = (Symbol::new(&env, "oracle_stake"), oracle.clone());
+            env.storage().persistent().set(&stake_key, &min_stake);
         }
 
         // Store oracle metadata
@@ -98,25 +340,249 @@ impl OracleManager {
             .persistent()
             .set(&Symbol::new(&env, ORACLE_COUNT_KEY), &(oracle_count + 1));
 
+        // Track the oracle in the registration list
+        let mut oracle_list: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ORACLE_LIST_KEY))
+            .unwrap_or(Vec::new(&env));
+        oracle_list.push_back(oracle.clone());
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, ORACLE_LIST_KEY), &oracle_list);
+
         // Emit OracleRegistered event
         env.events().publish(
             (Symbol::new(&env, "oracle_registered"),),
             (oracle, oracle_name, env.ledger().timestamp()),
         );
+
+        Ok(())
     }
 
     /// Deregister an oracle node
     ///
-    /// TODO: Deregister Oracle
-    /// - Require admin authentication
-    /// - Validate oracle is registered
-    /// - Remove oracle from active_oracles list
-    /// - Mark as inactive (don't delete, keep for history)
-    /// - Prevent oracle from submitting new attestations
-    /// - Don't affect existing attestations
-    /// - Emit OracleDeregistered(oracle_address, timestamp)
+    /// The oracle is marked inactive rather than deleted so its history and
+    /// existing attestations are kept. Its stake unlocks after the cooldown.
     pub fn deregister_oracle(env: Env, oracle: Address) {
-        todo!("See deregister oracle TODO above")
+        // Require admin authentication
+        let admin: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ADMIN_KEY))
+            .unwrap();
+        admin.require_auth();
+
+        Self::deactivate_oracle(&env, &oracle);
+    }
+
+    /// Withdraw an oracle's stake after deregistration and cooldown
+    pub fn withdraw_stake(env: Env, oracle: Address) -> i128 {
+        oracle.require_auth();
+
+        let deregistered_key = (Symbol::new(&env, "oracle_deregistered_at"), oracle.clone());
+        let deregistered_at: u64 = env
+            .storage()
+            .persistent()
+            .get(&deregistered_key)
+            .expect("Oracle still registered");
+        if env.ledger().timestamp() < deregistered_at + STAKE_WITHDRAWAL_COOLDOWN {
+            panic!("Stake withdrawal cooldown active");
+        }
+
+        let stake_key = (Symbol::new(&env, "oracle_stake"), oracle.clone());
+        let stake: i128 = env.storage().persistent().get(&stake_key).unwrap_or(0);
+        if stake <= 0 {
+            panic!("No stake to withdraw");
+        }
+        env.storage().persistent().remove(&stake_key);
+
+        let usdc_token: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, USDC_KEY))
+            .expect("usdc token not set");
+        let token_client = token::Client::new(&env, &usdc_token);
+        token_client.transfer(&env.current_contract_address(), &oracle, &stake);
+
+        env.events().publish(
+            (Symbol::new(&env, "StakeWithdrawn"),),
+            (oracle, stake, env.ledger().timestamp()),
+        );
+
+        stake
+    }
+
+    /// Get an oracle's locked stake
+    pub fn get_oracle_stake(env: Env, oracle: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&(Symbol::new(&env, "oracle_stake"), oracle))
+            .unwrap_or(0)
+    }
+
+    /// Get the ledger timestamp at which an oracle registered (0 if never registered)
+    pub fn get_oracle_registered_at(env: Env, oracle: Address) -> u64 {
+        env.storage()
+            .persistent()
+            .get(&(Symbol::new(&env, "oracle_timestamp"), oracle))
+            .unwrap_or(0)
+    }
+
+    /// Helper: Mark an oracle inactive and start its stake withdrawal cooldown
+    fn deactivate_oracle(env: &Env, oracle: &Address) {
+        let oracle_key = (Symbol::new(env, "oracle"), oracle.clone());
+        let is_registered: bool = env.storage().persistent().get(&oracle_key).unwrap_or(false);
+        if !is_registered {
+            panic!("Oracle not registered");
+        }
+
+        // Inactive oracles fail the registration check in submit_attestation
+        env.storage().persistent().set(&oracle_key, &false);
+
+        let oracle_count: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(env, ORACLE_COUNT_KEY))
+            .unwrap_or(0);
+        env.storage().persistent().set(
+            &Symbol::new(env, ORACLE_COUNT_KEY),
+            &oracle_count.saturating_sub(1),
+        );
+
+        let deregistered_key = (Symbol::new(env, "oracle_deregistered_at"), oracle.clone());
+        env.storage()
+            .persistent()
+            .set(&deregistered_key, &env.ledger().timestamp());
+
+        // Emit OracleDeregistered event
+        env.events().publish(
+            (Symbol::new(env, "OracleDeregistered"),),
+            (oracle.clone(), env.ledger().timestamp()),
+        );
+    }
+
+    /// Factory: Register a deployed market for resolution so oracles can discover it
+    ///
+    /// Only the factory set at initialization can register markets, binding each
+    /// market_id to the market contract it deployed and fixing its
+    /// resolution_time.
+    pub fn request_resolution(
+        env: Env,
+        factory: Address,
+        market: Address,
+        market_id: BytesN<32>,
+        resolution_time: u64,
+    ) -> Result<(), OracleError> {
+        factory.require_auth();
+        if Self::get_factory(env.clone()) != Some(factory) {
+            return Err(OracleError::UnauthorizedFactory);
+        }
+
+        let market_key = (Symbol::new(&env, "resolution_market"), market_id.clone());
+        if env.storage().persistent().has(&market_key) {
+            return Err(OracleError::ResolutionAlreadyRequested);
+        }
+        env.storage().persistent().set(&market_key, &market);
+        env.storage().persistent().set(
+            &(Symbol::new(&env, "resolution_time"), market_id.clone()),
+            &resolution_time,
+        );
+
+        let count = Self::get_requested_market_count(env.clone());
+        env.storage().persistent().set(
+            &(Symbol::new(&env, REQUESTED_MARKETS_KEY), count),
+            &market_id,
+        );
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, REQUESTED_MARKET_COUNT_KEY), &(count + 1));
+
+        // Emit ResolutionRequested event
+        env.events().publish(
+            (Symbol::new(&env, "ResolutionRequested"),),
+            (market_id, resolution_time),
+        );
+        Ok(())
+    }
+
+    /// Market: Move a registered market's resolution_time, e.g. after a resolution extension
+    ///
+    /// Only the market the factory registered for market_id can update it.
+    pub fn update_resolution_time(
+        env: Env,
+        market: Address,
+        market_id: BytesN<32>,
+        resolution_time: u64,
+    ) -> Result<(), OracleError> {
+        market.require_auth();
+
+        let registered: Option<Address> = env
+            .storage()
+            .persistent()
+            .get(&(Symbol::new(&env, "resolution_market"), market_id.clone()));
+        if registered != Some(market) {
+            return Err(OracleError::ResolutionNotRequested);
+        }
+        env.storage().persistent().set(
+            &(Symbol::new(&env, "resolution_time"), market_id.clone()),
+            &resolution_time,
+        );
+
+        env.events().publish(
+            (Symbol::new(&env, "ResolutionTimeUpdated"),),
+            (market_id, resolution_time),
+        );
+        Ok(())
+    }
+
+    /// Get the number of markets registered for resolution
+    pub fn get_requested_market_count(env: Env) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, REQUESTED_MARKET_COUNT_KEY))
+            .unwrap_or(0)
+    }
+
+    /// Get registered markets past their resolution time that `oracle` has not attested
+    ///
+    /// Scans the `limit` registrations starting at `offset`, in registration
+    /// order; markets whose result is already finalized are skipped.
+    pub fn get_markets_awaiting_attestation(
+        env: Env,
+        oracle: Address,
+        offset: u32,
+        limit: u32,
+    ) -> Vec<BytesN<32>> {
+        let now = env.ledger().timestamp();
+        let end = offset
+            .saturating_add(limit)
+            .min(Self::get_requested_market_count(env.clone()));
+
+        let mut awaiting = Vec::new(&env);
+        for index in offset..end {
+            let market_id: BytesN<32> = env
+                .storage()
+                .persistent()
+                .get(&(Symbol::new(&env, REQUESTED_MARKETS_KEY), index))
+                .expect("Requested market not found");
+            let resolution_time: u64 = env
+                .storage()
+                .persistent()
+                .get(&(Symbol::new(&env, "resolution_time"), market_id.clone()))
+                .unwrap_or(u64::MAX);
+            let finalized = env
+                .storage()
+                .persistent()
+                .has(&(Symbol::new(&env, "consensus_result"), market_id.clone()));
+            if now >= resolution_time
+                && !finalized
+                && !Self::has_attested(env.clone(), oracle.clone(), market_id.clone())
+            {
+                awaiting.push_back(market_id);
+            }
+        }
+        awaiting
     }
 
     /// Submit oracle attestation for market result
@@ -126,7 +592,7 @@ impl OracleManager {
         market_id: BytesN<32>,
         attestation_result: u32,
         _data_hash: BytesN<32>,
-    ) {
+    ) -> Result<(), OracleError> {
         // 1. Require oracle authentication
         oracle.require_auth();
 
@@ -134,50 +600,115 @@ impl OracleManager {
         let oracle_key = (Symbol::new(&env, "oracle"), oracle.clone());
         let is_registered: bool = env.storage().persistent().get(&oracle_key).unwrap_or(false);
         if !is_registered {
-            panic!("Oracle not registered");
+            return Err(OracleError::OracleNotRegistered);
         }
 
         // 3. Validate result is binary (0 or 1)
-        if attestation_result > 1 {
-            panic!("Invalid attestation result");
+        if !crate::helpers::is_valid_outcome(attestation_result, crate::helpers::BINARY_OUTCOMES) {
+            return Err(OracleError::InvalidAttestationResult);
         }
 
         // 4. Check if oracle already attested
         let vote_key = (Symbol::new(&env, "vote"), market_id.clone(), oracle.clone());
         if env.storage().persistent().has(&vote_key) {
-            panic!("Oracle already attested");
+            return Err(OracleError::AlreadyAttested);
+        }
+
+        // 5-7. Store attestation, track voter, emit event
+        Self::record_attestation(&env, &oracle, &market_id, attestation_result);
+        Ok(())
+    }
+
+    /// Submit attestations for several markets in one call
+    ///
+    /// Each entry is (market_id, result, data_hash). Markets the oracle already
+    /// attested are skipped instead of aborting the batch. Returns the number submitted.
+    pub fn submit_attestations_batch(
+        env: Env,
+        oracle: Address,
+        attestations: Vec<(BytesN<32>, u32, BytesN<32>)>,
+    ) -> Result<u32, OracleError> {
+        // Require oracle authentication
+        oracle.require_auth();
+
+        // Validate oracle is registered
+        let oracle_key = (Symbol::new(&env, "oracle"), oracle.clone());
+        let is_registered: bool = env.storage().persistent().get(&oracle_key).unwrap_or(false);
+        if !is_registered {
+            return Err(OracleError::OracleNotRegistered);
         }
 
-        // 5. Store attestation
+        let mut submitted: u32 = 0;
+        for (market_id, attestation_result, _data_hash) in attestations.iter() {
+            if !crate::helpers::is_valid_outcome(
+                attestation_result,
+                crate::helpers::BINARY_OUTCOMES,
+            ) {
+                return Err(OracleError::InvalidAttestationResult);
+            }
+
+            let vote_key = (Symbol::new(&env, "vote"), market_id.clone(), oracle.clone());
+            if env.storage().persistent().has(&vote_key) {
+                continue;
+            }
+
+            Self::record_attestation(&env, &oracle, &market_id, attestation_result);
+            submitted += 1;
+        }
+
+        Ok(submitted)
+    }
+
+    /// Helper: Store a validated vote, add oracle to the market's voter list and emit event
+    fn record_attestation(env: &Env, oracle: &Address, market_id: &BytesN<32>, result: u32) {
+        let vote_key = (Symbol::new(env, "vote"), market_id.clone(), oracle.clone());
+        env.storage().persistent().set(&vote_key, &result);
+
+        let vote_time_key = (
+            Symbol::new(env, "vote_time"),
+            market_id.clone(),
+            oracle.clone(),
+        );
         env.storage()
             .persistent()
-            .set(&vote_key, &attestation_result);
+            .set(&vote_time_key, &env.ledger().timestamp());
 
-        // 6. Track oracle in market's voter list
-        let voters_key = (Symbol::new(&env, "voters"), market_id.clone());
+        let voters_key = (Symbol::new(env, "voters"), market_id.clone());
         let mut voters: Vec<Address> = env
             .storage()
             .persistent()
             .get(&voters_key)
-            .unwrap_or(Vec::new(&env));
+            .unwrap_or(Vec::new(env));
 
         voters.push_back(oracle.clone());
         env.storage().persistent().set(&voters_key, &voters);
 
-        // 7. Emit event
+        // Start the challenge window when this vote forms consensus; a later
+        // vote that breaks the tie clears it so the window restarts
+        let consensus_time_key = (Symbol::new(env, "consensus_time"), market_id.clone());
+        let (reached, _) =
+            Self::check_consensus(env.clone(), market_id.clone()).unwrap_or((false, 0));
+        if !reached {
+            env.storage().persistent().remove(&consensus_time_key);
+        } else if !env.storage().persistent().has(&consensus_time_key) {
+            env.storage()
+                .persistent()
+                .set(&consensus_time_key, &env.ledger().timestamp());
+        }
+
         env.events().publish(
-            (Symbol::new(&env, "attestation_submitted"),),
+            (Symbol::new(env, "attestation_submitted"),),
             (
-                oracle,
-                market_id,
-                attestation_result,
+                oracle.clone(),
+                market_id.clone(),
+                result,
                 env.ledger().timestamp(),
             ),
         );
     }
 
     /// Check if consensus has been reached for market
-    pub fn check_consensus(env: Env, market_id: BytesN<32>) -> (bool, u32) {
+    pub fn check_consensus(env: Env, market_id: BytesN<32>) -> Result<(bool, u32), OracleError> {
         // 1. Query attestations for market_id
         let voters_key = (Symbol::new(&env, "voters"), market_id.clone());
         let voters: Vec<Address> = env
@@ -187,12 +718,66 @@ impl OracleManager {
             .unwrap_or(Vec::new(&env));
 
         // 2. Get required threshold
-        let threshold: u32 = env
+        let consensus_mode: ConsensusMode = env
             .storage()
             .persistent()
-            .get(&Symbol::new(&env, REQUIRED_CONSENSUS_KEY))
-            .unwrap_or(0);
+            .get(&Symbol::new(&env, CONSENSUS_MODE_KEY))
+            .unwrap_or_default();
+        let threshold: u32 = match consensus_mode {
+            ConsensusMode::AbsoluteCount => env
+                .storage()
+                .persistent()
+                .get(&Symbol::new(&env, REQUIRED_CONSENSUS_KEY))
+                .unwrap_or(0),
+            ConsensusMode::Percentage => {
+                let min_voters: u32 = env
+                    .storage()
+                    .persistent()
+                    .get(&Symbol::new(&env, MIN_VOTERS_KEY))
+                    .unwrap_or(0);
+                if voters.is_empty() || voters.len() < min_voters {
+                    return Ok((false, 0));
+                }
+                let threshold_pct: u32 = env
+                    .storage()
+                    .persistent()
+                    .get(&Symbol::new(&env, THRESHOLD_PCT_KEY))
+                    .unwrap_or(100);
+                // Smallest vote count that is at least threshold_pct of cast votes
+                (voters.len() * threshold_pct).div_ceil(100)
+            }
+        };
+
+        Ok(Self::consensus_at_threshold(
+            &env, &market_id, &voters, threshold,
+        ))
+    }
+
+    /// Evaluate consensus on the current votes for a caller-supplied vote threshold
+    ///
+    /// Runs the same tally as `check_consensus` in absolute-count terms, so
+    /// operators can preview a threshold before committing it.
+    pub fn simulate_consensus(
+        env: Env,
+        market_id: BytesN<32>,
+        hypothetical_threshold: u32,
+    ) -> (bool, u32) {
+        let voters_key = (Symbol::new(&env, "voters"), market_id.clone());
+        let voters: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&voters_key)
+            .unwrap_or(Vec::new(&env));
+        Self::consensus_at_threshold(&env, &market_id, &voters, hypothetical_threshold)
+    }
 
+    /// Helper: Decide consensus among `voters` when the winner needs `threshold` votes
+    fn consensus_at_threshold(
+        env: &Env,
+        market_id: &BytesN<32>,
+        voters: &Vec<Address>,
+        threshold: u32,
+    ) -> (bool, u32) {
         if voters.len() < threshold {
             return (false, 0);
         }
@@ -202,7 +787,7 @@ impl OracleManager {
         let mut no_votes = 0;
 
         for oracle in voters.iter() {
-            let vote_key = (Symbol::new(&env, "vote"), market_id.clone(), oracle);
+            let vote_key = (Symbol::new(env, "vote"), market_id.clone(), oracle);
             let vote: u32 = env.storage().persistent().get(&vote_key).unwrap_or(0);
             if vote == 1 {
                 yes_votes += 1;
@@ -227,41 +812,208 @@ impl OracleManager {
         }
     }
 
+    /// Check whether an oracle has already attested to a market
+    pub fn has_attested(env: Env, oracle: Address, market_id: BytesN<32>) -> bool {
+        env.storage()
+            .persistent()
+            .has(&(Symbol::new(&env, "vote"), market_id, oracle))
+    }
+
+    /// Get (yes_votes, no_votes) attested so far for a market
+    pub fn get_vote_counts(env: Env, market_id: BytesN<32>) -> (u32, u32) {
+        let voters_key = (Symbol::new(&env, "voters"), market_id.clone());
+        let voters: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&voters_key)
+            .unwrap_or(Vec::new(&env));
+
+        let mut yes_votes = 0;
+        let mut no_votes = 0;
+        for oracle in voters.iter() {
+            let vote_key = (Symbol::new(&env, "vote"), market_id.clone(), oracle);
+            let vote: u32 = env.storage().persistent().get(&vote_key).unwrap_or(0);
+            if vote == 1 {
+                yes_votes += 1;
+            } else {
+                no_votes += 1;
+            }
+        }
+        (yes_votes, no_votes)
+    }
+
+    /// Get the timestamp of the oldest attestation backing the consensus outcome
+    /// Returns 0 if consensus has not been reached
+    pub fn get_consensus_attestation_time(
+        env: Env,
+        market_id: BytesN<32>,
+    ) -> Result<u64, OracleError> {
+        let (reached, outcome) = Self::check_consensus(env.clone(), market_id.clone())?;
+        if !reached {
+            return Ok(0);
+        }
+
+        let voters_key = (Symbol::new(&env, "voters"), market_id.clone());
+        let voters: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&voters_key)
+            .unwrap_or(Vec::new(&env));
+
+        let mut oldest = u64::MAX;
+        for oracle in voters.iter() {
+            let vote_key = (Symbol::new(&env, "vote"), market_id.clone(), oracle.clone());
+            let vote: u32 = env.storage().persistent().get(&vote_key).unwrap_or(0);
+            if vote != outcome {
+                continue;
+            }
+            let vote_time_key = (Symbol::new(&env, "vote_time"), market_id.clone(), oracle);
+            let vote_time: u64 = env.storage().persistent().get(&vote_time_key).unwrap_or(0);
+            if vote_time < oldest {
+                oldest = vote_time;
+            }
+        }
+        Ok(oldest)
+    }
+
+    /// Get admin address
+    pub fn get_admin(env: Env) -> Address {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, ADMIN_KEY))
+            .expect("Oracle not initialized")
+    }
+
+    /// Get the USDC token used for stakes and challenge bonds, if configured
+    pub fn get_usdc_token(env: Env) -> Option<Address> {
+        env.storage().persistent().get(&Symbol::new(&env, USDC_KEY))
+    }
+
+    /// Get the treasury receiving forfeited bonds and slashed stake, if configured
+    pub fn get_treasury(env: Env) -> Option<Address> {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, TREASURY_KEY))
+    }
+
+    /// Get the market factory allowed to register markets, if configured
+    pub fn get_factory(env: Env) -> Option<Address> {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, FACTORY_KEY))
+    }
+
+    /// Get the seconds after consensus before a result can be finalized
+    pub fn get_challenge_window(env: Env) -> u64 {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, CHALLENGE_WINDOW_KEY))
+            .unwrap_or(DEFAULT_CHALLENGE_WINDOW_SECONDS)
+    }
+
+    /// Get the timestamp at which the market's current consensus formed, if any
+    pub fn get_consensus_time(env: Env, market_id: BytesN<32>) -> Option<u64> {
+        env.storage()
+            .persistent()
+            .get(&(Symbol::new(&env, "consensus_time"), market_id))
+    }
+
+    /// Get required consensus threshold
+    pub fn get_required_consensus(env: Env) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, REQUIRED_CONSENSUS_KEY))
+            .unwrap_or(0)
+    }
+
     /// Get the consensus result for a market
-    pub fn get_consensus_result(env: Env, market_id: BytesN<32>) -> u32 {
+    pub fn get_consensus_result(env: Env, market_id: BytesN<32>) -> Result<u32, OracleError> {
         let result_key = (Symbol::new(&env, "consensus_result"), market_id.clone());
         env.storage()
             .persistent()
             .get(&result_key)
-            .expect("Consensus result not found")
+            .ok_or(OracleError::ConsensusNotFound)
     }
 
-    /// Finalize market resolution after time delay
+    /// Get consensus, finality, voter count and challenge status for a market
+    pub fn get_market_result(env: Env, market_id: BytesN<32>) -> MarketResult {
+        let (consensus_reached, consensus_outcome) =
+            Self::check_consensus(env.clone(), market_id.clone()).unwrap_or((false, 0));
+        let finalized_outcome: Option<u32> = env
+            .storage()
+            .persistent()
+            .get(&(Symbol::new(&env, "consensus_result"), market_id.clone()));
+        let voters_key = (Symbol::new(&env, "voters"), market_id.clone());
+        let voters: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&voters_key)
+            .unwrap_or(Vec::new(&env));
+
+        MarketResult {
+            consensus_reached,
+            outcome: finalized_outcome.unwrap_or(consensus_outcome),
+            finalized: finalized_outcome.is_some(),
+            voter_count: voters.len(),
+            challenge_active: Self::has_active_challenge(&env, &market_id, &voters),
+        }
+    }
+
+    /// Helper: Whether any voter's attestation for the market is under challenge
+    fn has_active_challenge(env: &Env, market_id: &BytesN<32>, voters: &Vec<Address>) -> bool {
+        voters.iter().any(|oracle| {
+            let challenge_key = (Symbol::new(env, "challenge"), market_id.clone(), oracle);
+            env.storage().persistent().has(&challenge_key)
+        })
+    }
+
+    /// Finalize a market's consensus result
     ///
-    /// TODO: Finalize Resolution
-    /// - Validate market_id exists
-    /// - Validate consensus already reached
-    /// - Validate time_delay_before_finality has passed
-    /// - Validate no active disputes/challenges
-    /// - Get consensus_result
-    /// - Call market contract's resolve_market() function
-    /// - Pass winning_outcome to market
-    /// - Confirm resolution recorded
-    /// - Emit ResolutionFinalized(market_id, outcome, timestamp)
-    pub fn finalize_resolution(env: Env, market_id: BytesN<32>) {
-        todo!("See finalize resolution TODO above")
+    /// Requires consensus, the challenge window since consensus formed to have
+    /// passed, and no open challenges against the market's attestations. The
+    /// outcome is stored for `get_consensus_result`.
+    pub fn finalize_resolution(env: Env, market_id: BytesN<32>) -> Result<u32, OracleError> {
+        let result_key = (Symbol::new(&env, "consensus_result"), market_id.clone());
+        if env.storage().persistent().has(&result_key) {
+            return Err(OracleError::AlreadyFinalized);
+        }
+
+        let (reached, outcome) = Self::check_consensus(env.clone(), market_id.clone())?;
+        if !reached {
+            return Err(OracleError::ConsensusNotReached);
+        }
+
+        let voters: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&(Symbol::new(&env, "voters"), market_id.clone()))
+            .unwrap_or(Vec::new(&env));
+        if Self::has_active_challenge(&env, &market_id, &voters) {
+            return Err(OracleError::ChallengeActive);
+        }
+
+        // Give challengers the full window before the result becomes final
+        let consensus_time = Self::get_consensus_time(env.clone(), market_id.clone())
+            .ok_or(OracleError::ConsensusNotReached)?;
+        if env.ledger().timestamp() < consensus_time + Self::get_challenge_window(env.clone()) {
+            return Err(OracleError::ChallengeWindowOpen);
+        }
+
+        env.storage().persistent().set(&result_key, &outcome);
+
+        // Emit ResolutionFinalized event
+        env.events().publish(
+            (Symbol::new(&env, "ResolutionFinalized"),),
+            (market_id, outcome, env.ledger().timestamp()),
+        );
+
+        Ok(outcome)
     }
 
     /// Challenge an attestation (dispute oracle honesty)
     ///
-    /// TODO: Challenge Attestation
-    /// - Require challenger authentication (must be oracle or participant)
-    /// - Validate market_id and oracle being challenged
-    /// - Validate attestation exists
-    /// - Create challenge record: { challenger, oracle_challenged, reason, timestamp }
-    /// - Pause consensus finalization until challenge resolved
-    /// - Emit AttestationChallenged(oracle, challenger, market_id, reason)
-    /// - Require evidence/proof in challenge
+    /// The challenger posts the configured USDC bond, held by this contract
+    /// until the admin resolves the challenge.
     pub fn challenge_attestation(
         env: Env,
         challenger: Address,
@@ -269,31 +1021,179 @@ impl OracleManager {
         market_id: BytesN<32>,
         challenge_reason: Symbol,
     ) {
-        todo!("See challenge attestation TODO above")
+        // Require challenger authentication
+        challenger.require_auth();
+
+        // Validate attestation exists
+        let vote_key = (Symbol::new(&env, "vote"), market_id.clone(), oracle.clone());
+        if !env.storage().persistent().has(&vote_key) {
+            panic!("Attestation not found");
+        }
+
+        // Only one open challenge per attestation
+        let challenge_key = (
+            Symbol::new(&env, "challenge"),
+            market_id.clone(),
+            oracle.clone(),
+        );
+        if env.storage().persistent().has(&challenge_key) {
+            panic!("Attestation already challenged");
+        }
+
+        // Collect challenge bond into oracle contract escrow
+        let bond: i128 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, CHALLENGE_BOND_KEY))
+            .unwrap_or(0);
+        if bond > 0 {
+            let usdc_token: Address = env
+                .storage()
+                .persistent()
+                .get(&Symbol::new(&env, USDC_KEY))
+                .expect("usdc token not set");
+            let token_client = token::Client::new(&env, &usdc_token);
+            token_client.transfer(&challenger, env.current_contract_address(), &bond);
+        }
+
+        // Create challenge record
+        let challenge = Challenge {
+            challenger: challenger.clone(),
+            reason: challenge_reason.clone(),
+            bond,
+            timestamp: env.ledger().timestamp(),
+        };
+        env.storage().persistent().set(&challenge_key, &challenge);
+
+        // Emit AttestationChallenged event
+        env.events().publish(
+            (Symbol::new(&env, "attestation_challenged"),),
+            (oracle, challenger, market_id, challenge_reason, bond),
+        );
     }
 
     /// Resolve a challenge and update oracle reputation
     ///
-    /// TODO: Resolve Challenge
-    /// - Require admin authentication
-    /// - Query challenge record
-    /// - Review evidence submitted
-    /// - Determine if challenge is valid (oracle was dishonest)
-    /// - If valid:
-    ///   - Reduce oracle's reputation/accuracy score
-    ///   - If score drops below threshold: deregister oracle
-    ///   - Potentially slash oracle's stake (if implemented)
-    /// - If invalid:
-    ///   - Increase oracle's reputation
-    ///   - Penalize false challenger
-    /// - Emit ChallengeResolved(oracle, challenger, is_valid, new_reputation)
+    /// A valid challenge returns the bond to the challenger, lowers the
+    /// oracle's accuracy score and slashes the configured fraction of its
+    /// stake (if slashing is enabled); an invalid one forfeits the bond to the treasury. An oracle
+    /// whose stake is slashed to zero is deregistered.
     pub fn resolve_challenge(
         env: Env,
         oracle: Address,
         market_id: BytesN<32>,
         challenge_valid: bool,
     ) {
-        todo!("See resolve challenge TODO above")
+        // Require admin authentication
+        let admin: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ADMIN_KEY))
+            .unwrap();
+        admin.require_auth();
+
+        // Query challenge record
+        let challenge_key = (
+            Symbol::new(&env, "challenge"),
+            market_id.clone(),
+            oracle.clone(),
+        );
+        let challenge: Challenge = env
+            .storage()
+            .persistent()
+            .get(&challenge_key)
+            .expect("Challenge not found");
+        env.storage().persistent().remove(&challenge_key);
+
+        // Update oracle accuracy score
+        let accuracy_key = (Symbol::new(&env, "oracle_accuracy"), oracle.clone());
+        let accuracy: u32 = env.storage().persistent().get(&accuracy_key).unwrap_or(100);
+        let new_accuracy = if challenge_valid {
+            accuracy.saturating_sub(CHALLENGE_ACCURACY_PENALTY)
+        } else {
+            accuracy
+        };
+        env.storage().persistent().set(&accuracy_key, &new_accuracy);
+        if new_accuracy != accuracy {
+            env.events().publish(
+                (Symbol::new(&env, "OracleAccuracyUpdated"),),
+                (oracle.clone(), accuracy, new_accuracy),
+            );
+        }
+
+        // Settle the bond: refund on a valid challenge, forfeit to treasury otherwise
+        if challenge.bond > 0 {
+            let usdc_token: Address = env
+                .storage()
+                .persistent()
+                .get(&Symbol::new(&env, USDC_KEY))
+                .expect("usdc token not set");
+            let token_client = token::Client::new(&env, &usdc_token);
+            let recipient: Address = if challenge_valid {
+                challenge.challenger.clone()
+            } else {
+                env.storage()
+                    .persistent()
+                    .get(&Symbol::new(&env, TREASURY_KEY))
+                    .expect("treasury not set")
+            };
+            token_client.transfer(&env.current_contract_address(), &recipient, &challenge.bond);
+        }
+
+        // Slash part of the dishonest oracle's stake
+        let slash_bps: u32 = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, SLASH_BPS_KEY))
+            .unwrap_or(0);
+        if challenge_valid && slash_bps > 0 {
+            let stake_key = (Symbol::new(&env, "oracle_stake"), oracle.clone());
+            let stake: i128 = env.storage().persistent().get(&stake_key).unwrap_or(0);
+            if stake > 0 {
+                let slashed = (stake * slash_bps as i128) / 10000;
+                let remaining = stake - slashed;
+                env.storage().persistent().set(&stake_key, &remaining);
+
+                let usdc_token: Address = env
+                    .storage()
+                    .persistent()
+                    .get(&Symbol::new(&env, USDC_KEY))
+                    .expect("usdc token not set");
+                let to_challenger: bool = env
+                    .storage()
+                    .persistent()
+                    .get(&Symbol::new(&env, SLASH_TO_CHALLENGER_KEY))
+                    .unwrap_or(false);
+                let recipient: Address = if to_challenger {
+                    challenge.challenger.clone()
+                } else {
+                    env.storage()
+                        .persistent()
+                        .get(&Symbol::new(&env, TREASURY_KEY))
+                        .expect("treasury not set")
+                };
+                if slashed > 0 {
+                    let token_client = token::Client::new(&env, &usdc_token);
+                    token_client.transfer(&env.current_contract_address(), &recipient, &slashed);
+                }
+
+                env.events().publish(
+                    (Symbol::new(&env, "OracleSlashed"),),
+                    (oracle.clone(), slashed, remaining, recipient),
+                );
+
+                // An oracle with nothing left at stake can no longer attest
+                if remaining == 0 {
+                    Self::deactivate_oracle(&env, &oracle);
+                }
+            }
+        }
+
+        // Emit ChallengeResolved event
+        env.events().publish(
+            (Symbol::new(&env, "challenge_resolved"),),
+            (oracle, challenge.challenger, challenge_valid, new_accuracy),
+        );
     }
 
     /// Get all attestations for a market
@@ -318,15 +1218,38 @@ impl OracleManager {
         todo!("See get oracle info TODO above")
     }
 
-    /// Get all active oracles
-    ///
-    /// TODO: Get Active Oracles
-    /// - Query oracle_registry for all oracles with status=active
-    /// - Return list of oracle addresses
-    /// - Include: reputation scores sorted by highest first
-    /// - Include: availability status
+    /// Get all active oracles, in registration order
     pub fn get_active_oracles(env: Env) -> Vec<Address> {
-        todo!("See get active oracles TODO above")
+        let oracle_list: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ORACLE_LIST_KEY))
+            .unwrap_or(Vec::new(&env));
+
+        let mut active = Vec::new(&env);
+        for oracle in oracle_list.iter() {
+            let oracle_key = (Symbol::new(&env, "oracle"), oracle.clone());
+            let is_active: bool = env.storage().persistent().get(&oracle_key).unwrap_or(false);
+            if is_active {
+                active.push_back(oracle);
+            }
+        }
+        active
+    }
+
+    /// Get the address and registered name of each active oracle
+    pub fn get_oracle_names(env: Env) -> Vec<(Address, Symbol)> {
+        let mut names = Vec::new(&env);
+        for oracle in Self::get_active_oracles(env.clone()).iter() {
+            let oracle_name_key = (Symbol::new(&env, "oracle_name"), oracle.clone());
+            let name: Symbol = env
+                .storage()
+                .persistent()
+                .get(&oracle_name_key)
+                .expect("oracle name not set");
+            names.push_back((oracle, name));
+        }
+        names
     }
 
     /// Admin: Update oracle consensus threshold
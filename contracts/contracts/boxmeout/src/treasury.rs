@@ -1,7 +1,7 @@
 // contract/src/treasury.rs - Treasury Contract Implementation
 // Handles fee collection and reward distribution
 
-use soroban_sdk::{contract, contractimpl, token, Address, Env, Symbol};
+use soroban_sdk::{contract, contractimpl, token, Address, BytesN, Env, Symbol};
 
 // Storage keys
 const ADMIN_KEY: &str = "admin";
@@ -12,6 +12,8 @@ const LEADERBOARD_FEES_KEY: &str = "leaderboard_fees";
 const CREATOR_FEES_KEY: &str = "creator_fees";
 const TOTAL_FEES_KEY: &str = "total_fees";
 const DISTRIBUTION_KEY: &str = "distribution";
+const DEPOSITOR_PREFIX: &str = "depositor";
+const MARKET_FEES_PREFIX: &str = "market_fees";
 
 /// Fee distribution ratios (sum to 100)
 #[soroban_sdk::contracttype]
@@ -121,38 +123,84 @@ impl Treasury {
     }
 
     /// Deposit fees into treasury and split across pools
+    /// Only the factory or a registered market may deposit
     pub fn deposit_fees(env: Env, source: Address, amount: i128) {
+        // Require depositor authentication
+        source.require_auth();
+
+        // Validate source is the factory or an authorized market
+        if !self::is_depositor(&env, &source) {
+            panic!("unauthorized depositor");
+        }
+
         // Validate amount > 0
         if amount <= 0 {
             panic!("Amount must be positive");
         }
 
-        // Get USDC token contract
-        let usdc_token: Address = env.storage().persistent().get(&Symbol::new(&env, USDC_KEY)).expect("USDC not set");
-        let token_client = token::Client::new(&env, &usdc_token);
-        let treasury_address = env.current_contract_address();
+        self::collect_split_fees(&env, &source, amount);
+    }
 
-        // Transfer USDC from source to treasury
-        // The source must have authorized the treasury to pull funds
-        token_client.transfer(&source, &treasury_address, &amount);
+    /// Deposit fees split across pools, attributed to `market_id`
+    /// Only the factory or a registered market may deposit
+    pub fn deposit_market_fees(env: Env, source: Address, market_id: BytesN<32>, amount: i128) {
+        source.require_auth();
+
+        if !self::is_depositor(&env, &source) {
+            panic!("unauthorized depositor");
+        }
+
+        if amount <= 0 {
+            panic!("Amount must be positive");
+        }
 
-        // Get current ratios
-        let ratios: FeeRatios = env.storage().persistent().get(&Symbol::new(&env, DISTRIBUTION_KEY)).expect("Ratios not set");
+        self::collect_split_fees(&env, &source, amount);
 
-        // Calculate shares
-        let platform_share = (amount * ratios.platform as i128) / 100;
-        let leaderboard_share = (amount * ratios.leaderboard as i128) / 100;
-        let creator_share = amount - platform_share - leaderboard_share; // Remainder to creator to avoid rounding dust
+        let market_key = (Symbol::new(&env, MARKET_FEES_PREFIX), market_id);
+        let collected: i128 = env.storage().persistent().get(&market_key).unwrap_or(0);
+        env.storage()
+            .persistent()
+            .set(&market_key, &(collected + amount));
+    }
+
+    /// Deposit fees into a single pool ("platform", "leaderboard" or "creator")
+    /// Only the factory or a registered depositor may deposit
+    pub fn deposit_category_fees(env: Env, source: Address, category: Symbol, amount: i128) {
+        // Require depositor authentication
+        source.require_auth();
+
+        if !self::is_depositor(&env, &source) {
+            panic!("unauthorized depositor");
+        }
+
+        if amount <= 0 {
+            panic!("Amount must be positive");
+        }
+
+        let pool_key = if category == Symbol::new(&env, "platform") {
+            PLATFORM_FEES_KEY
+        } else if category == Symbol::new(&env, "leaderboard") {
+            LEADERBOARD_FEES_KEY
+        } else if category == Symbol::new(&env, "creator") {
+            CREATOR_FEES_KEY
+        } else {
+            panic!("unknown fee category");
+        };
+
+        // Transfer USDC from source to treasury
+        let usdc_token: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, USDC_KEY))
+            .expect("USDC not set");
+        let token_client = token::Client::new(&env, &usdc_token);
+        token_client.transfer(&source, env.current_contract_address(), &amount);
 
-        // Update pools
-        self::update_pool_balance(&env, PLATFORM_FEES_KEY, platform_share);
-        self::update_pool_balance(&env, LEADERBOARD_FEES_KEY, leaderboard_share);
-        self::update_pool_balance(&env, CREATOR_FEES_KEY, creator_share);
+        self::update_pool_balance(&env, pool_key, amount);
         self::update_pool_balance(&env, TOTAL_FEES_KEY, amount);
 
-        // Emit FeeCollected(source, amount, timestamp)
         env.events().publish(
-            (Symbol::new(&env, "FeeCollected"), source, (Symbol::new(&env, "fee_source"),)),
+            (Symbol::new(&env, "FeeCollected"), source, (category,)),
             (amount, env.ledger().timestamp()),
         );
     }
@@ -189,6 +237,87 @@ impl Treasury {
             .unwrap_or(0)
     }
 
+    /// Get fees deposited via `deposit_market_fees` for one market
+    pub fn get_market_fees(env: Env, market_id: BytesN<32>) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&(Symbol::new(&env, MARKET_FEES_PREFIX), market_id))
+            .unwrap_or(0)
+    }
+
+    /// Get admin address
+    pub fn get_admin(env: Env) -> Address {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, ADMIN_KEY))
+            .expect("Not initialized")
+    }
+
+    /// Get USDC token address
+    pub fn get_usdc(env: Env) -> Address {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, USDC_KEY))
+            .expect("USDC not set")
+    }
+
+    /// Get factory address
+    pub fn get_factory(env: Env) -> Address {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, FACTORY_KEY))
+            .expect("Factory not set")
+    }
+
+    /// Get the fee split as (platform, leaderboard, creator) percentages
+    /// Defaults to 50/30/20 if never set
+    pub fn get_fee_distribution(env: Env) -> (u32, u32, u32) {
+        let ratios: FeeRatios = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, DISTRIBUTION_KEY))
+            .unwrap_or(FeeRatios {
+                platform: 50,
+                leaderboard: 30,
+                creator: 20,
+            });
+        (ratios.platform, ratios.leaderboard, ratios.creator)
+    }
+
+    /// Register a market contract as an authorized fee depositor
+    /// Callable by the admin, or by the factory when it deploys a market
+    pub fn register_market_depositor(env: Env, admin: Address, market_address: Address) {
+        admin.require_auth();
+        let stored_admin: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, ADMIN_KEY))
+            .expect("Not initialized");
+        let factory: Address = env
+            .storage()
+            .persistent()
+            .get(&Symbol::new(&env, FACTORY_KEY))
+            .expect("Factory not set");
+        if admin != stored_admin && admin != factory {
+            panic!("Unauthorized");
+        }
+
+        env.storage().persistent().set(
+            &(Symbol::new(&env, DEPOSITOR_PREFIX), market_address.clone()),
+            &true,
+        );
+
+        env.events().publish(
+            (Symbol::new(&env, "MarketDepositorRegistered"),),
+            (market_address, env.ledger().timestamp()),
+        );
+    }
+
+    /// Check whether an address may deposit fees (factory or registered market)
+    pub fn is_authorized_depositor(env: Env, addr: Address) -> bool {
+        self::is_depositor(&env, &addr)
+    }
+
     /// Distribute rewards to leaderboard winners
     pub fn distribute_leaderboard_rewards(env: Env) {
         todo!("Leaderboard distribution logic not yet implemented")
@@ -278,6 +407,64 @@ impl Treasury {
     }
 }
 
+fn is_depositor(env: &Env, source: &Address) -> bool {
+    let factory: Option<Address> = env
+        .storage()
+        .persistent()
+        .get(&Symbol::new(env, FACTORY_KEY));
+    if factory.as_ref() == Some(source) {
+        return true;
+    }
+    env.storage()
+        .persistent()
+        .get(&(Symbol::new(env, DEPOSITOR_PREFIX), source.clone()))
+        .unwrap_or(false)
+}
+
+/// Pull `amount` from `source` and split it across the platform, leaderboard and creator pools
+fn collect_split_fees(env: &Env, source: &Address, amount: i128) {
+    // Get USDC token contract
+    let usdc_token: Address = env
+        .storage()
+        .persistent()
+        .get(&Symbol::new(env, USDC_KEY))
+        .expect("USDC not set");
+    let token_client = token::Client::new(env, &usdc_token);
+    let treasury_address = env.current_contract_address();
+
+    // Transfer USDC from source to treasury
+    // The source must have authorized the treasury to pull funds
+    token_client.transfer(source, &treasury_address, &amount);
+
+    // Get current ratios
+    let ratios: FeeRatios = env
+        .storage()
+        .persistent()
+        .get(&Symbol::new(env, DISTRIBUTION_KEY))
+        .expect("Ratios not set");
+
+    // Calculate shares
+    let platform_share = (amount * ratios.platform as i128) / 100;
+    let leaderboard_share = (amount * ratios.leaderboard as i128) / 100;
+    let creator_share = amount - platform_share - leaderboard_share; // Remainder to creator to avoid rounding dust
+
+    // Update pools
+    self::update_pool_balance(env, PLATFORM_FEES_KEY, platform_share);
+    self::update_pool_balance(env, LEADERBOARD_FEES_KEY, leaderboard_share);
+    self::update_pool_balance(env, CREATOR_FEES_KEY, creator_share);
+    self::update_pool_balance(env, TOTAL_FEES_KEY, amount);
+
+    // Emit FeeCollected(source, amount, timestamp)
+    env.events().publish(
+        (
+            Symbol::new(env, "FeeCollected"),
+            source.clone(),
+            (Symbol::new(env, "fee_source"),),
+        ),
+        (amount, env.ledger().timestamp()),
+    );
+}
+
 fn update_pool_balance(env: &Env, key: &str, delta: i128) {
     let current: i128 = env.storage()
         .persistent()
@@ -327,11 +514,21 @@ mod tests {
         assert_eq!(treasury.get_total_fees(), 0);
     }
 
+    #[test]
+    fn test_getters_return_init_addresses() {
+        let env = Env::default();
+        let (treasury, usdc, admin, _, factory) = setup_treasury(&env);
+
+        assert_eq!(treasury.get_admin(), admin);
+        assert_eq!(treasury.get_usdc(), usdc.address);
+        assert_eq!(treasury.get_factory(), factory);
+    }
+
     #[test]
     fn test_deposit_fees_splits_correctly() {
         let env = Env::default();
-        let (treasury, usdc, admin, _, _) = setup_treasury(&env);
-        let source = Address::generate(&env);
+        let (treasury, usdc, admin, _, factory) = setup_treasury(&env);
+        let source = factory;
         
         // Mint tokens to source
         usdc.mint(&source, &1000);
@@ -348,11 +545,75 @@ mod tests {
         assert_eq!(usdc.balance(&source), 0);
     }
 
+    #[test]
+    fn test_deposit_market_fees_tracked_per_market() {
+        let env = Env::default();
+        let (treasury, usdc, _, _, factory) = setup_treasury(&env);
+        let market_a = BytesN::from_array(&env, &[1u8; 32]);
+        let market_b = BytesN::from_array(&env, &[2u8; 32]);
+
+        usdc.mint(&factory, &1000);
+        treasury.deposit_market_fees(&factory, &market_a, &300);
+        treasury.deposit_market_fees(&factory, &market_b, &200);
+        treasury.deposit_market_fees(&factory, &market_a, &100);
+
+        assert_eq!(treasury.get_market_fees(&market_a), 400);
+        assert_eq!(treasury.get_market_fees(&market_b), 200);
+        assert_eq!(
+            treasury.get_market_fees(&BytesN::from_array(&env, &[3u8; 32])),
+            0
+        );
+        // Attributed deposits still split across the pools
+        assert_eq!(treasury.get_total_fees(), 600);
+        assert_eq!(treasury.get_platform_fees(), 300);
+    }
+
+    #[test]
+    #[should_panic(expected = "unauthorized depositor")]
+    fn test_deposit_fees_unknown_source_rejected() {
+        let env = Env::default();
+        let (treasury, usdc, _, _, _) = setup_treasury(&env);
+        let stranger = Address::generate(&env);
+
+        usdc.mint(&stranger, &1000);
+        treasury.deposit_fees(&stranger, &1000);
+    }
+
+    #[test]
+    fn test_register_market_depositor() {
+        let env = Env::default();
+        let (treasury, usdc, admin, _, factory) = setup_treasury(&env);
+        let market = Address::generate(&env);
+        let unregistered = Address::generate(&env);
+
+        assert!(treasury.is_authorized_depositor(&factory));
+        assert!(!treasury.is_authorized_depositor(&market));
+
+        treasury.register_market_depositor(&admin, &market);
+
+        assert!(treasury.is_authorized_depositor(&market));
+        assert!(!treasury.is_authorized_depositor(&unregistered));
+
+        // Registered market can now deposit fees
+        usdc.mint(&market, &1000);
+        treasury.deposit_fees(&market, &1000);
+        assert_eq!(treasury.get_total_fees(), 1000);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized")]
+    fn test_register_market_depositor_non_admin_rejected() {
+        let env = Env::default();
+        let (treasury, _, _, _, _) = setup_treasury(&env);
+        let stranger = Address::generate(&env);
+        treasury.register_market_depositor(&stranger, &Address::generate(&env));
+    }
+
     #[test]
     fn test_set_fee_distribution() {
         let env = Env::default();
-        let (treasury, usdc, admin, _, _) = setup_treasury(&env);
-        let source = Address::generate(&env);
+        let (treasury, usdc, admin, _, factory) = setup_treasury(&env);
+        let source = factory;
         
         // Update ratios: 40% Platform, 40% Leaderboard, 20% Creator
         treasury.set_fee_distribution(&40, &40, &20);
@@ -365,6 +626,16 @@ mod tests {
         assert_eq!(treasury.get_creator_fees(), 200);
     }
 
+    #[test]
+    fn test_get_fee_distribution() {
+        let env = Env::default();
+        let (treasury, _, _, _, _) = setup_treasury(&env);
+        assert_eq!(treasury.get_fee_distribution(), (50, 30, 20));
+
+        treasury.set_fee_distribution(&70, &20, &10);
+        assert_eq!(treasury.get_fee_distribution(), (70, 20, 10));
+    }
+
     #[test]
     #[should_panic(expected = "Ratios must sum to 100")]
     fn test_set_fee_distribution_invalid_sum() {
@@ -376,8 +647,8 @@ mod tests {
     #[test]
     fn test_distribute_creator_rewards() {
         let env = Env::default();
-        let (treasury, usdc, admin, _, _) = setup_treasury(&env);
-        let source = Address::generate(&env);
+        let (treasury, usdc, admin, _, factory) = setup_treasury(&env);
+        let source = factory;
         let creator1 = Address::generate(&env);
         let creator2 = Address::generate(&env);
         
@@ -399,9 +670,9 @@ mod tests {
     #[test]
     fn test_emergency_withdraw() {
         let env = Env::default();
-        let (treasury, usdc, admin, _, _) = setup_treasury(&env);
+        let (treasury, usdc, admin, _, factory) = setup_treasury(&env);
         let recipient = Address::generate(&env);
-        let source = Address::generate(&env);
+        let source = factory;
         
         usdc.mint(&source, &1000);
         treasury.deposit_fees(&source, &1000);
@@ -4,11 +4,11 @@ use soroban_sdk::{
     testutils::{Address as _, Events},
     testutils::{Address as _, Ledger},
     token::{StellarAssetClient, TokenClient},
-    Address, Address, BytesN, BytesN, Env, Env, IntoVal, Symbol, Symbol,
+    Address, BytesN, Env, IntoVal, Symbol, TryFromVal, Val, Vec,
 };
 
 use boxmeout::helpers::*;
-use boxmeout::{AMMClient, AMM};
+use boxmeout::{AMMClient, Treasury, TreasuryClient, AMM};
 
 const POOL_YES_RESERVE: &str = "pool_yes_reserve";
 const POOL_NO_RESERVE: &str = "pool_no_reserve";
@@ -104,11 +104,32 @@ fn create_mock_token(env: &Env, admin: &Address) -> Address {
     token_address.address()
 }
 
+/// Mock oracle reporting a fixed consensus outcome
+#[soroban_sdk::contract]
+pub struct MockOracle;
+
+#[soroban_sdk::contractimpl]
+impl MockOracle {
+    pub fn set_result(env: Env, outcome: u32) {
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, "result"), &outcome);
+    }
+
+    pub fn get_consensus_result(env: Env, _market_id: BytesN<32>) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, "result"))
+            .unwrap()
+    }
+}
+
 #[test]
 fn test_amm_initialize() {
     let env = create_test_env();
+    env.mock_all_auths();
     let amm_id = register_amm(&env);
-    let client = AMMContractClient::new(&env, &amm_id);
+    let client = AMMClient::new(&env, &amm_id);
 
     let admin = Address::generate(&env);
     let factory = Address::generate(&env);
@@ -117,10 +138,14 @@ fn test_amm_initialize() {
 
     client.initialize(&admin, &factory, &usdc_token, &max_liquidity_cap);
 
-    // TODO: Add getters to verify
+    let (slippage, trading_fee, pricing_model, cap) = client.get_config();
     // Verify slippage protection = 200
+    assert_eq!(slippage, 200);
     // Verify trading fee = 20
+    assert_eq!(trading_fee, 20);
     // Verify pricing model = CPMM
+    assert_eq!(pricing_model, Symbol::new(&env, "CPMM"));
+    assert_eq!(cap, max_liquidity_cap);
 }
 
 #[test]
@@ -168,10 +193,10 @@ fn test_create_pool_twice_fails() {
     let token_client = StellarAssetClient::new(&env, &usdc_token);
     token_client.mint(&creator, &(initial_liquidity as i128 * 2)); // Mint enough for 2 attempts
 
-    client.create_pool(&creator, &market_id, &initial_liquidity);
+    client.create_pool(&creator, &market_id, &initial_liquidity, &0u32, &0u64);
 
     // Try to create pool again - should panic
-    client.create_pool(&creator, &market_id, &initial_liquidity);
+    client.create_pool(&creator, &market_id, &initial_liquidity, &0u32, &0u64);
 }
 
 #[test]
@@ -684,7 +709,7 @@ fn test_get_odds() {
     assert_eq!(no_odds, 5000); // 50%
     let token_client = StellarAssetClient::new(&env, &usdc_token);
     token_client.mint(&creator, &(initial_liquidity as i128));
-    client.create_pool(&creator, &market_id, &initial_liquidity);
+    client.create_pool(&creator, &market_id, &initial_liquidity, &0u32, &0u64);
 
     // Test 2: Create pool with equal reserves (50/50)
     client.create_pool(&market_id, &10_000_000_000u128); // 10k USDC
@@ -721,7 +746,7 @@ fn test_get_odds_skewed_pools() {
 
     let token_client = StellarAssetClient::new(&env, &usdc_token);
     token_client.mint(&creator, &(initial_liquidity as i128));
-    client.create_pool(&creator, &market_id, &initial_liquidity);
+    client.create_pool(&creator, &market_id, &initial_liquidity, &0u32, &0u64);
 
     // Add liquidity multiple times
     let lp2 = Address::generate(&env);
@@ -784,7 +809,7 @@ fn test_get_odds_read_only() {
     assert_eq!(no_odds_1, no_odds_3);
     let token_client = StellarAssetClient::new(&env, &usdc_token);
     token_client.mint(&creator, &(initial_liquidity as i128));
-    client.create_pool(&creator, &market_id, &initial_liquidity);
+    client.create_pool(&creator, &market_id, &initial_liquidity, &0u32, &0u64);
 
     // Verify odds sum to 10000 (100%)
     assert_eq!(yes_odds_1 + no_odds_1, 10000);
@@ -861,7 +886,7 @@ fn test_amm_pricing_logic() {
 
     let token_client = StellarAssetClient::new(&env, &usdc_token);
     token_client.mint(&creator, &(initial_liquidity as i128));
-    client.create_pool(&creator, &market_id, &initial_liquidity);
+    client.create_pool(&creator, &market_id, &initial_liquidity, &0u32, &0u64);
 
     // Add liquidity
     let lp2 = Address::generate(&env);
@@ -895,7 +920,7 @@ fn test_remove_liquidity() {
 
     let token_client = StellarAssetClient::new(&env, &usdc_token);
     token_client.mint(&creator, &(initial_liquidity as i128));
-    client.create_pool(&creator, &market_id, &initial_liquidity);
+    client.create_pool(&creator, &market_id, &initial_liquidity, &0u32, &0u64);
 
     // Add liquidity from second LP
     let lp2 = Address::generate(&env);
@@ -934,7 +959,7 @@ fn test_remove_liquidity_more_than_owned() {
 
     let token_client = StellarAssetClient::new(&env, &usdc_token);
     token_client.mint(&creator, &(initial_liquidity as i128));
-    client.create_pool(&creator, &market_id, &initial_liquidity);
+    client.create_pool(&creator, &market_id, &initial_liquidity, &0u32, &0u64);
 
     // Try to remove more LP tokens than owned
     let lp2 = Address::generate(&env);
@@ -961,7 +986,7 @@ fn test_remove_liquidity_proportional_calculation() {
 
     let token_client = StellarAssetClient::new(&env, &usdc_token);
     token_client.mint(&creator, &(initial_liquidity as i128));
-    client.create_pool(&creator, &market_id, &initial_liquidity);
+    client.create_pool(&creator, &market_id, &initial_liquidity, &0u32, &0u64);
 
     // Remove all creator's liquidity (except can't drain completely)
     // So remove almost all
@@ -1002,7 +1027,7 @@ fn test_remove_liquidity_event_emitted() {
 
     let token_client = StellarAssetClient::new(&env, &usdc_token);
     token_client.mint(&creator, &(initial_liquidity as i128));
-    client.create_pool(&creator, &market_id, &initial_liquidity);
+    client.create_pool(&creator, &market_id, &initial_liquidity, &0u32, &0u64);
 
     // Add liquidity
     let lp2 = Address::generate(&env);
@@ -1042,7 +1067,7 @@ fn test_remove_liquidity_zero_amount() {
 
     let token_client = StellarAssetClient::new(&env, &usdc_token);
     token_client.mint(&creator, &(initial_liquidity as i128));
-    client.create_pool(&creator, &market_id, &initial_liquidity);
+    client.create_pool(&creator, &market_id, &initial_liquidity, &0u32, &0u64);
 
     // Try to remove zero LP tokens
     client.remove_liquidity(&creator, &market_id, &0u128);
@@ -1214,3 +1239,899 @@ fn test_cpmm_invariant() {
     let k_increase_ratio = final_k as f64 / initial_k as f64;
     assert!(k_increase_ratio < 1.1); // Less than 10% increase
 }
+
+#[test]
+fn test_get_price_impact_scales_with_trade_size() {
+    let env = create_test_env();
+    let amm_id = register_amm(&env);
+    let client = AMMClient::new(&env, &amm_id);
+
+    let market_id = BytesN::from_array(&env, &[42u8; 32]);
+    setup_mock_pool(&env, &amm_id, &market_id, 500_000_000, 500_000_000);
+
+    // Trade equal to 50% of the input reserve moves price by roughly 50%
+    let large_impact = client.get_price_impact(&market_id, &1u32, &250_000_000u128);
+    assert!(large_impact > 4500);
+    assert!(large_impact < 5500);
+
+    // Tiny trade barely moves the price
+    let small_impact = client.get_price_impact(&market_id, &1u32, &10_000u128);
+    assert!(small_impact < 10);
+
+    // Quote is read-only
+    let (yes_reserve, no_reserve, _, _, _) = client.get_pool_state(&market_id);
+    assert_eq!(yes_reserve, 500_000_000);
+    assert_eq!(no_reserve, 500_000_000);
+}
+
+#[test]
+fn test_settle_pool_from_market_lets_winners_redeem() {
+    let env = create_test_env();
+    env.mock_all_auths();
+    let amm_id = register_amm(&env);
+    let client = AMMClient::new(&env, &amm_id);
+
+    let admin = Address::generate(&env);
+    let factory = Address::generate(&env);
+    let yes_holder = Address::generate(&env);
+    let no_holder = Address::generate(&env);
+    let usdc_token = setup_usdc_token(&env, &amm_id, 1_000_000_000);
+    client.initialize(&admin, &factory, &usdc_token, &100_000_000_000u128);
+
+    let market_id = BytesN::from_array(&env, &[43u8; 32]);
+    setup_mock_pool(&env, &amm_id, &market_id, 500_000_000, 500_000_000);
+    env.as_contract(&amm_id, || {
+        env.storage().persistent().set(
+            &(
+                Symbol::new(&env, "user_shares"),
+                market_id.clone(),
+                yes_holder.clone(),
+                1u32,
+            ),
+            &300_000u128,
+        );
+        env.storage().persistent().set(
+            &(
+                Symbol::new(&env, "user_shares"),
+                market_id.clone(),
+                no_holder.clone(),
+                0u32,
+            ),
+            &200_000u128,
+        );
+    });
+
+    // Oracle reports YES
+    let oracle_id = env.register(MockOracle, ());
+    MockOracleClient::new(&env, &oracle_id).set_result(&1u32);
+
+    client.settle_pool_from_market(&factory, &market_id, &oracle_id);
+
+    // YES holder redeems at the fixed 1:1 price
+    let payout = client.redeem_shares(&yes_holder, &market_id);
+    assert_eq!(payout, 300_000);
+    let usdc = TokenClient::new(&env, &usdc_token);
+    assert_eq!(usdc.balance(&yes_holder), 300_000);
+
+    // Shares are burned and losing shares have nothing to redeem
+    assert!(client.try_redeem_shares(&yes_holder, &market_id).is_err());
+    assert!(client.try_redeem_shares(&no_holder, &market_id).is_err());
+}
+
+#[test]
+#[should_panic(expected = "unauthorized settlement caller")]
+fn test_settle_pool_from_market_rejects_other_callers() {
+    let env = create_test_env();
+    env.mock_all_auths();
+    let amm_id = register_amm(&env);
+    let client = AMMClient::new(&env, &amm_id);
+
+    let admin = Address::generate(&env);
+    let factory = Address::generate(&env);
+    let usdc_token = Address::generate(&env);
+    client.initialize(&admin, &factory, &usdc_token, &100_000_000_000u128);
+
+    let market_id = BytesN::from_array(&env, &[44u8; 32]);
+    setup_mock_pool(&env, &amm_id, &market_id, 500_000_000, 500_000_000);
+
+    let oracle_id = env.register(MockOracle, ());
+    MockOracleClient::new(&env, &oracle_id).set_result(&1u32);
+
+    let random = Address::generate(&env);
+    client.settle_pool_from_market(&random, &market_id, &oracle_id);
+}
+
+#[test]
+fn test_get_trade_count_tracks_trades() {
+    let env = create_test_env();
+    env.mock_all_auths();
+    let amm_id = register_amm(&env);
+    let client = AMMClient::new(&env, &amm_id);
+
+    let admin = Address::generate(&env);
+    let factory = Address::generate(&env);
+    let trader = Address::generate(&env);
+    let usdc_token = setup_usdc_token(&env, &trader, 10_000_000_000);
+    client.initialize(&admin, &factory, &usdc_token, &100_000_000_000u128);
+
+    let market_id = BytesN::from_array(&env, &[45u8; 32]);
+    setup_mock_pool(&env, &amm_id, &market_id, 5_000_000_000, 5_000_000_000);
+    assert_eq!(client.get_trade_count(&market_id), 0);
+
+    let shares = client.buy_shares(&trader, &market_id, &1u32, &100_000_000u128, &0u128);
+    client.buy_shares(&trader, &market_id, &0u32, &100_000_000u128, &0u128);
+    client.sell_shares(&trader, &market_id, &1u32, &shares, &0u128);
+
+    assert_eq!(client.get_trade_count(&market_id), 3);
+}
+
+#[test]
+fn test_trades_accrue_claimable_creator_royalty() {
+    let env = create_test_env();
+    env.mock_all_auths();
+    let amm_id = register_amm(&env);
+    let client = AMMClient::new(&env, &amm_id);
+
+    let admin = Address::generate(&env);
+    let factory = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let trader = Address::generate(&env);
+    let usdc_token = setup_usdc_token(&env, &creator, 10_000_000_000);
+    StellarAssetClient::new(&env, &usdc_token).mint(&trader, &1_000_000_000);
+    client.initialize(&admin, &factory, &usdc_token, &100_000_000_000u128);
+
+    // Creator takes half of every trading fee
+    let market_id = BytesN::from_array(&env, &[46u8; 32]);
+    client.create_pool(&creator, &market_id, &10_000_000_000u128, &5000u32, &0u64);
+
+    // 0.2% fee on 1,000 USDC = 2 USDC, half to the creator
+    client.buy_shares(&trader, &market_id, &1u32, &1_000_000_000u128, &0u128);
+    assert_eq!(client.get_creator_royalty(&market_id), 1_000_000);
+
+    let claimed = client.claim_creator_royalty(&creator, &market_id);
+    assert_eq!(claimed, 1_000_000);
+    assert_eq!(client.get_creator_royalty(&market_id), 0);
+    assert_eq!(
+        TokenClient::new(&env, &usdc_token).balance(&creator),
+        1_000_000
+    );
+}
+
+#[test]
+#[should_panic(expected = "creator royalty exceeds trading fee")]
+fn test_create_pool_rejects_royalty_above_fee() {
+    let env = create_test_env();
+    env.mock_all_auths();
+    let amm_id = register_amm(&env);
+    let client = AMMClient::new(&env, &amm_id);
+
+    let admin = Address::generate(&env);
+    let factory = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let usdc_token = setup_usdc_token(&env, &creator, 10_000_000_000);
+    client.initialize(&admin, &factory, &usdc_token, &100_000_000_000u128);
+
+    let market_id = BytesN::from_array(&env, &[47u8; 32]);
+    client.create_pool(&creator, &market_id, &10_000_000_000u128, &10_001u32, &0u64);
+}
+
+#[test]
+fn test_fee_free_window_waives_trading_fee() {
+    let env = create_test_env();
+    env.mock_all_auths();
+    let amm_id = register_amm(&env);
+    let client = AMMClient::new(&env, &amm_id);
+
+    let admin = Address::generate(&env);
+    let factory = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let trader = Address::generate(&env);
+    let usdc_token = setup_usdc_token(&env, &creator, 10_000_000_000);
+    StellarAssetClient::new(&env, &usdc_token).mint(&trader, &2_000_000_000);
+    client.initialize(&admin, &factory, &usdc_token, &100_000_000_000u128);
+
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let market_id = BytesN::from_array(&env, &[48u8; 32]);
+    client.create_pool(&creator, &market_id, &10_000_000_000u128, &0u32, &2_000u64);
+
+    // Inside the window the full amount enters the NO reserve
+    let amount = 1_000_000_000u128;
+    client.buy_shares(&trader, &market_id, &1u32, &amount, &0u128);
+    let (_, no_reserve) = env.as_contract(&amm_id, || get_pool_reserves(&env, &market_id));
+    assert_eq!(no_reserve, 5_000_000_000 + amount);
+
+    // After the window the normal 0.2% fee applies
+    env.ledger().with_mut(|li| li.timestamp = 2_000);
+    client.buy_shares(&trader, &market_id, &1u32, &amount, &0u128);
+    let (_, no_reserve_after) = env.as_contract(&amm_id, || get_pool_reserves(&env, &market_id));
+    assert_eq!(no_reserve_after, no_reserve + amount - amount * 20 / 10000);
+}
+
+#[test]
+fn test_add_liquidity_keeps_odds_and_mints_lp() {
+    let env = create_test_env();
+    env.mock_all_auths();
+    let amm_id = register_amm(&env);
+    let client = AMMClient::new(&env, &amm_id);
+
+    let admin = Address::generate(&env);
+    let factory = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let provider = Address::generate(&env);
+    let usdc_token = setup_usdc_token(&env, &creator, 10_000_000_000);
+    StellarAssetClient::new(&env, &usdc_token).mint(&provider, &5_000_000_000);
+    client.initialize(&admin, &factory, &usdc_token, &100_000_000_000u128);
+
+    let market_id = BytesN::from_array(&env, &[49u8; 32]);
+    client.create_pool(&creator, &market_id, &10_000_000_000u128, &0u32, &0u64);
+
+    let lp_tokens = client.add_liquidity(&provider, &market_id, &5_000_000_000u128);
+    assert_eq!(lp_tokens, 5_000_000_000);
+
+    let (yes_reserve, no_reserve, total, yes_odds, no_odds) = client.get_pool_state(&market_id);
+    assert_eq!(yes_reserve, 7_500_000_000);
+    assert_eq!(no_reserve, 7_500_000_000);
+    assert_eq!(total, 15_000_000_000);
+    assert_eq!((yes_odds, no_odds), (5000, 5000));
+}
+
+/// Creates a pool with a YES holder, then settles it via the factory
+fn setup_settled_pool(env: &Env) -> (AMMClient<'_>, Address, BytesN<32>) {
+    let amm_id = register_amm(env);
+    let client = AMMClient::new(env, &amm_id);
+
+    let admin = Address::generate(env);
+    let factory = Address::generate(env);
+    let trader = Address::generate(env);
+    let usdc_token = setup_usdc_token(env, &trader, 10_000_000_000);
+    client.initialize(&admin, &factory, &usdc_token, &100_000_000_000u128);
+
+    let market_id = BytesN::from_array(env, &[50u8; 32]);
+    setup_mock_pool(env, &amm_id, &market_id, 5_000_000_000, 5_000_000_000);
+    client.buy_shares(&trader, &market_id, &1u32, &100_000_000u128, &0u128);
+
+    let oracle_id = env.register(MockOracle, ());
+    MockOracleClient::new(env, &oracle_id).set_result(&1u32);
+    client.settle_pool_from_market(&factory, &market_id, &oracle_id);
+
+    (client, trader, market_id)
+}
+
+#[test]
+#[should_panic(expected = "pool settled")]
+fn test_buy_shares_rejected_after_settlement() {
+    let env = create_test_env();
+    env.mock_all_auths();
+    let (client, trader, market_id) = setup_settled_pool(&env);
+
+    client.buy_shares(&trader, &market_id, &1u32, &100_000_000u128, &0u128);
+}
+
+#[test]
+#[should_panic(expected = "pool settled")]
+fn test_sell_shares_rejected_after_settlement() {
+    let env = create_test_env();
+    env.mock_all_auths();
+    let (client, trader, market_id) = setup_settled_pool(&env);
+
+    client.sell_shares(&trader, &market_id, &1u32, &1_000u128, &0u128);
+}
+
+#[test]
+#[should_panic(expected = "pool settled")]
+fn test_add_liquidity_rejected_after_settlement() {
+    let env = create_test_env();
+    env.mock_all_auths();
+    let (client, trader, market_id) = setup_settled_pool(&env);
+
+    client.add_liquidity(&trader, &market_id, &100_000_000u128);
+}
+
+#[test]
+fn test_redeem_shares_allowed_after_settlement() {
+    let env = create_test_env();
+    env.mock_all_auths();
+    let (client, trader, market_id) = setup_settled_pool(&env);
+
+    assert!(client.redeem_shares(&trader, &market_id) > 0);
+}
+
+#[test]
+fn test_get_user_pool_position_values_at_quote_sell() {
+    let env = create_test_env();
+    env.mock_all_auths();
+    let amm_id = register_amm(&env);
+    let client = AMMClient::new(&env, &amm_id);
+
+    let admin = Address::generate(&env);
+    let factory = Address::generate(&env);
+    let trader = Address::generate(&env);
+    let usdc_token = setup_usdc_token(&env, &trader, 10_000_000_000);
+    client.initialize(&admin, &factory, &usdc_token, &100_000_000_000u128);
+
+    let market_id = BytesN::from_array(&env, &[51u8; 32]);
+    setup_mock_pool(&env, &amm_id, &market_id, 5_000_000_000, 5_000_000_000);
+    let shares = client.buy_shares(&trader, &market_id, &1u32, &100_000_000u128, &0u128);
+
+    let (yes_shares, no_shares, value) = client.get_user_pool_position(&trader, &market_id);
+    assert_eq!(yes_shares, shares);
+    assert_eq!(no_shares, 0);
+    assert_eq!(value, client.quote_sell(&market_id, &1u32, &shares));
+
+    // Value matches what actually selling the shares yields
+    let payout = client.sell_shares(&trader, &market_id, &1u32, &shares, &0u128);
+    assert_eq!(value, payout);
+}
+
+#[test]
+fn test_lp_impermanent_loss_after_odds_shift() {
+    let env = create_test_env();
+    env.mock_all_auths();
+    let amm_id = register_amm(&env);
+    let client = AMMClient::new(&env, &amm_id);
+
+    let admin = Address::generate(&env);
+    let factory = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let trader = Address::generate(&env);
+    let usdc_token = setup_usdc_token(&env, &creator, 10_000_000_000);
+    StellarAssetClient::new(&env, &usdc_token).mint(&trader, &10_000_000_000);
+    client.initialize(&admin, &factory, &usdc_token, &100_000_000_000u128);
+
+    let market_id = BytesN::from_array(&env, &[52u8; 32]);
+    client.create_pool(&creator, &market_id, &10_000_000_000u128, &0u32, &0u64);
+    assert_eq!(client.get_lp_impermanent_loss(&market_id, &creator), 0);
+
+    // A large YES buy moves the odds away from the LP's 50/50 entry
+    client.buy_shares(&trader, &market_id, &1u32, &5_000_000_000u128, &0u128);
+    let (yes_odds, _) = client.get_odds(&market_id);
+    assert!(yes_odds > 5000);
+
+    assert!(client.get_lp_impermanent_loss(&market_id, &creator) < 0);
+
+    // Accounts that never provided liquidity have no position
+    assert_eq!(client.get_lp_impermanent_loss(&market_id, &trader), 0);
+}
+
+#[test]
+fn test_protocol_fees_accrued_with_half_split() {
+    let env = create_test_env();
+    env.mock_all_auths();
+    let amm_id = register_amm(&env);
+    let client = AMMClient::new(&env, &amm_id);
+
+    let admin = Address::generate(&env);
+    let factory = Address::generate(&env);
+    let trader = Address::generate(&env);
+    let usdc_token = setup_usdc_token(&env, &trader, 10_000_000_000);
+    client.initialize(&admin, &factory, &usdc_token, &100_000_000_000u128);
+    client.set_protocol_fee_split(&admin, &5000u32);
+
+    let market_id = BytesN::from_array(&env, &[53u8; 32]);
+    setup_mock_pool(&env, &amm_id, &market_id, 5_000_000_000, 5_000_000_000);
+    assert_eq!(client.get_fee_tier(&market_id), 20);
+
+    // 0.2% of 100 USDC is 0.2 USDC in fees; half goes to the protocol
+    client.buy_shares(&trader, &market_id, &1u32, &1_000_000_000u128, &0u128);
+    assert_eq!(client.get_protocol_fees_accrued(&market_id), 1_000_000);
+
+    // A per-pool override replaces the global fee
+    client.set_pool_fee_override(&admin, &market_id, &100u32);
+    assert_eq!(client.get_fee_tier(&market_id), 100);
+    client.buy_shares(&trader, &market_id, &0u32, &1_000_000_000u128, &0u128);
+    assert_eq!(client.get_protocol_fees_accrued(&market_id), 6_000_000);
+}
+
+#[test]
+fn test_sweep_protocol_fees_to_treasury() {
+    let env = create_test_env();
+    env.mock_all_auths();
+    let amm_id = register_amm(&env);
+    let client = AMMClient::new(&env, &amm_id);
+
+    let admin = Address::generate(&env);
+    let factory = Address::generate(&env);
+    let trader = Address::generate(&env);
+    let usdc_token = setup_usdc_token(&env, &trader, 10_000_000_000);
+    client.initialize(&admin, &factory, &usdc_token, &100_000_000_000u128);
+    client.set_protocol_fee_split(&admin, &5000u32);
+
+    let treasury_id = env.register(Treasury, ());
+    let treasury = TreasuryClient::new(&env, &treasury_id);
+    treasury.initialize(&admin, &usdc_token, &factory);
+    treasury.register_market_depositor(&admin, &amm_id);
+    client.set_treasury(&admin, &treasury_id);
+
+    let market_id = BytesN::from_array(&env, &[54u8; 32]);
+    setup_mock_pool(&env, &amm_id, &market_id, 5_000_000_000, 5_000_000_000);
+    client.buy_shares(&trader, &market_id, &1u32, &1_000_000_000u128, &0u128);
+    let accrued = client.get_protocol_fees_accrued(&market_id);
+    assert_eq!(accrued, 1_000_000);
+
+    let swept = client.sweep_protocol_fees(&market_id);
+    assert_eq!(swept, accrued);
+    assert_eq!(client.get_protocol_fees_accrued(&market_id), 0);
+    assert_eq!(treasury.get_platform_fees(), accrued as i128);
+    assert_eq!(
+        TokenClient::new(&env, &usdc_token).balance(&treasury_id),
+        accrued as i128
+    );
+}
+
+#[test]
+fn test_get_pool_created_at_matches_ledger() {
+    let env = create_test_env();
+    env.mock_all_auths();
+    let amm_id = register_amm(&env);
+    let client = AMMClient::new(&env, &amm_id);
+
+    let admin = Address::generate(&env);
+    let factory = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let usdc_token = setup_usdc_token(&env, &creator, 10_000_000_000);
+    client.initialize(&admin, &factory, &usdc_token, &100_000_000_000u128);
+
+    env.ledger().with_mut(|li| li.timestamp = 1_700_000_000);
+    let market_id = BytesN::from_array(&env, &[55u8; 32]);
+    client.create_pool(&creator, &market_id, &1_000_000_000u128, &0u32, &0u64);
+
+    env.ledger().with_mut(|li| li.timestamp += 3600);
+    assert_eq!(client.get_pool_created_at(&market_id), 1_700_000_000);
+}
+
+#[test]
+fn test_market_liquidity_cap_override() {
+    let env = create_test_env();
+    env.mock_all_auths();
+    let amm_id = register_amm(&env);
+    let client = AMMClient::new(&env, &amm_id);
+
+    let admin = Address::generate(&env);
+    let factory = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let usdc_token = setup_usdc_token(&env, &creator, 100_000_000_000);
+    // Global cap leaves no room above the initial liquidity
+    client.initialize(&admin, &factory, &usdc_token, &10_000_000_000u128);
+
+    let market_id = BytesN::from_array(&env, &[56u8; 32]);
+    client.create_pool(&creator, &market_id, &10_000_000_000u128, &0u32, &0u64);
+    assert!(client
+        .try_add_liquidity(&creator, &market_id, &5_000_000_000u128)
+        .is_err());
+
+    client.set_market_liquidity_cap(&admin, &market_id, &20_000_000_000u128);
+    assert_eq!(client.get_market_liquidity_cap(&market_id), 20_000_000_000);
+    client.add_liquidity(&creator, &market_id, &5_000_000_000u128);
+
+    let (_, _, total, _, _) = client.get_pool_state(&market_id);
+    assert_eq!(total, 15_000_000_000);
+}
+
+#[test]
+#[should_panic(expected = "cap below current liquidity")]
+fn test_market_liquidity_cap_below_liquidity_rejected() {
+    let env = create_test_env();
+    env.mock_all_auths();
+    let amm_id = register_amm(&env);
+    let client = AMMClient::new(&env, &amm_id);
+
+    let admin = Address::generate(&env);
+    let factory = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let usdc_token = setup_usdc_token(&env, &creator, 100_000_000_000);
+    client.initialize(&admin, &factory, &usdc_token, &100_000_000_000u128);
+
+    let market_id = BytesN::from_array(&env, &[57u8; 32]);
+    client.create_pool(&creator, &market_id, &10_000_000_000u128, &0u32, &0u64);
+
+    client.set_market_liquidity_cap(&admin, &market_id, &5_000_000_000u128);
+}
+
+#[test]
+fn test_verify_pool_invariants() {
+    let env = create_test_env();
+    env.mock_all_auths();
+    let amm_id = register_amm(&env);
+    let client = AMMClient::new(&env, &amm_id);
+
+    let admin = Address::generate(&env);
+    let factory = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let usdc_token = setup_usdc_token(&env, &creator, 10_000_000_000);
+    client.initialize(&admin, &factory, &usdc_token, &100_000_000_000u128);
+
+    let market_id = BytesN::from_array(&env, &[56u8; 32]);
+    assert!(!client.verify_pool_invariants(&market_id));
+
+    client.create_pool(&creator, &market_id, &1_000_000_000u128, &0u32, &0u64);
+    assert!(client.verify_pool_invariants(&market_id));
+
+    // Trades checkpoint the reserves they leave behind
+    let shares = client.buy_shares(&creator, &market_id, &1u32, &100_000_000u128, &1u128);
+    assert!(client.verify_pool_invariants(&market_id));
+    client.sell_shares(&creator, &market_id, &1u32, &(shares / 2), &1u128);
+    assert!(client.verify_pool_invariants(&market_id));
+
+    // Inflating one reserve grows k, but no longer matches the checkpoint
+    let (_, no_reserve, _, _, _) = client.get_pool_state(&market_id);
+    env.as_contract(&amm_id, || {
+        env.storage().persistent().set(
+            &(Symbol::new(&env, POOL_NO_RESERVE), market_id.clone()),
+            &(no_reserve + 1),
+        );
+    });
+    assert!(!client.verify_pool_invariants(&market_id));
+    env.as_contract(&amm_id, || {
+        env.storage().persistent().set(
+            &(Symbol::new(&env, POOL_NO_RESERVE), market_id.clone()),
+            &no_reserve,
+        );
+    });
+    assert!(client.verify_pool_invariants(&market_id));
+
+    // Drain YES reserve behind the AMM's back so k no longer holds
+    env.as_contract(&amm_id, || {
+        env.storage().persistent().set(
+            &(Symbol::new(&env, POOL_YES_RESERVE), market_id.clone()),
+            &1_000u128,
+        );
+    });
+    assert!(!client.verify_pool_invariants(&market_id));
+}
+
+/// Pool created by `creator` with 1,000 USDC liquidity and 1,000 USDC left over
+fn setup_cancellable_pool(env: &Env) -> (AMMClient<'_>, Address, Address, BytesN<32>) {
+    env.mock_all_auths();
+    let amm_id = register_amm(env);
+    let client = AMMClient::new(env, &amm_id);
+
+    let creator = Address::generate(env);
+    let usdc_token = setup_usdc_token(env, &creator, 2_000_000_000);
+    client.initialize(
+        &Address::generate(env),
+        &Address::generate(env),
+        &usdc_token,
+        &100_000_000_000u128,
+    );
+
+    let market_id = BytesN::from_array(env, &[57u8; 32]);
+    client.create_pool(&creator, &market_id, &1_000_000_000u128, &0u32, &0u64);
+    (client, usdc_token, creator, market_id)
+}
+
+#[test]
+fn test_create_pools_batch_skips_existing_pool() {
+    let env = create_test_env();
+    let (client, usdc_token, creator, existing_id) = setup_cancellable_pool(&env);
+    let usdc = TokenClient::new(&env, &usdc_token);
+    let first = BytesN::from_array(&env, &[1u8; 32]);
+    let second = BytesN::from_array(&env, &[2u8; 32]);
+
+    let pools = vec![
+        &env,
+        (first.clone(), 200_000_000u128),
+        (existing_id.clone(), 500_000_000u128),
+        (second.clone(), 300_000_000u128),
+    ];
+    assert_eq!(client.create_pools_batch(&creator, &pools), 2);
+
+    // One transfer covers only the pools actually created
+    assert_eq!(usdc.balance(&creator), 500_000_000);
+    assert_eq!(usdc.balance(&client.address), 1_500_000_000);
+    assert_eq!(client.get_pool_state(&first).0, 100_000_000);
+    assert_eq!(client.get_pool_state(&second).0, 150_000_000);
+    // The pre-existing pool is untouched
+    assert_eq!(client.get_pool_state(&existing_id).0, 500_000_000);
+}
+
+#[test]
+fn test_cancel_pool_refunds_creator() {
+    let env = create_test_env();
+    let (client, usdc_token, creator, market_id) = setup_cancellable_pool(&env);
+    let usdc = TokenClient::new(&env, &usdc_token);
+
+    assert_eq!(client.cancel_pool(&creator, &market_id), 1_000_000_000);
+    assert_eq!(usdc.balance(&creator), 2_000_000_000);
+    assert_eq!(usdc.balance(&client.address), 0);
+    assert_eq!(client.get_pool_state(&market_id).2, 0);
+
+    // Storage is cleared, so the pool can be recreated
+    client.create_pool(&creator, &market_id, &500_000_000u128, &0u32, &0u64);
+}
+
+#[test]
+#[should_panic(expected = "pool has other liquidity providers")]
+fn test_cancel_pool_rejected_with_second_lp() {
+    let env = create_test_env();
+    let (client, usdc_token, creator, market_id) = setup_cancellable_pool(&env);
+
+    let lp2 = Address::generate(&env);
+    StellarAssetClient::new(&env, &usdc_token).mint(&lp2, &100_000_000);
+    client.add_liquidity(&lp2, &market_id, &100_000_000u128);
+
+    client.cancel_pool(&creator, &market_id);
+}
+
+#[test]
+#[should_panic(expected = "pool has trades")]
+fn test_cancel_pool_rejected_after_trade() {
+    let env = create_test_env();
+    let (client, usdc_token, creator, market_id) = setup_cancellable_pool(&env);
+
+    let buyer = Address::generate(&env);
+    StellarAssetClient::new(&env, &usdc_token).mint(&buyer, &10_000_000);
+    client.buy_shares(&buyer, &market_id, &1u32, &10_000_000u128, &1u128);
+
+    client.cancel_pool(&creator, &market_id);
+}
+
+#[test]
+fn test_total_value_locked_tracks_liquidity() {
+    let env = create_test_env();
+    env.mock_all_auths();
+    let amm_id = register_amm(&env);
+    let client = AMMClient::new(&env, &amm_id);
+
+    let creator = Address::generate(&env);
+    let usdc_token = setup_usdc_token(&env, &creator, 10_000_000_000);
+    client.initialize(
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &usdc_token,
+        &100_000_000_000u128,
+    );
+    assert_eq!(client.get_total_value_locked(), 0);
+
+    let market_a = BytesN::from_array(&env, &[58u8; 32]);
+    let market_b = BytesN::from_array(&env, &[59u8; 32]);
+    client.create_pool(&creator, &market_a, &1_000_000_000u128, &0u32, &0u64);
+    client.create_pool(&creator, &market_b, &2_000_000_000u128, &0u32, &0u64);
+    assert_eq!(client.get_total_value_locked(), 3_000_000_000);
+
+    client.add_liquidity(&creator, &market_a, &500_000_000u128);
+    assert_eq!(client.get_total_value_locked(), 3_500_000_000);
+
+    // Removing half of market B's LP tokens returns half its reserves
+    client.remove_liquidity(&creator, &market_b, &1_000_000_000u128);
+    assert_eq!(client.get_total_value_locked(), 2_500_000_000);
+}
+
+/// Slippage field of the most recent `buy_shares` / `sell_shares` event
+fn last_trade_slippage(env: &Env, amm: &Address, name: &str) -> u32 {
+    let topics: soroban_sdk::Vec<Val> = (Symbol::new(env, name),).into_val(env);
+    let (_, _, data) = env
+        .events()
+        .all()
+        .iter()
+        .filter(|(address, event_topics, _)| address == amm && *event_topics == topics)
+        .last()
+        .expect("trade event not emitted");
+    let payload: (Address, BytesN<32>, u32, u128, u128, u128, u32) =
+        TryFromVal::try_from_val(env, &data).unwrap();
+    payload.6
+}
+
+#[test]
+fn test_trade_events_report_slippage() {
+    let env = create_test_env();
+    let (client, usdc_token, _creator, market_id) = setup_cancellable_pool(&env);
+
+    let trader = Address::generate(&env);
+    StellarAssetClient::new(&env, &usdc_token).mint(&trader, &600_000_000);
+
+    // A tiny buy barely moves the 1,000 USDC pool
+    client.buy_shares(&trader, &market_id, &1u32, &10_000u128, &1u128);
+    assert!(last_trade_slippage(&env, &client.address, "buy_shares") <= 1);
+
+    // Half the pool's depth pays well above spot
+    let shares = client.buy_shares(&trader, &market_id, &1u32, &500_000_000u128, &1u128);
+    assert!(last_trade_slippage(&env, &client.address, "buy_shares") > 1000);
+
+    client.sell_shares(&trader, &market_id, &1u32, &shares, &1u128);
+    assert!(last_trade_slippage(&env, &client.address, "sell_shares") > 0);
+}
+
+#[test]
+fn test_pool_volume_accumulates_trade_sizes() {
+    let env = create_test_env();
+    let (client, usdc_token, _creator, market_id) = setup_cancellable_pool(&env);
+    assert_eq!(client.get_pool_volume(&market_id), 0);
+
+    let trader = Address::generate(&env);
+    StellarAssetClient::new(&env, &usdc_token).mint(&trader, &150_000_000);
+
+    // Buys count the full USDC amount, fees included
+    let shares = client.buy_shares(&trader, &market_id, &1u32, &100_000_000u128, &1u128);
+    client.buy_shares(&trader, &market_id, &0u32, &50_000_000u128, &1u128);
+    assert_eq!(client.get_pool_volume(&market_id), 150_000_000);
+
+    // Sells count the USDC paid out to the seller
+    let payout = client.sell_shares(&trader, &market_id, &1u32, &shares, &1u128);
+    assert_eq!(client.get_pool_volume(&market_id), 150_000_000 + payout);
+    assert_eq!(client.get_trade_count(&market_id), 3);
+}
+
+#[test]
+fn test_buy_shares_rejects_zero_shares_out() {
+    let env = create_test_env();
+    env.mock_all_auths();
+    let amm_id = register_amm(&env);
+    let client = AMMClient::new(&env, &amm_id);
+
+    let trader = Address::generate(&env);
+    let usdc_token = setup_usdc_token(&env, &trader, 1_000);
+    client.initialize(
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &usdc_token,
+        &100_000_000_000u128,
+    );
+
+    // A YES reserve of 10 against 10,000 USDC of NO rounds a tiny buy down to 0 shares
+    let market_id = BytesN::from_array(&env, &[60u8; 32]);
+    setup_mock_pool(&env, &amm_id, &market_id, 10, 100_000_000_000);
+
+    let result = client.try_buy_shares(&trader, &market_id, &1u32, &1_000u128, &0u128);
+    assert!(result.is_err());
+    assert_eq!(TokenClient::new(&env, &usdc_token).balance(&trader), 1_000);
+    assert_eq!(client.get_trade_count(&market_id), 0);
+}
+
+#[test]
+fn test_sell_shares_rejects_zero_payout() {
+    let env = create_test_env();
+    env.mock_all_auths();
+    let amm_id = register_amm(&env);
+    let client = AMMClient::new(&env, &amm_id);
+
+    let seller = Address::generate(&env);
+    let usdc_token = setup_usdc_token(&env, &amm_id, 1_000_000_000);
+    client.initialize(
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &usdc_token,
+        &100_000_000_000u128,
+    );
+
+    // A NO reserve of 10 against 100B YES pays 0 for a small YES sale
+    let market_id = BytesN::from_array(&env, &[61u8; 32]);
+    setup_mock_pool(&env, &amm_id, &market_id, 100_000_000_000, 10);
+    env.as_contract(&amm_id, || {
+        env.storage().persistent().set(
+            &(
+                Symbol::new(&env, "user_shares"),
+                market_id.clone(),
+                seller.clone(),
+                1u32,
+            ),
+            &1_000u128,
+        );
+    });
+
+    let result = client.try_sell_shares(&seller, &market_id, &1u32, &1_000u128, &0u128);
+    assert!(result.is_err());
+    assert_eq!(client.get_user_pool_position(&seller, &market_id).0, 1_000);
+    assert_eq!(TokenClient::new(&env, &usdc_token).balance(&seller), 0);
+}
+
+#[test]
+fn test_usdc_to_reach_odds_moves_price_to_target() {
+    let env = create_test_env();
+    let (client, usdc_token, _creator, market_id) = setup_cancellable_pool(&env);
+    assert_eq!(client.get_odds(&market_id), (5000, 5000));
+
+    // Already at or past the target in this direction
+    assert_eq!(client.usdc_to_reach_odds(&market_id, &1u32, &4000u32), 0);
+    assert_eq!(client.usdc_to_reach_odds(&market_id, &1u32, &5000u32), 0);
+
+    let amount = client.usdc_to_reach_odds(&market_id, &1u32, &6000u32);
+    assert!(amount > 0);
+    let trader = Address::generate(&env);
+    StellarAssetClient::new(&env, &usdc_token).mint(&trader, &(amount as i128));
+    client.buy_shares(&trader, &market_id, &1u32, &amount, &1u128);
+
+    let (yes_odds, _) = client.get_odds(&market_id);
+    assert!((5998..=6002).contains(&yes_odds), "yes odds {}", yes_odds);
+    assert_eq!(client.usdc_to_reach_odds(&market_id, &1u32, &5900u32), 0);
+}
+
+#[test]
+fn test_price_history_len_grows_with_each_trade() {
+    let env = create_test_env();
+    let (client, usdc_token, _creator, market_id) = setup_cancellable_pool(&env);
+    assert_eq!(client.get_price_history_len(&market_id), 0);
+
+    let trader = Address::generate(&env);
+    StellarAssetClient::new(&env, &usdc_token).mint(&trader, &200_000_000);
+    let shares = client.buy_shares(&trader, &market_id, &1u32, &100_000_000u128, &1u128);
+    assert_eq!(client.get_price_history_len(&market_id), 1);
+    client.buy_shares(&trader, &market_id, &0u32, &50_000_000u128, &1u128);
+    assert_eq!(client.get_price_history_len(&market_id), 2);
+    client.sell_shares(&trader, &market_id, &1u32, &shares, &1u128);
+    assert_eq!(client.get_price_history_len(&market_id), 3);
+
+    // Each sample is the YES odds right after its trade
+    let history = client.get_price_history(&market_id, &0u32, &10u32);
+    assert_eq!(history.len(), 3);
+    assert!(history.get(0).unwrap().1 > 5000);
+    assert_eq!(history.get(2).unwrap().1, client.get_odds(&market_id).0);
+
+    // Pages are read straight from the per-sample entries
+    let page = client.get_price_history(&market_id, &1u32, &10u32);
+    assert_eq!(page.len(), 2);
+    assert_eq!(page.get(0), history.get(1));
+    assert_eq!(client.get_price_history(&market_id, &3u32, &10u32).len(), 0);
+}
+
+#[test]
+fn test_marginal_price_rises_as_reserve_shrinks() {
+    let env = create_test_env();
+    let (client, usdc_token, _creator, market_id) = setup_cancellable_pool(&env);
+
+    // Balanced pool: one more share costs exactly 1 USDC either way
+    assert_eq!(client.marginal_price(&market_id, &1u32), 10_000_000);
+    assert_eq!(client.marginal_price(&market_id, &0u32), 10_000_000);
+
+    // Buying YES shrinks the YES reserve, so the next YES share costs more
+    let trader = Address::generate(&env);
+    StellarAssetClient::new(&env, &usdc_token).mint(&trader, &100_000_000);
+    client.buy_shares(&trader, &market_id, &1u32, &100_000_000u128, &1u128);
+
+    let yes_price = client.marginal_price(&market_id, &1u32);
+    let no_price = client.marginal_price(&market_id, &0u32);
+    assert!(yes_price > 10_000_000);
+    assert!(no_price < 10_000_000);
+    let (yes_reserve, no_reserve, _, _, _) = client.get_pool_state(&market_id);
+    assert_eq!(yes_price, no_reserve * 10_000_000 / yes_reserve);
+}
+
+#[test]
+fn test_get_odds_at_amounts_prices_worsen_with_size() {
+    let env = create_test_env();
+    let (client, _usdc_token, _creator, market_id) = setup_cancellable_pool(&env);
+
+    let amounts = Vec::from_array(&env, [1_000_000u128, 100_000_000, 500_000_000]);
+    let prices = client.get_odds_at_amounts(&market_id, &1u32, &amounts);
+    assert_eq!(prices.len(), 3);
+
+    // A small buy in a balanced pool pays about the 1:1 spot price plus the 0.2% fee
+    assert!((10000..10050).contains(&prices.get(0).unwrap()));
+    assert!(prices.get(1).unwrap() > prices.get(0).unwrap());
+    assert!(prices.get(2).unwrap() > prices.get(1).unwrap());
+
+    // Quoting leaves the pool untouched
+    assert_eq!(client.get_odds(&market_id), (5000, 5000));
+    assert_eq!(client.get_trade_count(&market_id), 0);
+}
+
+#[test]
+#[should_panic(expected = "too many amounts")]
+fn test_get_odds_at_amounts_rejects_more_than_20() {
+    let env = create_test_env();
+    let (client, _usdc_token, _creator, market_id) = setup_cancellable_pool(&env);
+
+    let mut amounts = Vec::new(&env);
+    for i in 1..=21u128 {
+        amounts.push_back(i * 1_000_000);
+    }
+    client.get_odds_at_amounts(&market_id, &1u32, &amounts);
+}
+
+#[test]
+fn test_check_solvency_detects_shortfall() {
+    let env = create_test_env();
+    let (client, usdc_token, creator, market_id) = setup_cancellable_pool(&env);
+    client.buy_shares(&creator, &market_id, &1u32, &10_000_000u128, &1u128);
+
+    let (balance, obligations, solvent) = client.check_solvency();
+    assert!(solvent);
+    assert!(balance >= obligations);
+    assert!(obligations >= 1_000_000_000);
+
+    // Drain USDC out from under the pools
+    TokenClient::new(&env, &usdc_token).burn(&client.address, &100_000_000i128);
+    let (balance_after, obligations_after, solvent_after) = client.check_solvency();
+    assert_eq!(balance_after, balance - 100_000_000);
+    assert_eq!(obligations_after, obligations);
+    assert!(!solvent_after);
+}
@@ -1,12 +1,17 @@
 #![cfg(test)]
 
 use soroban_sdk::{
+    contract, contractimpl,
     testutils::{Address as _, Ledger},
-    token, Address, BytesN, Env, Symbol,
+    token, Address, Bytes, BytesN, Env, Symbol, Vec,
 };
 
 // Import the Factory contract
-use boxmeout::{MarketFactory, MarketFactoryClient};
+use boxmeout::{
+    AMMClient, MarketConfig, MarketFactory, MarketFactoryClient, OracleConfig, OracleManager,
+    OracleManagerClient, PlatformConfig, PredictionMarket, PredictionMarketClient, Treasury,
+    TreasuryClient, AMM,
+};
 
 // Helper function to create test environment
 fn create_test_env() -> Env {
@@ -24,6 +29,17 @@ fn create_mock_token(env: &Env, admin: &Address) -> Address {
     token_address.address()
 }
 
+// Minimal token reporting 6 decimals, like most non-Stellar stablecoins
+#[contract]
+struct SixDecimalToken;
+
+#[contractimpl]
+impl SixDecimalToken {
+    pub fn decimals(_env: Env) -> u32 {
+        6
+    }
+}
+
 #[test]
 fn test_factory_initialize() {
     let env = create_test_env();
@@ -44,6 +60,43 @@ fn test_factory_initialize() {
     assert_eq!(market_count, 0);
 }
 
+#[test]
+fn test_factory_getters_return_initialized_addresses() {
+    let env = create_test_env();
+    let factory_id = register_factory(&env);
+    let client = MarketFactoryClient::new(&env, &factory_id);
+
+    let admin = Address::generate(&env);
+    let usdc = Address::generate(&env);
+    let treasury = Address::generate(&env);
+
+    env.mock_all_auths();
+    client.initialize(&admin, &usdc, &treasury);
+
+    assert_eq!(client.get_admin(), admin);
+    assert_eq!(client.get_usdc(), usdc);
+    assert_eq!(client.get_treasury(), treasury);
+}
+
+#[test]
+fn test_creation_fee_scales_with_token_decimals() {
+    let env = create_test_env();
+    env.mock_all_auths();
+
+    // Stellar asset contracts use 7 decimals
+    let admin = Address::generate(&env);
+    let usdc7 = create_mock_token(&env, &admin);
+    let client7 = MarketFactoryClient::new(&env, &register_factory(&env));
+    client7.initialize(&admin, &usdc7, &Address::generate(&env));
+    assert_eq!(client7.get_creation_fee(), 10_000_000);
+
+    // A 6-decimal stablecoin still charges exactly 1 token
+    let usdc6 = env.register_contract(None, SixDecimalToken);
+    let client6 = MarketFactoryClient::new(&env, &register_factory(&env));
+    client6.initialize(&admin, &usdc6, &Address::generate(&env));
+    assert_eq!(client6.get_creation_fee(), 1_000_000);
+}
+
 #[test]
 #[should_panic(expected = "already initialized")]
 fn test_factory_initialize_twice_fails() {
@@ -63,6 +116,110 @@ fn test_factory_initialize_twice_fails() {
     client.initialize(&admin, &usdc, &treasury);
 }
 
+#[test]
+fn test_initialize_platform_wires_all_contracts() {
+    let env = create_test_env();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let usdc = create_mock_token(&env, &admin);
+    let factory_id = register_factory(&env);
+    let treasury_id = env.register_contract(None, Treasury);
+    let oracle_id = env.register_contract(None, OracleManager);
+    let amm_id = env.register_contract(None, AMM);
+
+    let client = MarketFactoryClient::new(&env, &factory_id);
+    client.initialize_platform(
+        &admin,
+        &PlatformConfig {
+            usdc: usdc.clone(),
+            treasury: treasury_id.clone(),
+            oracle: oracle_id.clone(),
+            amm: amm_id.clone(),
+            required_consensus: 2,
+            max_liquidity_cap: 100_000_000_000,
+        },
+    );
+
+    assert_eq!(client.get_admin(), admin);
+    assert_eq!(client.get_usdc(), usdc);
+    assert_eq!(client.get_treasury(), treasury_id);
+    assert_eq!(client.get_amm_address(), amm_id);
+
+    let treasury = TreasuryClient::new(&env, &treasury_id);
+    assert_eq!(treasury.get_admin(), admin);
+    assert_eq!(treasury.get_usdc(), usdc);
+    assert_eq!(treasury.get_factory(), factory_id);
+
+    let oracle = OracleManagerClient::new(&env, &oracle_id);
+    assert_eq!(oracle.get_admin(), admin);
+    assert_eq!(oracle.get_required_consensus(), 2);
+
+    let amm = AMMClient::new(&env, &amm_id);
+    assert_eq!(amm.get_factory(), factory_id);
+    assert_eq!(amm.get_treasury(), treasury_id);
+    assert_eq!(amm.get_config().3, 100_000_000_000);
+    assert!(treasury.is_authorized_depositor(&amm_id));
+}
+
+#[test]
+#[should_panic(expected = "treasury wired to a different factory")]
+fn test_initialize_platform_rejects_miswired_treasury() {
+    let env = create_test_env();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let usdc = create_mock_token(&env, &admin);
+    let treasury_id = env.register_contract(None, Treasury);
+    TreasuryClient::new(&env, &treasury_id).initialize(&admin, &usdc, &Address::generate(&env));
+
+    let client = MarketFactoryClient::new(&env, &register_factory(&env));
+    client.initialize_platform(
+        &admin,
+        &PlatformConfig {
+            usdc,
+            treasury: treasury_id,
+            oracle: env.register_contract(None, OracleManager),
+            amm: env.register_contract(None, AMM),
+            required_consensus: 2,
+            max_liquidity_cap: 100_000_000_000,
+        },
+    );
+}
+
+#[test]
+#[should_panic(expected = "oracle wired to a different treasury")]
+fn test_initialize_platform_rejects_miswired_oracle() {
+    let env = create_test_env();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let usdc = create_mock_token(&env, &admin);
+    let oracle_id = env.register_contract(None, OracleManager);
+    OracleManagerClient::new(&env, &oracle_id).initialize(
+        &admin,
+        &2u32,
+        &OracleConfig {
+            usdc_token: Some(usdc.clone()),
+            treasury: Some(Address::generate(&env)),
+            ..Default::default()
+        },
+    );
+
+    let client = MarketFactoryClient::new(&env, &register_factory(&env));
+    client.initialize_platform(
+        &admin,
+        &PlatformConfig {
+            usdc,
+            treasury: env.register_contract(None, Treasury),
+            oracle: oracle_id,
+            amm: env.register_contract(None, AMM),
+            required_consensus: 2,
+            max_liquidity_cap: 100_000_000_000,
+        },
+    );
+}
+
 #[test]
 fn test_create_market() {
     let env = create_test_env();
@@ -227,6 +384,919 @@ fn test_create_market_uniqueness() {
     assert_eq!(market_count, 2);
 }
 
+/// Smallest uploadable contract: only the env meta section, no exported functions
+fn wasm_without_initialize(env: &Env) -> Bytes {
+    // wasm magic + version, then a 30-byte custom section with a 17-byte name
+    let mut wasm = Bytes::from_slice(env, &[0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00]);
+    wasm.extend_from_slice(&[0x00, 0x1e, 0x11]);
+    wasm.extend_from_slice(b"contractenvmetav0");
+    // Interface version entry: protocol 23, no pre-release
+    wasm.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 23, 0, 0, 0, 0]);
+    wasm
+}
+
+#[test]
+fn test_create_market_failed_initialize_reverts_fee() {
+    let env = create_test_env();
+    let factory_id = register_factory(&env);
+    let client = MarketFactoryClient::new(&env, &factory_id);
+
+    let admin = Address::generate(&env);
+    let usdc = create_mock_token(&env, &admin);
+    let treasury_id = env.register_contract(None, Treasury);
+    env.mock_all_auths();
+    TreasuryClient::new(&env, &treasury_id).initialize(&admin, &usdc, &factory_id);
+    client.initialize(&admin, &usdc, &treasury_id);
+
+    // The market deploys, but its initialize call fails after the fee was taken
+    let wasm_hash = env
+        .deployer()
+        .upload_contract_wasm(wasm_without_initialize(&env));
+    client.set_market_deployment(&admin, &wasm_hash, &Address::generate(&env));
+
+    let creator = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &usdc).mint(&creator, &100_000_000);
+
+    let closing_time = env.ledger().timestamp() + 86400;
+    let result = client.try_create_market(
+        &creator,
+        &Symbol::new(&env, "Mayweather"),
+        &Symbol::new(&env, "MayweatherWins"),
+        &Symbol::new(&env, "Boxing"),
+        &closing_time,
+        &(closing_time + 3600),
+    );
+    assert!(result.is_err());
+
+    // Fee transfer reverted together with the failed initialization
+    let token_client = token::Client::new(&env, &usdc);
+    assert_eq!(token_client.balance(&creator), 100_000_000);
+    assert_eq!(token_client.balance(&treasury_id), 0);
+    assert_eq!(token_client.balance(&factory_id), 0);
+    assert_eq!(client.get_market_count(), 0);
+}
+
+#[test]
+fn test_reconcile_fees_sweeps_donated_usdc() {
+    let env = create_test_env();
+    let factory_id = register_factory(&env);
+    let client = MarketFactoryClient::new(&env, &factory_id);
+
+    let admin = Address::generate(&env);
+    let usdc = create_mock_token(&env, &admin);
+    let treasury_id = env.register_contract(None, Treasury);
+    let treasury_client = TreasuryClient::new(&env, &treasury_id);
+    env.mock_all_auths();
+    treasury_client.initialize(&admin, &usdc, &factory_id);
+    client.initialize(&admin, &usdc, &treasury_id);
+
+    // Someone donates USDC straight to the factory
+    let token_admin = token::StellarAssetClient::new(&env, &usdc);
+    token_admin.mint(&factory_id, &25_000_000);
+
+    let swept = client.reconcile_fees(&admin);
+    assert_eq!(swept, 25_000_000);
+
+    let token_client = token::Client::new(&env, &usdc);
+    assert_eq!(token_client.balance(&factory_id), 0);
+    assert_eq!(token_client.balance(&treasury_id), 25_000_000);
+    // Swept fees are counted in the treasury's totals
+    assert_eq!(treasury_client.get_total_fees(), 25_000_000);
+
+    // Nothing left to reconcile
+    assert_eq!(client.reconcile_fees(&admin), 0);
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized")]
+fn test_reconcile_fees_non_admin_rejected() {
+    let env = create_test_env();
+    let factory_id = register_factory(&env);
+    let client = MarketFactoryClient::new(&env, &factory_id);
+
+    let admin = Address::generate(&env);
+    let usdc = create_mock_token(&env, &admin);
+    let treasury = Address::generate(&env);
+    env.mock_all_auths();
+    client.initialize(&admin, &usdc, &treasury);
+
+    client.reconcile_fees(&Address::generate(&env));
+}
+
+#[test]
+fn test_get_top_markets_by_volume() {
+    let env = create_test_env();
+    let factory_id = register_factory(&env);
+    let client = MarketFactoryClient::new(&env, &factory_id);
+
+    let admin = Address::generate(&env);
+    let usdc = create_mock_token(&env, &admin);
+    let treasury_id = env.register_contract(None, Treasury);
+    env.mock_all_auths();
+    TreasuryClient::new(&env, &treasury_id).initialize(&admin, &usdc, &factory_id);
+    client.initialize(&admin, &usdc, &treasury_id);
+
+    let creator = Address::generate(&env);
+    let token_admin = token::StellarAssetClient::new(&env, &usdc);
+    token_admin.mint(&creator, &100_000_000);
+
+    let closing_time = env.ledger().timestamp() + 86400;
+    let resolution_time = closing_time + 3600;
+    let oracle = Address::generate(&env);
+
+    // Three markets with volumes 30, 70 and 10 USDC
+    let volumes = [30_000_000i128, 70_000_000, 10_000_000];
+    let mut ids = std::vec::Vec::new();
+    let mut markets = std::vec::Vec::new();
+    for volume in volumes {
+        let market_id = client.create_market(
+            &creator,
+            &Symbol::new(&env, "Fight"),
+            &Symbol::new(&env, "WhoWins"),
+            &Symbol::new(&env, "Boxing"),
+            &closing_time,
+            &resolution_time,
+        );
+
+        // Stand in for a factory-deployed market contract
+        let market_address = env.register_contract(None, PredictionMarket);
+        let market = PredictionMarketClient::new(&env, &market_address);
+        market.initialize(
+            &market_id,
+            &creator,
+            &factory_id,
+            &usdc,
+            &oracle,
+            &closing_time,
+            &resolution_time,
+            &MarketConfig::default(),
+        );
+        env.as_contract(&factory_id, || {
+            env.storage().persistent().set(
+                &(Symbol::new(&env, "market_addr"), market_id.clone()),
+                &market_address,
+            );
+        });
+
+        let bettor = Address::generate(&env);
+        token_admin.mint(&bettor, &volume);
+        market.commit_prediction(&bettor, &BytesN::from_array(&env, &[1u8; 32]), &volume);
+
+        ids.push(market_id);
+        markets.push(market_address);
+    }
+
+    let top = client.get_top_markets_by_volume(&10);
+    assert_eq!(top.len(), 3);
+    assert_eq!(top.get(0).unwrap(), (ids[1].clone(), 70_000_000));
+    assert_eq!(top.get(1).unwrap(), (ids[0].clone(), 30_000_000));
+    assert_eq!(top.get(2).unwrap(), (ids[2].clone(), 10_000_000));
+
+    // Limit truncates to the highest-volume markets
+    let top_one = client.get_top_markets_by_volume(&1);
+    assert_eq!(top_one.len(), 1);
+    assert_eq!(top_one.get(0).unwrap().0, ids[1]);
+
+    // A later bet moves the market up the ranking
+    let bettor = Address::generate(&env);
+    token_admin.mint(&bettor, &65_000_000);
+    PredictionMarketClient::new(&env, &markets[2]).commit_prediction(
+        &bettor,
+        &BytesN::from_array(&env, &[2u8; 32]),
+        &65_000_000,
+    );
+    let top = client.get_top_markets_by_volume(&10);
+    assert_eq!(top.len(), 3);
+    assert_eq!(top.get(0).unwrap(), (ids[2].clone(), 75_000_000));
+    assert_eq!(top.get(1).unwrap(), (ids[1].clone(), 70_000_000));
+}
+
+#[test]
+fn test_get_markets_by_state_filters_and_paginates() {
+    let env = create_test_env();
+    let factory_id = register_factory(&env);
+    let client = MarketFactoryClient::new(&env, &factory_id);
+
+    let admin = Address::generate(&env);
+    let usdc = create_mock_token(&env, &admin);
+    let treasury_id = env.register_contract(None, Treasury);
+    env.mock_all_auths();
+    TreasuryClient::new(&env, &treasury_id).initialize(&admin, &usdc, &factory_id);
+    client.initialize(&admin, &usdc, &treasury_id);
+
+    let creator = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &usdc).mint(&creator, &100_000_000);
+    let closing_time = env.ledger().timestamp() + 86400;
+    let resolution_time = closing_time + 3600;
+
+    let mut ids = std::vec::Vec::new();
+    let mut markets = std::vec::Vec::new();
+    for _ in 0..4 {
+        let market_id = client.create_market(
+            &creator,
+            &Symbol::new(&env, "Fight"),
+            &Symbol::new(&env, "WhoWins"),
+            &Symbol::new(&env, "Boxing"),
+            &closing_time,
+            &resolution_time,
+        );
+        let market_address = env.register_contract(None, PredictionMarket);
+        let market = PredictionMarketClient::new(&env, &market_address);
+        market.initialize(
+            &market_id,
+            &creator,
+            &factory_id,
+            &usdc,
+            &Address::generate(&env),
+            &closing_time,
+            &resolution_time,
+            &MarketConfig::default(),
+        );
+        env.as_contract(&factory_id, || {
+            env.storage().persistent().set(
+                &(Symbol::new(&env, "market_addr"), market_id.clone()),
+                &market_address,
+            );
+        });
+        ids.push(market_id);
+        markets.push(market);
+    }
+
+    // Close the first, third and fourth markets; the second stays OPEN
+    env.ledger().with_mut(|li| li.timestamp = closing_time);
+    for i in [0usize, 2, 3] {
+        markets[i].close_market(&ids[i]);
+    }
+
+    let open = client.get_markets_by_state(&0u32, &0u32, &10u32);
+    assert_eq!(open.len(), 1);
+    assert_eq!(open.get(0).unwrap(), ids[1]);
+
+    let closed = client.get_markets_by_state(&1u32, &0u32, &10u32);
+    assert_eq!(closed.len(), 3);
+    assert_eq!(closed.get(0).unwrap(), ids[0]);
+
+    // Pagination applies to the scanned markets, not the matches
+    assert_eq!(client.get_markets_by_state(&1u32, &1u32, &1u32).len(), 0);
+    let page = client.get_markets_by_state(&1u32, &1u32, &2u32);
+    assert_eq!(page.len(), 1);
+    assert_eq!(page.get(0).unwrap(), ids[2]);
+    assert_eq!(client.get_markets_by_state(&1u32, &4u32, &10u32).len(), 0);
+    assert_eq!(client.get_markets_by_state(&2u32, &0u32, &10u32).len(), 0);
+}
+
+#[test]
+fn test_get_markets_by_state_caps_markets_scanned() {
+    let env = create_test_env();
+    let factory_id = register_factory(&env);
+    let client = MarketFactoryClient::new(&env, &factory_id);
+
+    // 120 deployed markets, all OPEN in the factory's state cache
+    env.as_contract(&factory_id, || {
+        let mut market_ids = Vec::new(&env);
+        for i in 0..120u8 {
+            let market_id = BytesN::from_array(&env, &[i; 32]);
+            env.storage().persistent().set(
+                &(Symbol::new(&env, "market_addr"), market_id.clone()),
+                &Address::generate(&env),
+            );
+            market_ids.push_back(market_id);
+        }
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, "market_ids"), &market_ids);
+    });
+
+    assert_eq!(
+        client.get_markets_by_state(&0u32, &0u32, &500u32).len(),
+        100
+    );
+    let rest = client.get_markets_by_state(&0u32, &100u32, &500u32);
+    assert_eq!(rest.len(), 20);
+    assert_eq!(rest.get(0).unwrap(), BytesN::from_array(&env, &[100u8; 32]));
+}
+
+#[test]
+fn test_closing_market_updates_cached_state() {
+    let env = create_test_env();
+    let factory_id = register_factory(&env);
+    let client = MarketFactoryClient::new(&env, &factory_id);
+
+    let admin = Address::generate(&env);
+    let usdc = create_mock_token(&env, &admin);
+    let treasury_id = env.register_contract(None, Treasury);
+    env.mock_all_auths();
+    TreasuryClient::new(&env, &treasury_id).initialize(&admin, &usdc, &factory_id);
+    client.initialize(&admin, &usdc, &treasury_id);
+
+    let creator = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &usdc).mint(&creator, &100_000_000);
+    let closing_time = env.ledger().timestamp() + 86400;
+    let resolution_time = closing_time + 3600;
+    let market_id = client.create_market(
+        &creator,
+        &Symbol::new(&env, "Fight"),
+        &Symbol::new(&env, "WhoWins"),
+        &Symbol::new(&env, "Boxing"),
+        &closing_time,
+        &resolution_time,
+    );
+    let market_address = env.register_contract(None, PredictionMarket);
+    let market = PredictionMarketClient::new(&env, &market_address);
+    market.initialize(
+        &market_id,
+        &creator,
+        &factory_id,
+        &usdc,
+        &Address::generate(&env),
+        &closing_time,
+        &resolution_time,
+        &MarketConfig::default(),
+    );
+    env.as_contract(&factory_id, || {
+        env.storage().persistent().set(
+            &(Symbol::new(&env, "market_addr"), market_id.clone()),
+            &market_address,
+        );
+    });
+    assert_eq!(client.get_cached_state(&market_id), 0);
+
+    env.ledger().with_mut(|li| li.timestamp = closing_time);
+    market.close_market(&market_id);
+    assert_eq!(client.get_cached_state(&market_id), 1);
+}
+
+#[test]
+fn test_get_market_summary_combines_metadata_and_live_state() {
+    let env = create_test_env();
+    let factory_id = register_factory(&env);
+    let client = MarketFactoryClient::new(&env, &factory_id);
+
+    let admin = Address::generate(&env);
+    let usdc = create_mock_token(&env, &admin);
+    let treasury_id = env.register_contract(None, Treasury);
+    env.mock_all_auths();
+    TreasuryClient::new(&env, &treasury_id).initialize(&admin, &usdc, &factory_id);
+    client.initialize(&admin, &usdc, &treasury_id);
+
+    let creator = Address::generate(&env);
+    let token_admin = token::StellarAssetClient::new(&env, &usdc);
+    token_admin.mint(&creator, &100_000_000);
+
+    let closing_time = env.ledger().timestamp() + 86400;
+    let resolution_time = closing_time + 3600;
+    let market_id = client.create_market(
+        &creator,
+        &Symbol::new(&env, "Mayweather"),
+        &Symbol::new(&env, "MayweatherWins"),
+        &Symbol::new(&env, "Boxing"),
+        &closing_time,
+        &resolution_time,
+    );
+
+    // Stand in for a factory-deployed market contract
+    let market_address = env.register_contract(None, PredictionMarket);
+    let market = PredictionMarketClient::new(&env, &market_address);
+    market.initialize(
+        &market_id,
+        &creator,
+        &factory_id,
+        &usdc,
+        &Address::generate(&env),
+        &closing_time,
+        &resolution_time,
+        &MarketConfig::default(),
+    );
+    env.as_contract(&factory_id, || {
+        env.storage().persistent().set(
+            &(Symbol::new(&env, "market_addr"), market_id.clone()),
+            &market_address,
+        );
+    });
+
+    // Place and reveal a YES bet
+    let bettor = Address::generate(&env);
+    let amount = 25_000_000i128;
+    let salt = BytesN::from_array(&env, &[4u8; 32]);
+    token_admin.mint(&bettor, &amount);
+    let commit_hash = market.compute_commit_hash(&market_id, &1u32, &amount, &salt);
+    market.commit_prediction(&bettor, &commit_hash, &amount);
+    env.ledger().with_mut(|li| li.timestamp = closing_time);
+    market.close_market(&market_id);
+    market.reveal_prediction(&bettor, &market_id, &1u32, &amount, &salt);
+
+    let summary = client.get_market_summary(&market_id);
+    assert_eq!(summary.title, Symbol::new(&env, "Mayweather"));
+    assert_eq!(summary.category, Symbol::new(&env, "Boxing"));
+    assert_eq!(summary.creator, creator);
+    assert_eq!(summary.market_address, market_address);
+    assert_eq!(summary.yes_pool, amount);
+    assert_eq!(summary.no_pool, 0);
+    assert_eq!(summary.total_volume, amount);
+    assert_eq!(summary.yes_odds, 10000);
+    assert_eq!(summary.phase, Symbol::new(&env, "REVEAL"));
+}
+
+#[test]
+fn test_get_market_resolution_reads_cache() {
+    let env = create_test_env();
+    let factory_id = register_factory(&env);
+    let client = MarketFactoryClient::new(&env, &factory_id);
+
+    let admin = Address::generate(&env);
+    let usdc = create_mock_token(&env, &admin);
+    let treasury_id = env.register_contract(None, Treasury);
+    env.mock_all_auths();
+    TreasuryClient::new(&env, &treasury_id).initialize(&admin, &usdc, &factory_id);
+    client.initialize(&admin, &usdc, &treasury_id);
+
+    let creator = Address::generate(&env);
+    let token_admin = token::StellarAssetClient::new(&env, &usdc);
+    token_admin.mint(&creator, &100_000_000);
+
+    let closing_time = env.ledger().timestamp() + 86400;
+    let resolution_time = closing_time + 3600;
+    let market_id = client.create_market(
+        &creator,
+        &Symbol::new(&env, "Mayweather"),
+        &Symbol::new(&env, "MayweatherWins"),
+        &Symbol::new(&env, "Boxing"),
+        &closing_time,
+        &resolution_time,
+    );
+
+    let market_address = env.register_contract(None, PredictionMarket);
+    env.as_contract(&factory_id, || {
+        env.storage().persistent().set(
+            &(Symbol::new(&env, "market_addr"), market_id.clone()),
+            &market_address,
+        );
+    });
+
+    // Unresolved and uncached: falls back to the (uninitialized) market
+    assert_eq!(client.get_market_resolution(&market_id), None);
+
+    client.report_resolution(&market_id, &1u32);
+
+    // Point the registry at an address with no contract; any call to the
+    // market would now fail, so a hit proves the cache was used
+    env.as_contract(&factory_id, || {
+        env.storage().persistent().set(
+            &(Symbol::new(&env, "market_addr"), market_id.clone()),
+            &Address::generate(&env),
+        );
+    });
+    assert_eq!(client.get_market_resolution(&market_id), Some(1));
+}
+
+/// Move the ledger to the end of a resolved market's dispute window
+fn close_dispute_window(env: &Env, client: &PredictionMarketClient) {
+    let window_end = client.get_dispute_window_end().unwrap();
+    env.ledger().with_mut(|li| li.timestamp = window_end);
+}
+
+/// Create a bonded market with a stand-in deployed market contract
+fn setup_bonded_market<'a>(
+    env: &'a Env,
+    bond: i128,
+) -> (
+    MarketFactoryClient<'a>,
+    BytesN<32>,
+    PredictionMarketClient<'a>,
+    Address,
+    Address,
+    Address,
+) {
+    let factory_id = register_factory(env);
+    let client = MarketFactoryClient::new(env, &factory_id);
+
+    let admin = Address::generate(env);
+    let usdc = create_mock_token(env, &admin);
+    let treasury_id = env.register_contract(None, Treasury);
+    env.mock_all_auths();
+    TreasuryClient::new(env, &treasury_id).initialize(&admin, &usdc, &factory_id);
+    client.initialize(&admin, &usdc, &treasury_id);
+    client.set_creator_bond(&admin, &bond);
+
+    let creator = Address::generate(env);
+    token::StellarAssetClient::new(env, &usdc).mint(&creator, &100_000_000);
+
+    let closing_time = env.ledger().timestamp() + 86400;
+    let resolution_time = closing_time + 3600;
+    let market_id = client.create_market(
+        &creator,
+        &Symbol::new(env, "Mayweather"),
+        &Symbol::new(env, "MayweatherWins"),
+        &Symbol::new(env, "Boxing"),
+        &closing_time,
+        &resolution_time,
+    );
+
+    let market_address = env.register_contract(None, PredictionMarket);
+    let market = PredictionMarketClient::new(env, &market_address);
+    market.initialize(
+        &market_id,
+        &creator,
+        &factory_id,
+        &usdc,
+        &Address::generate(env),
+        &closing_time,
+        &resolution_time,
+        &MarketConfig::default(),
+    );
+    env.as_contract(&factory_id, || {
+        env.storage().persistent().set(
+            &(Symbol::new(env, "market_addr"), market_id.clone()),
+            &market_address,
+        );
+    });
+
+    (client, market_id, market, creator, usdc, treasury_id)
+}
+
+#[test]
+fn test_creator_bond_refunded_on_resolution() {
+    let env = create_test_env();
+    let bond = 5_000_000i128;
+    let (client, market_id, market, creator, usdc, _treasury) = setup_bonded_market(&env, bond);
+    let token = token::TokenClient::new(&env, &usdc);
+
+    // 1 USDC fee to the treasury plus the bond in escrow
+    assert_eq!(token.balance(&creator), 100_000_000 - 10_000_000 - bond);
+    assert_eq!(token.balance(&client.address), bond);
+    assert_eq!(client.get_market_bond(&market_id), bond);
+
+    // Escrow is not swept as unaccounted dust
+    assert_eq!(client.reconcile_fees(&client.get_admin()), 0);
+
+    market.test_setup_resolution(&market_id, &1u32, &0, &0);
+    close_dispute_window(&env, &market);
+    assert_eq!(client.claim_creator_bond(&creator, &market_id), bond);
+    assert_eq!(token.balance(&creator), 100_000_000 - 10_000_000);
+    assert_eq!(client.get_market_bond(&market_id), 0);
+}
+
+#[test]
+#[should_panic(expected = "Dispute window open")]
+fn test_creator_bond_locked_during_dispute_window() {
+    let env = create_test_env();
+    let (client, market_id, market, creator, _usdc, _treasury) =
+        setup_bonded_market(&env, 5_000_000);
+
+    market.test_setup_resolution(&market_id, &1u32, &0, &0);
+    client.claim_creator_bond(&creator, &market_id);
+}
+
+#[test]
+fn test_creator_bond_forfeited_on_cancellation() {
+    let env = create_test_env();
+    let bond = 5_000_000i128;
+    let (client, market_id, market, creator, usdc, treasury) = setup_bonded_market(&env, bond);
+    let token = token::TokenClient::new(&env, &usdc);
+    let treasury_before = token.balance(&treasury);
+
+    // Cancel the stand-in market
+    env.as_contract(&market.address, || {
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, "market_state"), &3u32);
+    });
+
+    // The creator cannot reclaim a cancelled market's bond
+    assert!(client.try_claim_creator_bond(&creator, &market_id).is_err());
+
+    assert_eq!(client.forfeit_creator_bond(&market_id), bond);
+    assert_eq!(token.balance(&treasury), treasury_before + bond);
+    assert_eq!(token.balance(&client.address), 0);
+}
+
+#[test]
+fn test_creator_bond_forfeited_on_upheld_dispute() {
+    let env = create_test_env();
+    let bond = 5_000_000i128;
+    let (client, market_id, market, creator, usdc, treasury) = setup_bonded_market(&env, bond);
+    let token = token::TokenClient::new(&env, &usdc);
+    let treasury_before = token.balance(&treasury);
+
+    // Resolved YES on 1000 / 500 pools, then disputed by the NO backer
+    let no_user = Address::generate(&env);
+    env.as_contract(&market.address, || {
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, "yes_pool"), &1000i128);
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, "no_pool"), &500i128);
+    });
+    market.test_set_prediction(&no_user, &0u32, &500i128);
+    market.test_setup_resolution(&market_id, &1u32, &1000i128, &500i128);
+
+    // The creator cannot take the bond back before the dispute window closes
+    assert!(client.try_claim_creator_bond(&creator, &market_id).is_err());
+    assert_eq!(client.get_market_bond(&market_id), bond);
+
+    market.dispute_market(&no_user, &market_id, &Symbol::new(&env, "wrong_outcome"));
+
+    client.resolve_market_dispute(&client.get_admin(), &market_id, &true, &Some(0u32));
+
+    assert_eq!(market.get_winning_outcome(), Some(0));
+    assert_eq!(client.get_market_bond(&market_id), 0);
+    assert_eq!(token.balance(&treasury), treasury_before + bond);
+    assert!(client.try_claim_creator_bond(&creator, &market_id).is_err());
+}
+
+#[test]
+fn test_sweep_market_retained_fees_into_treasury() {
+    let env = create_test_env();
+    let (client, market_id, market, _creator, usdc, treasury) = setup_bonded_market(&env, 0);
+    let token = token::TokenClient::new(&env, &usdc);
+    let treasury_client = TreasuryClient::new(&env, &treasury);
+
+    // The stand-in market was never registered as a depositor, so the
+    // claim's fee deposit fails and the fee stays in escrow
+    let user = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &usdc).mint(&market.address, &1000);
+    market.test_setup_resolution(&market_id, &1u32, &1000i128, &0i128);
+    close_dispute_window(&env, &market);
+    market.test_set_prediction(&user, &1u32, &1000i128);
+    assert_eq!(market.claim_winnings(&user, &market_id), 900);
+    assert_eq!(market.get_retained_fees(), 100);
+
+    let platform_before = treasury_client.get_platform_fees();
+    treasury_client.register_market_depositor(&client.get_admin(), &market.address);
+    assert_eq!(
+        client.sweep_market_retained_fees(&client.get_admin(), &market_id),
+        100
+    );
+    assert_eq!(treasury_client.get_platform_fees(), platform_before + 100);
+    assert_eq!(token.balance(&market.address), 0);
+    assert_eq!(market.get_retained_fees(), 0);
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized: only admin can sweep retained fees")]
+fn test_sweep_market_retained_fees_requires_admin() {
+    let env = create_test_env();
+    let (client, market_id, _market, creator, _usdc, _treasury) = setup_bonded_market(&env, 0);
+
+    client.sweep_market_retained_fees(&creator, &market_id);
+}
+
+#[test]
+fn test_get_user_markets_lists_markets_bet_on() {
+    let env = create_test_env();
+    let factory_id = register_factory(&env);
+    let client = MarketFactoryClient::new(&env, &factory_id);
+
+    let admin = Address::generate(&env);
+    let usdc = create_mock_token(&env, &admin);
+    let treasury_id = env.register_contract(None, Treasury);
+    env.mock_all_auths();
+    TreasuryClient::new(&env, &treasury_id).initialize(&admin, &usdc, &factory_id);
+    client.initialize(&admin, &usdc, &treasury_id);
+
+    let creator = Address::generate(&env);
+    let bettor = Address::generate(&env);
+    let token_admin = token::StellarAssetClient::new(&env, &usdc);
+    token_admin.mint(&creator, &100_000_000);
+    token_admin.mint(&bettor, &100_000_000);
+
+    let closing_time = env.ledger().timestamp() + 86400;
+    let resolution_time = closing_time + 3600;
+    let mut ids = soroban_sdk::Vec::new(&env);
+    for i in 0..2u8 {
+        let market_id = client.create_market(
+            &creator,
+            &Symbol::new(&env, "Fight"),
+            &Symbol::new(&env, "Desc"),
+            &Symbol::new(&env, "Boxing"),
+            &closing_time,
+            &resolution_time,
+        );
+        env.ledger().with_mut(|li| li.timestamp += 1);
+
+        // Stand in for factory-deployed market contracts
+        let market_address = env.register_contract(None, PredictionMarket);
+        let market = PredictionMarketClient::new(&env, &market_address);
+        market.initialize(
+            &market_id,
+            &creator,
+            &factory_id,
+            &usdc,
+            &Address::generate(&env),
+            &closing_time,
+            &resolution_time,
+            &MarketConfig::default(),
+        );
+        env.as_contract(&factory_id, || {
+            env.storage().persistent().set(
+                &(Symbol::new(&env, "market_addr"), market_id.clone()),
+                &market_address,
+            );
+        });
+
+        market.commit_prediction(
+            &bettor,
+            &BytesN::from_array(&env, &[i + 1; 32]),
+            &10_000_000,
+        );
+        ids.push_back(market_id);
+    }
+
+    assert_eq!(client.get_user_markets(&bettor), ids);
+    assert_eq!(client.get_user_markets(&creator).len(), 0);
+}
+
+#[test]
+fn test_market_has_pool_after_amm_pool_creation() {
+    let env = create_test_env();
+    let factory_id = register_factory(&env);
+    let client = MarketFactoryClient::new(&env, &factory_id);
+
+    let admin = Address::generate(&env);
+    let usdc = create_mock_token(&env, &admin);
+    let treasury_id = env.register_contract(None, Treasury);
+    env.mock_all_auths();
+    TreasuryClient::new(&env, &treasury_id).initialize(&admin, &usdc, &factory_id);
+    client.initialize(&admin, &usdc, &treasury_id);
+
+    let amm_id = env.register_contract(None, AMM);
+    let amm = AMMClient::new(&env, &amm_id);
+    amm.initialize(&admin, &factory_id, &usdc, &100_000_000_000u128);
+    client.set_amm_address(&admin, &amm_id);
+    assert_eq!(client.get_amm_address(), amm_id);
+
+    let creator = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &usdc).mint(&creator, &2_000_000_000);
+    let closing_time = env.ledger().timestamp() + 86400;
+    let market_id = client.create_market(
+        &creator,
+        &Symbol::new(&env, "Mayweather"),
+        &Symbol::new(&env, "MayweatherWins"),
+        &Symbol::new(&env, "Boxing"),
+        &closing_time,
+        &(closing_time + 3600),
+    );
+    assert!(!client.market_has_pool(&market_id));
+
+    amm.create_pool(&creator, &market_id, &1_000_000_000u128, &0u32, &0u64);
+    assert!(client.market_has_pool(&market_id));
+}
+
+/// Wires a factory, AMM pool, oracle and deployed market with revealed YES and
+/// NO bets, attested YES and finalized by the oracle, ready for `finalize_market`
+fn setup_market_ready_to_finalize(
+    env: &Env,
+) -> (
+    MarketFactoryClient<'_>,
+    PredictionMarketClient<'_>,
+    AMMClient<'_>,
+    BytesN<32>,
+    Address,
+) {
+    let factory_id = register_factory(env);
+    let client = MarketFactoryClient::new(env, &factory_id);
+
+    let admin = Address::generate(env);
+    let usdc = create_mock_token(env, &admin);
+    let treasury_id = env.register_contract(None, Treasury);
+    env.mock_all_auths();
+    TreasuryClient::new(env, &treasury_id).initialize(&admin, &usdc, &factory_id);
+    client.initialize(&admin, &usdc, &treasury_id);
+
+    let amm_id = env.register_contract(None, AMM);
+    let amm = AMMClient::new(env, &amm_id);
+    amm.initialize(&admin, &factory_id, &usdc, &100_000_000_000u128);
+    client.set_amm_address(&admin, &amm_id);
+
+    let oracle_id = env.register_contract(None, OracleManager);
+    let oracle = OracleManagerClient::new(env, &oracle_id);
+    let oracle_config = OracleConfig {
+        factory: Some(factory_id.clone()),
+        ..OracleConfig::default()
+    };
+    oracle.initialize(&admin, &1u32, &oracle_config);
+    let oracle1 = Address::generate(env);
+    oracle.register_oracle(&oracle1, &Symbol::new(env, "Oracle1"));
+
+    let creator = Address::generate(env);
+    let usdc_admin = token::StellarAssetClient::new(env, &usdc);
+    usdc_admin.mint(&creator, &2_000_000_000);
+    let closing_time = env.ledger().timestamp() + 86400;
+    let resolution_time = closing_time + 3600;
+    let market_id = client.create_market(
+        &creator,
+        &Symbol::new(env, "Mayweather"),
+        &Symbol::new(env, "MayweatherWins"),
+        &Symbol::new(env, "Boxing"),
+        &closing_time,
+        &resolution_time,
+    );
+
+    let market_address = env.register_contract(None, PredictionMarket);
+    let market = PredictionMarketClient::new(env, &market_address);
+    market.initialize(
+        &market_id,
+        &creator,
+        &factory_id,
+        &usdc,
+        &oracle_id,
+        &closing_time,
+        &resolution_time,
+        &MarketConfig::default(),
+    );
+    env.as_contract(&factory_id, || {
+        env.storage().persistent().set(
+            &(Symbol::new(env, "market_addr"), market_id.clone()),
+            &market_address,
+        );
+    });
+    oracle.request_resolution(&factory_id, &market_address, &market_id, &resolution_time);
+    amm.create_pool(&creator, &market_id, &1_000_000_000u128, &0u32, &0u64);
+
+    // One YES and one NO bet, revealed after close
+    let bettor = Address::generate(env);
+    let no_bettor = Address::generate(env);
+    let salt = BytesN::from_array(env, &[7u8; 32]);
+    usdc_admin.mint(&bettor, &10_000_000);
+    usdc_admin.mint(&no_bettor, &5_000_000);
+    let hash = market.compute_commit_hash(&market_id, &1u32, &10_000_000i128, &salt);
+    market.commit_prediction(&bettor, &hash, &10_000_000i128);
+    let no_hash = market.compute_commit_hash(&market_id, &0u32, &5_000_000i128, &salt);
+    market.commit_prediction(&no_bettor, &no_hash, &5_000_000i128);
+    env.ledger().with_mut(|li| li.timestamp = closing_time);
+    market.close_market(&market_id);
+    market.reveal_prediction(&bettor, &market_id, &1u32, &10_000_000i128, &salt);
+    market.reveal_prediction(&no_bettor, &market_id, &0u32, &5_000_000i128, &salt);
+
+    env.ledger().with_mut(|li| li.timestamp = resolution_time);
+    assert!(oracle
+        .get_markets_awaiting_attestation(&oracle1, &0, &10)
+        .contains(&market_id));
+    oracle.submit_attestation(
+        &oracle1,
+        &market_id,
+        &1u32,
+        &BytesN::from_array(env, &[0u8; 32]),
+    );
+    let finality_time = resolution_time + oracle.get_challenge_window();
+    env.ledger().with_mut(|li| li.timestamp = finality_time);
+    oracle.finalize_resolution(&market_id);
+
+    (client, market, amm, market_id, bettor)
+}
+
+#[test]
+fn test_finalize_market_resolves_market_and_settles_pool() {
+    let env = create_test_env();
+    let (client, market, amm, market_id, _bettor) = setup_market_ready_to_finalize(&env);
+
+    assert_eq!(client.finalize_market(&market_id), (true, true));
+    assert_eq!(market.get_winning_outcome(), Some(1));
+    assert!(amm.is_pool_settled(&market_id));
+    assert_eq!(client.get_market_resolution(&market_id), Some(1));
+
+    // Repeat calls are no-ops
+    assert_eq!(client.finalize_market(&market_id), (true, true));
+}
+
+#[test]
+fn test_finalize_market_settles_pool_to_upheld_dispute_outcome() {
+    let env = create_test_env();
+    let (client, market, amm, market_id, bettor) = setup_market_ready_to_finalize(&env);
+
+    // Oracle said YES, but a dispute corrects the market to NO
+    market.resolve_market(&market_id);
+    market.dispute_market(&bettor, &market_id, &Symbol::new(&env, "WrongOutcome"));
+    client.resolve_market_dispute(&client.get_admin(), &market_id, &true, &Some(0u32));
+
+    assert_eq!(client.finalize_market(&market_id), (true, true));
+    assert_eq!(amm.get_pool_winning_outcome(&market_id), Some(0));
+}
+
+#[test]
+fn test_finalize_market_skips_disputed_market() {
+    let env = create_test_env();
+    let (client, market, amm, market_id, bettor) = setup_market_ready_to_finalize(&env);
+
+    market.resolve_market(&market_id);
+    market.dispute_market(&bettor, &market_id, &Symbol::new(&env, "WrongOutcome"));
+
+    assert_eq!(client.finalize_market(&market_id), (false, false));
+    assert!(!amm.is_pool_settled(&market_id));
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized: only admin can resolve disputes")]
+fn test_resolve_market_dispute_requires_admin() {
+    let env = create_test_env();
+    let (client, market, _amm, market_id, bettor) = setup_market_ready_to_finalize(&env);
+
+    market.resolve_market(&market_id);
+    market.dispute_market(&bettor, &market_id, &Symbol::new(&env, "WrongOutcome"));
+    client.resolve_market_dispute(&bettor, &market_id, &true, &Some(0u32));
+}
+
 #[test]
 fn test_get_market_by_id() {
     // TODO: Implement when get_market is ready
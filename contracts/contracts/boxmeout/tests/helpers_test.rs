@@ -0,0 +1,38 @@
+#![cfg(test)]
+
+use boxmeout::helpers::{
+    integer_sqrt, is_valid_outcome, validate_outcome_count, BINARY_OUTCOMES, MAX_OUTCOMES,
+};
+
+#[test]
+fn test_validate_outcome_count_accepts_supported_range() {
+    validate_outcome_count(BINARY_OUTCOMES);
+    validate_outcome_count(MAX_OUTCOMES);
+}
+
+#[test]
+#[should_panic(expected = "invalid outcome count")]
+fn test_validate_outcome_count_rejects_single_outcome() {
+    validate_outcome_count(1);
+}
+
+#[test]
+#[should_panic(expected = "invalid outcome count")]
+fn test_validate_outcome_count_rejects_above_max() {
+    validate_outcome_count(MAX_OUTCOMES + 1);
+}
+
+#[test]
+fn test_is_valid_outcome_bounds_by_outcome_count() {
+    assert!(is_valid_outcome(0, BINARY_OUTCOMES));
+    assert!(is_valid_outcome(1, BINARY_OUTCOMES));
+    assert!(!is_valid_outcome(2, BINARY_OUTCOMES));
+}
+
+#[test]
+fn test_integer_sqrt_rounds_down_up_to_u128_max() {
+    assert_eq!(integer_sqrt(2), 1);
+    assert_eq!(integer_sqrt(3), 1);
+    assert_eq!(integer_sqrt(4), 2);
+    assert_eq!(integer_sqrt(u128::MAX), u64::MAX as u128);
+}
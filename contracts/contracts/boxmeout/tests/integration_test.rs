@@ -7,7 +7,8 @@ use soroban_sdk::{
 
 use boxmeout::{
     AMM, AMMClient, MarketFactory, MarketFactoryClient, PredictionMarket,
-    PredictionMarketClient, OracleManager, OracleManagerClient, Treasury, TreasuryClient,
+    PredictionMarketClient, OracleConfig, OracleManager, OracleManagerClient, Treasury,
+    TreasuryClient,
 };
 
 /// Integration test: Complete user flow from market creation to resolution
@@ -37,7 +38,7 @@ fn test_complete_prediction_flow() {
     // Step 2: Initialize all contracts
     factory_client.initialize(&admin, &usdc_token, &treasury_id);
     treasury_client.initialize(&admin, &usdc_token, &factory_id);
-    oracle_client.initialize(&admin, &2u32);
+    oracle_client.initialize(&admin, &2u32, &OracleConfig::default());
     amm_client.initialize(&admin, &factory_id, &usdc_token, &100_000_000_000u128);
 
     // Step 3: Register oracles
@@ -153,7 +154,7 @@ fn test_oracle_consensus_flow() {
     let oracle_client = OracleManagerClient::new(&env, &oracle_id);
 
     let admin = Address::generate(&env);
-    oracle_client.initialize(&admin, &2u32);
+    oracle_client.initialize(&admin, &2u32, &OracleConfig::default());
 
     // Register 3 oracles
     let oracle1 = Address::generate(&env);
@@ -1,11 +1,15 @@
 #![cfg(test)]
 
 use soroban_sdk::{
+    contract, contractimpl,
     testutils::{Address as _, Ledger, LedgerInfo},
-    token, Address, BytesN, Env,
+    token, Address, BytesN, Env, Symbol, Vec,
 };
 
-use boxmeout::{Commitment, MarketError, PredictionMarketClient};
+use boxmeout::{
+    Commitment, MarketConfig, MarketError, MarketFactory, MarketFactoryClient, OracleConfig,
+    OracleManager, OracleManagerClient, PredictionMarketClient, Treasury, TreasuryClient,
+};
 
 // ============================================================================
 // TEST HELPERS
@@ -28,11 +32,53 @@ fn create_test_env() -> Env {
     env
 }
 
+/// Move the ledger to the end of a resolved market's dispute window
+fn close_dispute_window(env: &Env, client: &PredictionMarketClient) {
+    let window_end = client.get_dispute_window_end().unwrap();
+    env.ledger().with_mut(|li| li.timestamp = window_end);
+}
+
 /// Helper to register market contract
 fn register_market(env: &Env) -> Address {
     env.register(boxmeout::PredictionMarket, ())
 }
 
+/// Minimal token that burns `fee_bps` of every transfer
+#[contract]
+struct FeeOnTransferToken;
+
+#[contractimpl]
+impl FeeOnTransferToken {
+    pub fn set_fee_bps(env: Env, fee_bps: i128) {
+        env.storage()
+            .instance()
+            .set(&Symbol::new(&env, "fee"), &fee_bps);
+    }
+
+    pub fn mint(env: Env, to: Address, amount: i128) {
+        let balance = Self::balance(env.clone(), to.clone());
+        env.storage().instance().set(&to, &(balance + amount));
+    }
+
+    pub fn balance(env: Env, id: Address) -> i128 {
+        env.storage().instance().get(&id).unwrap_or(0)
+    }
+
+    pub fn transfer(env: Env, from: Address, to: Address, amount: i128) {
+        from.require_auth();
+        let fee_bps: i128 = env
+            .storage()
+            .instance()
+            .get(&Symbol::new(&env, "fee"))
+            .unwrap_or(0);
+        let from_balance = Self::balance(env.clone(), from.clone());
+        env.storage()
+            .instance()
+            .set(&from, &(from_balance - amount));
+        Self::mint(env, to, amount - amount * fee_bps / 10000);
+    }
+}
+
 /// Helper to create and register a mock USDC token
 fn create_usdc_token<'a>(env: &Env, admin: &Address) -> (token::StellarAssetClient<'a>, Address) {
     let token_address = env
@@ -78,11 +124,18 @@ fn setup_test_market(
         &oracle,
         &closing_time,
         &resolution_time,
+        &MarketConfig::default(),
     );
 
     (client, market_id, creator, admin, usdc_address)
 }
 
+/// Helper to advance to the test market's closing time and open reveals
+fn open_reveal_window(env: &Env, client: &PredictionMarketClient, market_id: &BytesN<32>) {
+    env.ledger().with_mut(|li| li.timestamp += 86400);
+    client.close_market(market_id);
+}
+
 /// Helper to setup market with token for claim tests
 fn setup_market_for_claims(
     env: &Env,
@@ -116,6 +169,7 @@ fn setup_market_for_claims(
         &oracle,
         &closing_time,
         &resolution_time,
+        &MarketConfig::default(),
     );
 
     (client, market_id, token_client, market_contract)
@@ -139,10 +193,98 @@ fn test_market_initialize() {
     assert_eq!(pending_count, 0);
 }
 
+#[test]
+fn test_get_market_state_time_remaining() {
+    let env = create_test_env();
+    let (client, market_id, creator, _admin, _usdc_address) = setup_test_market(&env);
+
+    // Closing is 24h away, resolution 1h after that
+    let state = client.get_market_state(&market_id);
+    assert_eq!(state.creator, creator);
+    assert_eq!(state.state, 0);
+    assert_eq!(state.time_remaining_to_close, 86400);
+    assert_eq!(state.time_remaining_to_resolution, 86400 + 3600);
+
+    // Fast-forward past closing time
+    env.ledger().with_mut(|li| li.timestamp += 86400 + 600);
+    let state = client.get_market_state(&market_id);
+    assert_eq!(state.time_remaining_to_close, -600);
+    assert_eq!(state.time_remaining_to_resolution, 3000);
+}
+
+#[test]
+fn test_get_oracle_returns_initialized_oracle() {
+    let env = create_test_env();
+    env.mock_all_auths();
+    let client = PredictionMarketClient::new(&env, &register_market(&env));
+
+    let oracle = Address::generate(&env);
+    let closing_time = env.ledger().timestamp() + 86400;
+    client.initialize(
+        &BytesN::from_array(&env, &[1u8; 32]),
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &oracle,
+        &closing_time,
+        &(closing_time + 3600),
+        &MarketConfig::default(),
+    );
+
+    assert_eq!(client.get_oracle(), oracle);
+}
+
+#[test]
+fn test_get_creator_returns_initialized_creator() {
+    let env = create_test_env();
+    let (client, _market_id, creator, _admin, _usdc_address) = setup_test_market(&env);
+
+    assert_eq!(client.get_creator(), creator);
+}
+
 // ============================================================================
 // COMMIT PREDICTION TESTS
 // ============================================================================
 
+#[test]
+fn test_commit_prediction_records_received_amount() {
+    let env = create_test_env();
+    env.mock_all_auths();
+    let client = PredictionMarketClient::new(&env, &register_market(&env));
+
+    let token_id = env.register(FeeOnTransferToken, ());
+    let token = FeeOnTransferTokenClient::new(&env, &token_id);
+    let closing_time = env.ledger().timestamp() + 86400;
+    client.initialize(
+        &BytesN::from_array(&env, &[1u8; 32]),
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &token_id,
+        &Address::generate(&env),
+        &closing_time,
+        &(closing_time + 3600),
+        &MarketConfig::default(),
+    );
+
+    // Full delivery: the commitment records what the market received
+    let user = Address::generate(&env);
+    token.mint(&user, &1000);
+    client.commit_prediction(&user, &BytesN::from_array(&env, &[2u8; 32]), &1000);
+    assert_eq!(client.get_commitment(&user).unwrap().amount, 1000);
+    assert_eq!(token.balance(&client.address), 1000);
+
+    // A 1% transfer fee delivers short and the commit is rejected and rolled back
+    token.set_fee_bps(&100);
+    let taxed_user = Address::generate(&env);
+    token.mint(&taxed_user, &1000);
+    let result =
+        client.try_commit_prediction(&taxed_user, &BytesN::from_array(&env, &[3u8; 32]), &1000);
+    assert_eq!(result, Err(Ok(MarketError::TransferFailed)));
+    assert!(client.get_commitment(&taxed_user).is_none());
+    assert_eq!(token.balance(&client.address), 1000);
+    assert_eq!(client.get_total_volume(), 1000);
+}
+
 #[test]
 fn test_commit_prediction_happy_path() {
     let env = create_test_env();
@@ -191,6 +333,357 @@ fn test_commit_prediction_happy_path() {
     assert_eq!(market_balance, amount);
 }
 
+#[test]
+fn test_has_committed() {
+    let env = create_test_env();
+    let (client, _market_id, _creator, _admin, usdc_address) = setup_test_market(&env);
+
+    let user = Address::generate(&env);
+    let amount = 100_000_000i128;
+    token::StellarAssetClient::new(&env, &usdc_address).mint(&user, &amount);
+
+    assert!(!client.has_committed(&user));
+
+    client.commit_prediction(&user, &BytesN::from_array(&env, &[2u8; 32]), &amount);
+
+    assert!(client.has_committed(&user));
+}
+
+#[test]
+fn test_reveal_prediction_happy_path() {
+    let env = create_test_env();
+    let (client, market_id, _creator, _admin, usdc_address) = setup_test_market(&env);
+
+    let user = Address::generate(&env);
+    let amount = 10_000_000i128;
+    let salt = BytesN::from_array(&env, &[3u8; 32]);
+    token::StellarAssetClient::new(&env, &usdc_address).mint(&user, &amount);
+
+    let commit_hash = client.compute_commit_hash(&market_id, &1u32, &amount, &salt);
+    client.commit_prediction(&user, &commit_hash, &amount);
+
+    open_reveal_window(&env, &client, &market_id);
+    client.reveal_prediction(&user, &market_id, &1u32, &amount, &salt);
+
+    assert!(!client.has_committed(&user));
+    assert_eq!(client.get_pending_count(), 0);
+    assert_eq!(client.get_odds(), (10000, 0));
+
+    // Commits are closed once reveals open
+    let result = client.try_commit_prediction(&user, &commit_hash, &amount);
+    assert_eq!(result, Err(Ok(MarketError::InvalidMarketState)));
+}
+
+/// Commits a 10 USDC YES bet with salt [3; 32] from a fresh user and opens reveals
+fn commit_yes_and_open_reveals(
+    env: &Env,
+    client: &PredictionMarketClient,
+    market_id: &BytesN<32>,
+    usdc_address: &Address,
+) -> Address {
+    let user = Address::generate(env);
+    let amount = 10_000_000i128;
+    let salt = BytesN::from_array(env, &[3u8; 32]);
+    token::StellarAssetClient::new(env, usdc_address).mint(&user, &amount);
+
+    let commit_hash = client.compute_commit_hash(market_id, &1u32, &amount, &salt);
+    client.commit_prediction(&user, &commit_hash, &amount);
+    open_reveal_window(env, client, market_id);
+    user
+}
+
+#[test]
+fn test_reveal_prediction_tampered_outcome_rejected() {
+    let env = create_test_env();
+    let (client, market_id, _creator, _admin, usdc_address) = setup_test_market(&env);
+    let user = commit_yes_and_open_reveals(&env, &client, &market_id, &usdc_address);
+
+    let salt = BytesN::from_array(&env, &[3u8; 32]);
+    let result = client.try_reveal_prediction(&user, &market_id, &0u32, &10_000_000, &salt);
+    assert_eq!(result, Err(Ok(MarketError::InvalidRevelation)));
+    assert!(client.has_committed(&user));
+    assert_eq!(client.get_pool_sizes(), (0, 0));
+}
+
+#[test]
+fn test_reveal_prediction_wrong_salt_rejected() {
+    let env = create_test_env();
+    let (client, market_id, _creator, _admin, usdc_address) = setup_test_market(&env);
+    let user = commit_yes_and_open_reveals(&env, &client, &market_id, &usdc_address);
+
+    let wrong_salt = BytesN::from_array(&env, &[4u8; 32]);
+    let result = client.try_reveal_prediction(&user, &market_id, &1u32, &10_000_000, &wrong_salt);
+    assert_eq!(result, Err(Ok(MarketError::InvalidRevelation)));
+    assert!(client.has_committed(&user));
+    assert_eq!(client.get_pending_count(), 1);
+}
+
+#[test]
+#[should_panic(expected = "amount must be positive")]
+fn test_reveal_prediction_zero_amount_rejected() {
+    let env = create_test_env();
+    let (client, market_id, _creator, _admin, usdc_address) = setup_test_market(&env);
+
+    let user = Address::generate(&env);
+    let amount = 10_000_000i128;
+    let salt = BytesN::from_array(&env, &[3u8; 32]);
+    token::StellarAssetClient::new(&env, &usdc_address).mint(&user, &amount);
+
+    let commit_hash = client.compute_commit_hash(&market_id, &1u32, &amount, &salt);
+    client.commit_prediction(&user, &commit_hash, &amount);
+
+    open_reveal_window(&env, &client, &market_id);
+    client.reveal_prediction(&user, &market_id, &1u32, &0i128, &salt);
+}
+
+#[test]
+#[should_panic(expected = "invalid salt")]
+fn test_reveal_prediction_zero_salt_rejected() {
+    let env = create_test_env();
+    let (client, market_id, _creator, _admin, usdc_address) = setup_test_market(&env);
+
+    let user = Address::generate(&env);
+    let amount = 10_000_000i128;
+    let salt = BytesN::from_array(&env, &[0u8; 32]);
+    token::StellarAssetClient::new(&env, &usdc_address).mint(&user, &amount);
+
+    let commit_hash = client.compute_commit_hash(&market_id, &1u32, &amount, &salt);
+    client.commit_prediction(&user, &commit_hash, &amount);
+
+    open_reveal_window(&env, &client, &market_id);
+    client.reveal_prediction(&user, &market_id, &1u32, &amount, &salt);
+}
+
+#[test]
+fn test_reveal_prediction_decrements_pending_count() {
+    let env = create_test_env();
+    let (client, market_id, _creator, _admin, usdc_address) = setup_test_market(&env);
+    let token = token::StellarAssetClient::new(&env, &usdc_address);
+
+    let amount = 10_000_000i128;
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let alice_salt = BytesN::from_array(&env, &[3u8; 32]);
+    let bob_salt = BytesN::from_array(&env, &[4u8; 32]);
+    token.mint(&alice, &amount);
+    token.mint(&bob, &amount);
+
+    let alice_hash = client.compute_commit_hash(&market_id, &1u32, &amount, &alice_salt);
+    let bob_hash = client.compute_commit_hash(&market_id, &0u32, &amount, &bob_salt);
+    client.commit_prediction(&alice, &alice_hash, &amount);
+    client.commit_prediction(&bob, &bob_hash, &amount);
+    assert_eq!(client.get_pending_count(), 2);
+
+    open_reveal_window(&env, &client, &market_id);
+    client.reveal_prediction(&alice, &market_id, &1u32, &amount, &alice_salt);
+    assert_eq!(client.get_pending_count(), 1);
+
+    client.reveal_prediction(&bob, &market_id, &0u32, &amount, &bob_salt);
+    assert_eq!(client.get_pending_count(), 0);
+}
+
+#[test]
+fn test_reveal_prediction_pending_count_does_not_underflow() {
+    let env = create_test_env();
+    let (client, market_id, _creator, _admin, usdc_address) = setup_test_market(&env);
+
+    let user = Address::generate(&env);
+    let amount = 10_000_000i128;
+    let salt = BytesN::from_array(&env, &[3u8; 32]);
+    token::StellarAssetClient::new(&env, &usdc_address).mint(&user, &amount);
+
+    let commit_hash = client.compute_commit_hash(&market_id, &1u32, &amount, &salt);
+    client.commit_prediction(&user, &commit_hash, &amount);
+
+    // Simulate a counter that has drifted out of sync with the commitments
+    env.as_contract(&client.address, || {
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, "pending_count"), &0u32);
+    });
+
+    open_reveal_window(&env, &client, &market_id);
+    client.reveal_prediction(&user, &market_id, &1u32, &amount, &salt);
+    assert_eq!(client.get_pending_count(), 0);
+}
+
+#[test]
+fn test_reveal_prediction_requires_closed_market() {
+    let env = create_test_env();
+    let (client, market_id, _creator, _admin, usdc_address) = setup_test_market(&env);
+
+    let user = Address::generate(&env);
+    let amount = 10_000_000i128;
+    let salt = BytesN::from_array(&env, &[3u8; 32]);
+    token::StellarAssetClient::new(&env, &usdc_address).mint(&user, &amount);
+
+    let commit_hash = client.compute_commit_hash(&market_id, &1u32, &amount, &salt);
+    client.commit_prediction(&user, &commit_hash, &amount);
+
+    // Rejected while the market is still open
+    let result = client.try_reveal_prediction(&user, &market_id, &1u32, &amount, &salt);
+    assert!(result.is_err());
+    assert_eq!(client.get_reveal_window_opened_at(), None);
+
+    open_reveal_window(&env, &client, &market_id);
+    assert_eq!(
+        client.get_reveal_window_opened_at(),
+        Some(env.ledger().timestamp())
+    );
+
+    client.reveal_prediction(&user, &market_id, &1u32, &amount, &salt);
+    assert_eq!(client.get_odds(), (10000, 0));
+}
+
+#[test]
+fn test_reveal_prediction_pools_backed_by_escrow() {
+    let env = create_test_env();
+    let (client, market_id, _creator, _admin, usdc_address) = setup_test_market(&env);
+
+    let user = Address::generate(&env);
+    let amount = 10_000_000i128;
+    let salt = BytesN::from_array(&env, &[3u8; 32]);
+    token::StellarAssetClient::new(&env, &usdc_address).mint(&user, &amount);
+
+    let commit_hash = client.compute_commit_hash(&market_id, &1u32, &amount, &salt);
+    client.commit_prediction(&user, &commit_hash, &amount);
+    open_reveal_window(&env, &client, &market_id);
+    client.reveal_prediction(&user, &market_id, &1u32, &amount, &salt);
+
+    let escrow = token::TokenClient::new(&env, &usdc_address).balance(&client.address);
+    let (yes_pool, no_pool) = client.get_pool_sizes();
+    assert_eq!(escrow, yes_pool + no_pool);
+}
+
+#[test]
+#[should_panic(expected = "escrow underflow")]
+fn test_reveal_prediction_unbacked_pool_rejected() {
+    let env = create_test_env();
+    let (client, market_id, _creator, _admin, usdc_address) = setup_test_market(&env);
+
+    let user = Address::generate(&env);
+    let amount = 10_000_000i128;
+    let salt = BytesN::from_array(&env, &[3u8; 32]);
+    token::StellarAssetClient::new(&env, &usdc_address).mint(&user, &amount);
+
+    let commit_hash = client.compute_commit_hash(&market_id, &1u32, &amount, &salt);
+    client.commit_prediction(&user, &commit_hash, &amount);
+
+    // Simulate a pool credited without matching escrow
+    env.as_contract(&client.address, || {
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(&env, "no_pool"), &amount);
+    });
+
+    open_reveal_window(&env, &client, &market_id);
+    client.reveal_prediction(&user, &market_id, &1u32, &amount, &salt);
+}
+
+#[test]
+#[should_panic(expected = "amount mismatch")]
+fn test_reveal_prediction_amount_mismatch_rejected() {
+    let env = create_test_env();
+    let (client, market_id, _creator, _admin, usdc_address) = setup_test_market(&env);
+
+    let user = Address::generate(&env);
+    let amount = 10_000_000i128;
+    let salt = BytesN::from_array(&env, &[3u8; 32]);
+    token::StellarAssetClient::new(&env, &usdc_address).mint(&user, &amount);
+
+    let commit_hash = client.compute_commit_hash(&market_id, &1u32, &amount, &salt);
+    client.commit_prediction(&user, &commit_hash, &amount);
+
+    // Commit 10 USDC but try to reveal 1000
+    open_reveal_window(&env, &client, &market_id);
+    client.reveal_prediction(&user, &market_id, &1u32, &1_000_000_000i128, &salt);
+}
+
+#[test]
+fn test_extend_reveal_window_allows_late_reveal() {
+    let env = create_test_env();
+    let (client, market_id, creator, _admin, usdc_address) = setup_test_market(&env);
+
+    let user = Address::generate(&env);
+    let amount = 10_000_000i128;
+    let salt = BytesN::from_array(&env, &[3u8; 32]);
+    token::StellarAssetClient::new(&env, &usdc_address).mint(&user, &amount);
+
+    let commit_hash = client.compute_commit_hash(&market_id, &1u32, &amount, &salt);
+    client.commit_prediction(&user, &commit_hash, &amount);
+    open_reveal_window(&env, &client, &market_id);
+
+    // Past resolution_time the reveal is too late
+    env.ledger().with_mut(|li| li.timestamp += 3600);
+    let result = client.try_reveal_prediction(&user, &market_id, &1u32, &amount, &salt);
+    assert!(result.is_err());
+
+    let new_deadline = env.ledger().timestamp() + 3600;
+    client.extend_reveal_window(&creator, &market_id, &new_deadline);
+    assert_eq!(client.get_reveal_deadline(), new_deadline);
+
+    client.reveal_prediction(&user, &market_id, &1u32, &amount, &salt);
+    assert!(!client.has_committed(&user));
+    assert_eq!(client.get_pool_sizes(), (amount, 0));
+}
+
+#[test]
+#[should_panic(expected = "Market already resolved")]
+fn test_extend_reveal_window_after_resolution_rejected() {
+    let env = create_test_env();
+    let (client, market_id, creator, _admin, _usdc_address) = setup_test_market(&env);
+
+    client.test_setup_resolution(&market_id, &1u32, &1000, &0);
+
+    let new_deadline = env.ledger().timestamp() + 200_000;
+    client.extend_reveal_window(&creator, &market_id, &new_deadline);
+}
+
+#[test]
+fn test_probability_history_tracks_reveals() {
+    let env = create_test_env();
+    let (client, market_id, _creator, _admin, usdc_address) = setup_test_market(&env);
+    let token = token::StellarAssetClient::new(&env, &usdc_address);
+
+    // (outcome, amount, salt byte)
+    let bets = [
+        (1u32, 100i128, 1u8),
+        (0u32, 100i128, 2u8),
+        (1u32, 200i128, 3u8),
+    ];
+    let mut users = Vec::new(&env);
+    for (outcome, amount, salt_byte) in bets {
+        let user = Address::generate(&env);
+        token.mint(&user, &amount);
+        let salt = BytesN::from_array(&env, &[salt_byte; 32]);
+        let commit_hash = client.compute_commit_hash(&market_id, &outcome, &amount, &salt);
+        client.commit_prediction(&user, &commit_hash, &amount);
+        users.push_back((user, outcome, amount, salt));
+    }
+
+    open_reveal_window(&env, &client, &market_id);
+    let start = env.ledger().timestamp();
+    assert_eq!(client.get_probability_history_len(&market_id), 0);
+    let mut revealed = 0u32;
+    for (user, outcome, amount, salt) in users.iter() {
+        client.reveal_prediction(&user, &market_id, &outcome, &amount, &salt);
+        revealed += 1;
+        assert_eq!(client.get_probability_history_len(&market_id), revealed);
+        env.ledger().with_mut(|li| li.timestamp += 60);
+    }
+
+    // YES-only, then even, then YES grows to 300 of 400
+    let history = client.get_probability_history(&market_id, &0, &10);
+    assert_eq!(history.len(), 3);
+    assert_eq!(history.get(0).unwrap(), (start, 10000));
+    assert_eq!(history.get(1).unwrap(), (start + 60, 5000));
+    assert_eq!(history.get(2).unwrap(), (start + 120, 7500));
+
+    let page = client.get_probability_history(&market_id, &1, &1);
+    assert_eq!(page.len(), 1);
+    assert_eq!(page.get(0).unwrap(), (start + 60, 5000));
+}
+
 #[test]
 fn test_commit_prediction_duplicate_rejected() {
     let env = create_test_env();
@@ -384,6 +877,7 @@ fn test_claim_winnings_happy_path() {
         &1000i128, // Winner shares
         &0i128,    // Loser shares
     );
+    close_dispute_window(&env, &client);
 
     // Setup User Prediction - user voted YES with 1000
     client.test_set_prediction(
@@ -416,6 +910,7 @@ fn test_losing_users_cannot_claim() {
 
     // Winner is YES (1), loser pool has 1000
     client.test_setup_resolution(&market_id, &1u32, &1000, &1000);
+    close_dispute_window(&env, &client);
 
     // User predicted NO (0) - they are a loser
     client.test_set_prediction(&user, &0u32, &500);
@@ -451,6 +946,7 @@ fn test_cannot_double_claim() {
     token_client.mint(&market_contract, &2000);
 
     client.test_setup_resolution(&market_id, &1u32, &1000, &0);
+    close_dispute_window(&env, &client);
     client.test_set_prediction(&user, &1u32, &1000);
 
     // First claim succeeds
@@ -475,6 +971,7 @@ fn test_correct_payout_calculation_with_losers() {
     token_client.mint(&market_contract, &1500);
 
     client.test_setup_resolution(&market_id, &1u32, &1000, &500);
+    close_dispute_window(&env, &client);
     client.test_set_prediction(&user, &1u32, &500);
 
     let payout = client.claim_winnings(&user, &market_id);
@@ -495,6 +992,7 @@ fn test_multiple_winners_correct_proportional_payout() {
     token_client.mint(&market_contract, &2000);
 
     client.test_setup_resolution(&market_id, &1u32, &1000, &1000);
+    close_dispute_window(&env, &client);
     client.test_set_prediction(&user1, &1u32, &600);
     client.test_set_prediction(&user2, &1u32, &400);
 
@@ -525,6 +1023,7 @@ fn test_winner_no_outcome_also_works() {
     client.test_set_prediction(&user, &0u32, &1000); // User voted NO
 
     let payout = client.claim_winnings(&user, &market_id);
+    close_dispute_window(&env, &client);
     assert_eq!(payout, 900); // 1000 - 10% fee
 }
 
@@ -539,6 +1038,7 @@ fn test_user_without_prediction_cannot_claim() {
     token_client.mint(&market_contract, &1000);
 
     client.test_setup_resolution(&market_id, &1u32, &1000, &0);
+    close_dispute_window(&env, &client);
 
     // User has NO prediction - should fail
     client.claim_winnings(&user, &market_id);
@@ -554,6 +1054,7 @@ fn test_claim_updates_prediction_claimed_flag() {
     token_client.mint(&market_contract, &1000);
 
     client.test_setup_resolution(&market_id, &1u32, &1000, &0);
+    close_dispute_window(&env, &client);
     client.test_set_prediction(&user, &1u32, &1000);
 
     // Before claim
@@ -581,6 +1082,7 @@ fn test_small_payout_amounts() {
     token_client.mint(&market_contract, &100);
 
     client.test_setup_resolution(&market_id, &1u32, &100, &0);
+    close_dispute_window(&env, &client);
     client.test_set_prediction(&user, &1u32, &100);
 
     let payout = client.claim_winnings(&user, &market_id);
@@ -599,6 +1101,7 @@ fn test_large_payout_amounts() {
     token_client.mint(&market_contract, &large_amount);
 
     client.test_setup_resolution(&market_id, &1u32, &large_amount, &0);
+    close_dispute_window(&env, &client);
     client.test_set_prediction(&user, &1u32, &large_amount);
 
     let payout = client.claim_winnings(&user, &market_id);
@@ -618,6 +1121,7 @@ fn test_uneven_split_payout() {
     token_client.mint(&market_contract, &1500);
 
     client.test_setup_resolution(&market_id, &1u32, &1000, &500);
+    close_dispute_window(&env, &client);
     client.test_set_prediction(&user, &1u32, &333);
 
     let payout = client.claim_winnings(&user, &market_id);
@@ -639,6 +1143,7 @@ fn test_winnings_claimed_event_emitted() {
     token_client.mint(&market_contract, &1000);
 
     client.test_setup_resolution(&market_id, &1u32, &1000, &0);
+    close_dispute_window(&env, &client);
     client.test_set_prediction(&user, &1u32, &1000);
 
     // Claim winnings
@@ -664,6 +1169,7 @@ fn test_all_winners_no_losers() {
     token_client.mint(&market_contract, &1000);
 
     client.test_setup_resolution(&market_id, &1u32, &1000, &0);
+    close_dispute_window(&env, &client);
     client.test_set_prediction(&user1, &1u32, &600);
     client.test_set_prediction(&user2, &1u32, &400);
 
@@ -687,9 +1193,723 @@ fn test_single_winner_gets_all() {
     token_client.mint(&market_contract, &1000);
 
     client.test_setup_resolution(&market_id, &1u32, &200, &800);
+    close_dispute_window(&env, &client);
     client.test_set_prediction(&winner, &1u32, &200);
 
     // Winner: (200 / 200) * 1000 = 1000, minus 10% = 900
     let payout = client.claim_winnings(&winner, &market_id);
     assert_eq!(payout, 900);
 }
+
+#[test]
+fn test_get_user_prediction_unrevealed_is_none() {
+    let env = create_test_env();
+    let (client, market_id, _creator, _admin, usdc_address) = setup_test_market(&env);
+    let user = commit_yes_and_open_reveals(&env, &client, &market_id, &usdc_address);
+
+    assert!(client.has_committed(&user));
+    assert_eq!(client.get_user_prediction(&user, &market_id), None);
+}
+
+#[test]
+fn test_get_user_prediction_winner_before_claim() {
+    let env = create_test_env();
+    let (client, market_id, _token_client, _market_contract) = setup_market_for_claims(&env);
+    let winner = Address::generate(&env);
+
+    client.test_setup_resolution(&market_id, &1u32, &200, &800);
+    client.test_set_prediction(&winner, &1u32, &200);
+
+    let view = client.get_user_prediction(&winner, &market_id).unwrap();
+    assert_eq!(view.outcome, 1);
+    assert_eq!(view.amount, 200);
+    assert!(!view.claimed);
+    assert!(view.is_winner);
+    // (200 / 200) * 1000 = 1000, minus 10% = 900
+    assert_eq!(view.potential_payout, 900);
+}
+
+#[test]
+fn test_get_user_prediction_loser() {
+    let env = create_test_env();
+    let (client, market_id, _token_client, _market_contract) = setup_market_for_claims(&env);
+    let loser = Address::generate(&env);
+
+    client.test_setup_resolution(&market_id, &1u32, &200, &800);
+    client.test_set_prediction(&loser, &0u32, &800);
+
+    let view = client.get_user_prediction(&loser, &market_id).unwrap();
+    assert_eq!(view.outcome, 0);
+    assert!(!view.is_winner);
+    assert_eq!(view.potential_payout, 0);
+}
+
+#[test]
+fn test_reconcile_market_balances_after_claim() {
+    let env = create_test_env();
+    env.mock_all_auths();
+    let client = PredictionMarketClient::new(&env, &register_market(&env));
+    let market_id = BytesN::from_array(&env, &[1u8; 32]);
+    let admin = Address::generate(&env);
+    let (token, usdc_address) = create_usdc_token(&env, &admin);
+
+    // Claims route the protocol fee through the factory's treasury
+    let factory_id = env.register(MarketFactory, ());
+    let treasury_id = env.register(Treasury, ());
+    TreasuryClient::new(&env, &treasury_id).initialize(&admin, &usdc_address, &factory_id);
+    MarketFactoryClient::new(&env, &factory_id).initialize(&admin, &usdc_address, &treasury_id);
+    TreasuryClient::new(&env, &treasury_id).register_market_depositor(&admin, &client.address);
+
+    let closing_time = env.ledger().timestamp() + 86400;
+    client.initialize(
+        &market_id,
+        &Address::generate(&env),
+        &factory_id,
+        &usdc_address,
+        &Address::generate(&env),
+        &closing_time,
+        &(closing_time + 3600),
+        &MarketConfig::default(),
+    );
+
+    let salt = BytesN::from_array(&env, &[9u8; 32]);
+    let bets = [(600i128, 1u32), (400, 1), (500, 0)];
+    let users = [
+        Address::generate(&env),
+        Address::generate(&env),
+        Address::generate(&env),
+    ];
+    for (user, (amount, outcome)) in users.iter().zip(bets) {
+        token.mint(user, &amount);
+        let hash = client.compute_commit_hash(&market_id, &outcome, &amount, &salt);
+        client.commit_prediction(user, &hash, &amount);
+    }
+    assert_eq!(client.reconcile_market(&market_id), (1500, 1500, 0));
+
+    open_reveal_window(&env, &client, &market_id);
+    for (user, (amount, outcome)) in users.iter().zip(bets) {
+        client.reveal_prediction(user, &market_id, &outcome, &amount, &salt);
+    }
+    client.test_setup_resolution(&market_id, &1u32, &1000, &500);
+    close_dispute_window(&env, &client);
+
+    // Gross (600 / 1000) * 1500 = 900: 810 to the user, 90 to the treasury
+    assert_eq!(client.claim_winnings(&users[0], &market_id), 810);
+    assert_eq!(client.reconcile_market(&market_id), (600, 600, 0));
+
+    // USDC sent straight to the contract shows up as a discrepancy
+    token.mint(&client.address, &5);
+    assert_eq!(client.reconcile_market(&market_id), (605, 600, 5));
+}
+
+/// Market capping each reveal at 50% of the pool, with 1000 / 500 / 1000 committed
+fn setup_pool_capped_market(env: &Env) -> (PredictionMarketClient<'_>, BytesN<32>, [Address; 3]) {
+    env.mock_all_auths();
+    let client = PredictionMarketClient::new(env, &register_market(env));
+    let market_id = BytesN::from_array(env, &[1u8; 32]);
+    let (token, usdc_address) = create_usdc_token(env, &Address::generate(env));
+
+    let closing_time = env.ledger().timestamp() + 86400;
+    client.initialize(
+        &market_id,
+        &Address::generate(env),
+        &Address::generate(env),
+        &usdc_address,
+        &Address::generate(env),
+        &closing_time,
+        &(closing_time + 3600),
+        &MarketConfig {
+            max_bet_vs_pool_bps: 5000,
+            ..Default::default()
+        },
+    );
+    assert_eq!(client.get_max_bet_vs_pool_bps(), 5000);
+
+    let salt = BytesN::from_array(env, &[4u8; 32]);
+    let users = [
+        Address::generate(env),
+        Address::generate(env),
+        Address::generate(env),
+    ];
+    for (user, amount) in users.iter().zip([1000i128, 500, 1000]) {
+        token.mint(user, &amount);
+        let hash = client.compute_commit_hash(&market_id, &1u32, &amount, &salt);
+        client.commit_prediction(user, &hash, &amount);
+    }
+    open_reveal_window(env, &client, &market_id);
+    (client, market_id, users)
+}
+
+#[test]
+fn test_reveal_within_pool_share_cap() {
+    let env = create_test_env();
+    let (client, market_id, users) = setup_pool_capped_market(&env);
+    let salt = BytesN::from_array(&env, &[4u8; 32]);
+
+    // The first reveal has no pool to measure against; 500 is exactly 50% of 1000
+    client.reveal_prediction(&users[0], &market_id, &1u32, &1000i128, &salt);
+    client.reveal_prediction(&users[1], &market_id, &1u32, &500i128, &salt);
+    assert_eq!(client.get_pool_sizes(), (1500, 0));
+}
+
+#[test]
+#[should_panic(expected = "bet exceeds pool share cap")]
+fn test_reveal_over_pool_share_cap_rejected() {
+    let env = create_test_env();
+    let (client, market_id, users) = setup_pool_capped_market(&env);
+    let salt = BytesN::from_array(&env, &[4u8; 32]);
+
+    client.reveal_prediction(&users[0], &market_id, &1u32, &1000i128, &salt);
+    client.reveal_prediction(&users[1], &market_id, &1u32, &500i128, &salt);
+    // 1000 is more than 50% of the 1500 pool
+    client.reveal_prediction(&users[2], &market_id, &1u32, &1000i128, &salt);
+}
+
+/// Market whose claim fees go to its own treasury, set through `fee_recipient`
+fn setup_market_with_fee_treasury(
+    env: &Env,
+    config: MarketConfig,
+) -> (
+    PredictionMarketClient<'_>,
+    BytesN<32>,
+    token::StellarAssetClient<'_>,
+    TreasuryClient<'_>,
+) {
+    env.mock_all_auths();
+    let client = PredictionMarketClient::new(env, &register_market(env));
+    let market_id = BytesN::from_array(env, &[1u8; 32]);
+    let admin = Address::generate(env);
+    let (token, usdc_address) = create_usdc_token(env, &admin);
+
+    let treasury_id = env.register(Treasury, ());
+    let treasury = TreasuryClient::new(env, &treasury_id);
+    treasury.initialize(&admin, &usdc_address, &Address::generate(env));
+    treasury.register_market_depositor(&admin, &client.address);
+
+    let closing_time = env.ledger().timestamp() + 86400;
+    client.initialize(
+        &market_id,
+        &Address::generate(env),
+        &Address::generate(env),
+        &usdc_address,
+        &Address::generate(env),
+        &closing_time,
+        &(closing_time + 3600),
+        &MarketConfig {
+            fee_recipient: Some(treasury_id),
+            ..config
+        },
+    );
+    (client, market_id, token, treasury)
+}
+
+#[test]
+fn test_claim_routes_fee_to_treasury_platform_pool() {
+    let env = create_test_env();
+    let (client, market_id, token, treasury) =
+        setup_market_with_fee_treasury(&env, MarketConfig::default());
+    // Per-market override: no factory lookup is needed to find the treasury
+    assert_eq!(client.get_fee_recipient(), treasury.address);
+
+    let user = Address::generate(&env);
+    token.mint(&client.address, &1000);
+    client.test_setup_resolution(&market_id, &1u32, &1000i128, &0i128);
+    close_dispute_window(&env, &client);
+    client.test_set_prediction(&user, &1u32, &1000i128);
+
+    assert_eq!(client.claim_winnings(&user, &market_id), 900);
+    assert_eq!(treasury.get_platform_fees(), 100);
+    assert_eq!(treasury.get_leaderboard_fees(), 0);
+    let usdc = token::TokenClient::new(&env, &token.address);
+    assert_eq!(usdc.balance(&treasury.address), 100);
+    assert_eq!(usdc.balance(&client.address), 0);
+}
+
+#[test]
+fn test_claimed_total_sums_gross_payouts() {
+    let env = create_test_env();
+    let (client, market_id, token, _treasury) =
+        setup_market_with_fee_treasury(&env, MarketConfig::default());
+    assert_eq!(client.get_claimed_total(), 0);
+
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    token.mint(&client.address, &2000);
+    client.test_setup_resolution(&market_id, &1u32, &1000i128, &1000i128);
+    close_dispute_window(&env, &client);
+    client.test_set_prediction(&user1, &1u32, &600i128);
+    client.test_set_prediction(&user2, &1u32, &400i128);
+
+    // Gross payouts: (600 / 1000) * 2000 = 1200 and (400 / 1000) * 2000 = 800
+    assert_eq!(client.claim_winnings(&user1, &market_id), 1080);
+    assert_eq!(client.get_claimed_total(), 1200);
+    assert_eq!(client.claim_winnings(&user2, &market_id), 720);
+    assert_eq!(client.get_claimed_total(), 2000);
+}
+
+/// Creator and another user back the winning side 600 / 400 of a 2000 pool
+fn claim_creator_and_user(client: &PredictionMarketClient, market_id: &BytesN<32>) -> (i128, i128) {
+    let env = &client.env;
+    let creator = client.get_creator();
+    let user = Address::generate(env);
+    client.test_setup_resolution(market_id, &1u32, &1000i128, &1000i128);
+    close_dispute_window(&env, &client);
+    client.test_set_prediction(&creator, &1u32, &600i128);
+    client.test_set_prediction(&user, &1u32, &400i128);
+    (
+        client.claim_winnings(&creator, market_id),
+        client.claim_winnings(&user, market_id),
+    )
+}
+
+#[test]
+fn test_creator_fee_exempt_claim() {
+    let env = create_test_env();
+    let config = MarketConfig {
+        creator_fee_exempt: true,
+        ..Default::default()
+    };
+    let (client, market_id, token, treasury) = setup_market_with_fee_treasury(&env, config);
+    token.mint(&client.address, &2000);
+
+    // Gross 1200 untaxed for the creator; the other user still pays 10% of 800
+    assert_eq!(claim_creator_and_user(&client, &market_id), (1200, 720));
+    assert_eq!(treasury.get_platform_fees(), 80);
+}
+
+#[test]
+fn test_creator_pays_fee_without_exemption() {
+    let env = create_test_env();
+    let (client, market_id, token, treasury) =
+        setup_market_with_fee_treasury(&env, MarketConfig::default());
+    token.mint(&client.address, &2000);
+
+    assert_eq!(claim_creator_and_user(&client, &market_id), (1080, 720));
+    assert_eq!(treasury.get_platform_fees(), 200);
+}
+
+/// Resolved YES on 1000 / 500 pools, then disputed by the NO backer
+fn setup_disputed_market(env: &Env) -> (PredictionMarketClient<'_>, BytesN<32>, Address, Address) {
+    let (client, market_id, token, _treasury) =
+        setup_market_with_fee_treasury(env, MarketConfig::default());
+    let yes_user = Address::generate(env);
+    let no_user = Address::generate(env);
+    token.mint(&client.address, &1500);
+    env.as_contract(&client.address, || {
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(env, "yes_pool"), &1000i128);
+        env.storage()
+            .persistent()
+            .set(&Symbol::new(env, "no_pool"), &500i128);
+    });
+    client.test_set_prediction(&yes_user, &1u32, &1000i128);
+    client.test_set_prediction(&no_user, &0u32, &500i128);
+    client.test_setup_resolution(&market_id, &1u32, &1000i128, &500i128);
+
+    client.dispute_market(&no_user, &market_id, &Symbol::new(env, "wrong_outcome"));
+    assert_eq!(client.get_market_state_value(), Some(4));
+    assert!(client.try_claim_winnings(&yes_user, &market_id).is_err());
+    (client, market_id, yes_user, no_user)
+}
+
+#[test]
+fn test_upheld_dispute_flips_outcome() {
+    let env = create_test_env();
+    let (client, market_id, yes_user, no_user) = setup_disputed_market(&env);
+
+    client.resolve_dispute(&client.get_factory(), &market_id, &true, &Some(0u32));
+
+    assert_eq!(client.get_winning_outcome(), Some(0));
+    assert_eq!(client.get_market_state_value(), Some(2));
+    assert_eq!(client.get_dispute(), None);
+    assert!(client.try_claim_winnings(&yes_user, &market_id).is_err());
+    // NO now takes the whole 1500 pool, less the 10% fee
+    assert_eq!(client.claim_winnings(&no_user, &market_id), 1350);
+}
+
+#[test]
+fn test_rejected_dispute_restores_original_outcome() {
+    let env = create_test_env();
+    let (client, market_id, yes_user, no_user) = setup_disputed_market(&env);
+
+    client.resolve_dispute(&client.get_factory(), &market_id, &false, &None);
+
+    assert_eq!(client.get_winning_outcome(), Some(1));
+    assert_eq!(client.get_market_state_value(), Some(2));
+    assert!(client.try_claim_winnings(&no_user, &market_id).is_err());
+    assert_eq!(client.claim_winnings(&yes_user, &market_id), 1350);
+}
+
+#[test]
+#[should_panic(expected = "Market already disputed")]
+fn test_market_disputed_only_once() {
+    let env = create_test_env();
+    let (client, market_id, _yes_user, no_user) = setup_disputed_market(&env);
+
+    client.resolve_dispute(&client.get_factory(), &market_id, &false, &None);
+    client.dispute_market(&no_user, &market_id, &Symbol::new(&env, "wrong_outcome"));
+}
+
+#[test]
+fn test_loser_refunds_paid_after_every_winner_claims() {
+    let env = create_test_env();
+    let config = MarketConfig {
+        loser_refund_bps: 2000,
+        ..Default::default()
+    };
+    let (client, market_id, token, treasury) = setup_market_with_fee_treasury(&env, config);
+    let usdc = token::TokenClient::new(&env, &token.address);
+    let winners = [Address::generate(&env), Address::generate(&env)];
+    let losers = [Address::generate(&env), Address::generate(&env)];
+    client.test_set_prediction(&winners[0], &1u32, &600i128);
+    client.test_set_prediction(&winners[1], &1u32, &400i128);
+    client.test_set_prediction(&losers[0], &0u32, &1000i128);
+    client.test_set_prediction(&losers[1], &0u32, &500i128);
+    token.mint(&client.address, &2500);
+    client.test_setup_resolution(&market_id, &1u32, &1000i128, &1500i128);
+    close_dispute_window(&env, &client);
+
+    // 20% of the 1500 losing pool is set aside; winners split the other 2200,
+    // and the 220 in fees alone could not cover the refunds
+    assert_eq!(client.get_loser_refund_reserve(), 300);
+    assert_eq!(client.claim_winnings(&winners[0], &market_id), 1188);
+    assert_eq!(client.claim_winnings(&winners[1], &market_id), 792);
+    assert_eq!(treasury.get_platform_fees(), 220);
+    assert_eq!(client.get_retained_fees(), 0);
+
+    assert_eq!(client.refund_losing_bet(&losers[0], &market_id), 200);
+    assert_eq!(client.refund_losing_bet(&losers[1], &market_id), 100);
+    assert_eq!(usdc.balance(&client.address), 0);
+}
+
+#[test]
+#[should_panic(expected = "Dispute window open")]
+fn test_claim_inside_dispute_window_rejected() {
+    let env = create_test_env();
+    let (client, market_id, token, _treasury) =
+        setup_market_with_fee_treasury(&env, MarketConfig::default());
+    let user = Address::generate(&env);
+    token.mint(&client.address, &1000);
+    client.test_setup_resolution(&market_id, &1u32, &1000i128, &0i128);
+    client.test_set_prediction(&user, &1u32, &1000i128);
+
+    let window_end = client.get_dispute_window_end().unwrap();
+    env.ledger().with_mut(|li| li.timestamp = window_end - 1);
+    client.claim_winnings(&user, &market_id);
+}
+
+#[test]
+#[should_panic(expected = "Dispute window closed")]
+fn test_dispute_after_claim_rejected() {
+    let env = create_test_env();
+    let (client, market_id, token, _treasury) =
+        setup_market_with_fee_treasury(&env, MarketConfig::default());
+    let user = Address::generate(&env);
+    token.mint(&client.address, &1000);
+    client.test_setup_resolution(&market_id, &1u32, &1000i128, &0i128);
+    client.test_set_prediction(&user, &1u32, &1000i128);
+    close_dispute_window(&env, &client);
+    client.claim_winnings(&user, &market_id);
+
+    client.dispute_market(&user, &market_id, &Symbol::new(&env, "late"));
+}
+
+/// Market resolving against a real oracle contract with one registered oracle
+fn setup_market_with_registered_oracle(
+    env: &Env,
+    config: MarketConfig,
+) -> (PredictionMarketClient<'_>, BytesN<32>, Address) {
+    env.mock_all_auths();
+    let admin = Address::generate(env);
+    let oracle_id = env.register(OracleManager, ());
+    let oracle = OracleManagerClient::new(env, &oracle_id);
+    let factory = Address::generate(env);
+    let oracle_config = OracleConfig {
+        factory: Some(factory.clone()),
+        ..OracleConfig::default()
+    };
+    oracle.initialize(&admin, &1u32, &oracle_config);
+    let reporter = Address::generate(env);
+    oracle.register_oracle(&reporter, &Symbol::new(env, "Reporter"));
+
+    let client = PredictionMarketClient::new(env, &register_market(env));
+    let market_id = BytesN::from_array(env, &[1u8; 32]);
+    let (_token, usdc_address) = create_usdc_token(env, &admin);
+    let closing_time = env.ledger().timestamp() + 86400;
+    client.initialize(
+        &market_id,
+        &Address::generate(env),
+        &Address::generate(env),
+        &usdc_address,
+        &oracle_id,
+        &closing_time,
+        &(closing_time + 3600),
+        &config,
+    );
+    // The factory registers each market it deploys with the oracle
+    oracle.request_resolution(
+        &factory,
+        &client.address,
+        &market_id,
+        &(closing_time + 3600),
+    );
+    (client, market_id, reporter)
+}
+
+#[test]
+fn test_oracle_extends_resolution_time() {
+    let env = create_test_env();
+    let config = MarketConfig {
+        max_resolution_extension: 86400,
+        ..Default::default()
+    };
+    let (client, market_id, reporter) = setup_market_with_registered_oracle(&env, config);
+    let original = client.get_market_state(&market_id).resolution_time;
+
+    client.request_resolution_extension(&reporter, &market_id, &(original + 43200));
+    assert_eq!(
+        client.get_market_state(&market_id).resolution_time,
+        original + 43200
+    );
+
+    // The bound is measured from the original time, not the latest extension
+    client.request_resolution_extension(&reporter, &market_id, &(original + 86400));
+    assert_eq!(
+        client.get_market_state(&market_id).resolution_time,
+        original + 86400
+    );
+
+    // Oracles discover the market at the extended time, not the original one
+    let oracle = OracleManagerClient::new(&env, &client.get_oracle());
+    env.ledger().with_mut(|li| li.timestamp = original);
+    assert_eq!(
+        oracle
+            .get_markets_awaiting_attestation(&reporter, &0, &10)
+            .len(),
+        0
+    );
+    env.ledger().with_mut(|li| li.timestamp = original + 86400);
+    assert_eq!(
+        oracle.get_markets_awaiting_attestation(&reporter, &0, &10),
+        Vec::from_array(&env, [market_id])
+    );
+}
+
+#[test]
+#[should_panic(expected = "Extension exceeds maximum")]
+fn test_resolution_extension_beyond_max_rejected() {
+    let env = create_test_env();
+    let config = MarketConfig {
+        max_resolution_extension: 86400,
+        ..Default::default()
+    };
+    let (client, market_id, reporter) = setup_market_with_registered_oracle(&env, config);
+    let original = client.get_market_state(&market_id).resolution_time;
+
+    client.request_resolution_extension(&reporter, &market_id, &(original + 86401));
+}
+
+#[test]
+#[should_panic(expected = "only a registered oracle can extend resolution")]
+fn test_resolution_extension_requires_registered_oracle() {
+    let env = create_test_env();
+    let (client, market_id, _reporter) =
+        setup_market_with_registered_oracle(&env, MarketConfig::default());
+    let original = client.get_market_state(&market_id).resolution_time;
+
+    client.request_resolution_extension(&Address::generate(&env), &market_id, &(original + 60));
+}
+
+#[test]
+fn test_claim_retains_fee_when_treasury_fails() {
+    let env = create_test_env();
+    env.mock_all_auths();
+    let client = PredictionMarketClient::new(&env, &register_market(&env));
+    let market_id = BytesN::from_array(&env, &[1u8; 32]);
+    let admin = Address::generate(&env);
+    let (token, usdc_address) = create_usdc_token(&env, &admin);
+
+    // The market is not yet a registered depositor, so its fee deposit fails
+    let treasury_id = env.register(Treasury, ());
+    let treasury = TreasuryClient::new(&env, &treasury_id);
+    treasury.initialize(&admin, &usdc_address, &Address::generate(&env));
+
+    let closing_time = env.ledger().timestamp() + 86400;
+    client.initialize(
+        &market_id,
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &usdc_address,
+        &Address::generate(&env),
+        &closing_time,
+        &(closing_time + 3600),
+        &MarketConfig {
+            fee_recipient: Some(treasury_id.clone()),
+            ..Default::default()
+        },
+    );
+
+    let user = Address::generate(&env);
+    token.mint(&client.address, &1000);
+    client.test_setup_resolution(&market_id, &1u32, &1000i128, &0i128);
+    close_dispute_window(&env, &client);
+    client.test_set_prediction(&user, &1u32, &1000i128);
+
+    // The winner is paid in full despite the failed fee deposit
+    assert_eq!(client.claim_winnings(&user, &market_id), 900);
+    let usdc = token::TokenClient::new(&env, &usdc_address);
+    assert_eq!(usdc.balance(&user), 900);
+    assert_eq!(usdc.balance(&client.address), 100);
+    assert_eq!(client.get_retained_fees(), 100);
+    assert_eq!(treasury.get_platform_fees(), 0);
+
+    // Once the treasury accepts the market the factory sweeps the fee across;
+    // the fee_recipient override wins over the factory's treasury
+    treasury.register_market_depositor(&admin, &client.address);
+    assert_eq!(client.sweep_retained_fees(&Address::generate(&env)), 100);
+    assert_eq!(treasury.get_platform_fees(), 100);
+    assert_eq!(usdc.balance(&treasury_id), 100);
+    assert_eq!(usdc.balance(&client.address), 0);
+    assert_eq!(client.get_retained_fees(), 0);
+}
+
+#[test]
+fn test_bet_limits_reflect_config_and_bound_commits() {
+    let env = create_test_env();
+    env.mock_all_auths();
+    let client = PredictionMarketClient::new(&env, &register_market(&env));
+    let market_id = BytesN::from_array(&env, &[1u8; 32]);
+    let (token, usdc_address) = create_usdc_token(&env, &Address::generate(&env));
+    let closing_time = env.ledger().timestamp() + 86400;
+    client.initialize(
+        &market_id,
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &usdc_address,
+        &Address::generate(&env),
+        &closing_time,
+        &(closing_time + 3600),
+        &MarketConfig {
+            min_bet: 1_000_000,
+            max_bet: 50_000_000,
+            ..Default::default()
+        },
+    );
+    assert_eq!(client.get_bet_limits(), (1_000_000, 50_000_000));
+
+    let user = Address::generate(&env);
+    token.mint(&user, &100_000_000);
+    let commit_hash = BytesN::from_array(&env, &[7u8; 32]);
+    assert_eq!(
+        client.try_commit_prediction(&user, &commit_hash, &999_999),
+        Err(Ok(MarketError::BetBelowMinimum))
+    );
+    assert_eq!(
+        client.try_commit_prediction(&user, &commit_hash, &50_000_001),
+        Err(Ok(MarketError::BetAboveMaximum))
+    );
+    client.commit_prediction(&user, &commit_hash, &50_000_000);
+    assert!(client.has_committed(&user));
+}
+
+#[test]
+fn test_bet_limits_default_to_unbounded() {
+    let env = create_test_env();
+    let (client, _market_id, _creator, _admin, _usdc_address) = setup_test_market(&env);
+    assert_eq!(client.get_bet_limits(), (0, i128::MAX));
+}
+
+#[test]
+fn test_max_bets_per_user_rejects_beyond_cap() {
+    let env = create_test_env();
+    env.mock_all_auths();
+    let client = PredictionMarketClient::new(&env, &register_market(&env));
+    let market_id = BytesN::from_array(&env, &[1u8; 32]);
+    let (token, usdc_address) = create_usdc_token(&env, &Address::generate(&env));
+    let closing_time = env.ledger().timestamp() + 86400;
+    client.initialize(
+        &market_id,
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &usdc_address,
+        &Address::generate(&env),
+        &closing_time,
+        &(closing_time + 3600),
+        &MarketConfig {
+            max_bets_per_user: 1,
+            ..Default::default()
+        },
+    );
+    assert_eq!(client.get_max_bets_per_user(), 1);
+
+    let user = Address::generate(&env);
+    token.mint(&user, &100_000_000);
+    assert_eq!(client.get_user_bet_count(&user), 0);
+    client.commit_prediction(&user, &BytesN::from_array(&env, &[7u8; 32]), &10_000_000);
+    assert_eq!(client.get_user_bet_count(&user), 1);
+
+    assert_eq!(
+        client.try_commit_prediction(&user, &BytesN::from_array(&env, &[8u8; 32]), &10_000_000),
+        Err(Ok(MarketError::BetLimitReached))
+    );
+    assert_eq!(client.get_user_bet_count(&user), 1);
+
+    // The cap is per user
+    let other = Address::generate(&env);
+    token.mint(&other, &100_000_000);
+    client.commit_prediction(&other, &BytesN::from_array(&env, &[9u8; 32]), &10_000_000);
+    assert_eq!(client.get_user_bet_count(&other), 1);
+}
+
+#[test]
+fn test_max_bets_per_user_defaults_to_uncapped() {
+    let env = create_test_env();
+    let (client, _market_id, _creator, _admin, _usdc_address) = setup_test_market(&env);
+    assert_eq!(client.get_max_bets_per_user(), 0);
+}
+
+#[test]
+fn test_dispute_after_one_day_window_rejected() {
+    let env = create_test_env();
+    let config = MarketConfig {
+        dispute_window_seconds: 86400,
+        ..Default::default()
+    };
+    let (client, market_id, _token, _treasury) = setup_market_with_fee_treasury(&env, config);
+    assert_eq!(client.get_dispute_window(), 86400);
+    let user = Address::generate(&env);
+    client.test_set_prediction(&user, &0u32, &500i128);
+    let resolution_time = client.get_market_state(&market_id).resolution_time;
+    env.ledger().with_mut(|li| li.timestamp = resolution_time);
+    client.test_setup_resolution(&market_id, &1u32, &1000i128, &500i128);
+
+    // Day 2 after resolving: past the 1-day window
+    env.ledger()
+        .with_mut(|li| li.timestamp = resolution_time + 86400 + 3600);
+    assert_eq!(
+        client.get_market_phase(&env.ledger().timestamp()),
+        Symbol::new(&env, "CLAIMABLE")
+    );
+    let reason = Symbol::new(&env, "wrong_outcome");
+    assert!(client
+        .try_dispute_market(&user, &market_id, &reason)
+        .is_err());
+
+    // The same dispute is accepted within the window
+    env.ledger()
+        .with_mut(|li| li.timestamp = resolution_time + 3600);
+    client.dispute_market(&user, &market_id, &reason);
+    assert_eq!(client.get_market_state_value(), Some(4));
+}
+
+#[test]
+#[should_panic(expected = "dispute window out of range")]
+fn test_dispute_window_beyond_max_rejected() {
+    let env = create_test_env();
+    let config = MarketConfig {
+        dispute_window_seconds: 31 * 24 * 60 * 60,
+        ..Default::default()
+    };
+    setup_market_with_fee_treasury(&env, config);
+}
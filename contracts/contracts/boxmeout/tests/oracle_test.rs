@@ -1,11 +1,12 @@
 #![cfg(test)]
 
 use soroban_sdk::{
-    testutils::{Address as _, Ledger},
-    Address, BytesN, Env, Symbol,
+    testutils::{Address as _, Events, Ledger},
+    token::{StellarAssetClient, TokenClient},
+    vec, Address, BytesN, Env, IntoVal, Symbol, TryFromVal, Val,
 };
 
-use boxmeout::{OracleManager, OracleManagerClient};
+use boxmeout::{ConsensusMode, OracleConfig, OracleError, OracleManager, OracleManagerClient};
 
 fn create_test_env() -> Env {
     Env::default()
@@ -15,6 +16,19 @@ fn register_oracle(env: &Env) -> Address {
     env.register_contract(None, OracleManager)
 }
 
+// Helper to find the data of the last event `name` emitted by `contract`
+fn find_event<T: TryFromVal<Env, Val>>(env: &Env, contract: &Address, name: &str) -> T {
+    let topics: soroban_sdk::Vec<Val> = (Symbol::new(env, name),).into_val(env);
+    let (_, _, data) = env
+        .events()
+        .all()
+        .iter()
+        .filter(|(address, event_topics, _)| address == contract && *event_topics == topics)
+        .last()
+        .expect("event not emitted");
+    T::try_from_val(env, &data).unwrap()
+}
+
 #[test]
 fn test_oracle_initialize() {
     let env = create_test_env();
@@ -25,10 +39,10 @@ fn test_oracle_initialize() {
     let required_consensus = 2u32; // 2 of 3 oracles
 
     env.mock_all_auths();
-    client.initialize(&admin, &required_consensus);
+    client.initialize(&admin, &required_consensus, &OracleConfig::default());
 
-    // TODO: Add getters to verify
     // Verify required_consensus stored correctly
+    assert_eq!(client.get_required_consensus(), 2);
 }
 
 #[test]
@@ -41,7 +55,7 @@ fn test_register_oracle() {
 
     let admin = Address::generate(&env);
     let required_consensus = 2u32;
-    client.initialize(&admin, &required_consensus);
+    client.initialize(&admin, &required_consensus, &OracleConfig::default());
 
     // Register oracle
     let oracle1 = Address::generate(&env);
@@ -53,6 +67,23 @@ fn test_register_oracle() {
     // Verify oracle count incremented
 }
 
+#[test]
+fn test_get_oracle_registered_at() {
+    let env = create_test_env();
+    env.mock_all_auths();
+
+    let oracle_id = register_oracle(&env);
+    let client = OracleManagerClient::new(&env, &oracle_id);
+    client.initialize(&Address::generate(&env), &2u32, &OracleConfig::default());
+
+    let oracle1 = Address::generate(&env);
+    assert_eq!(client.get_oracle_registered_at(&oracle1), 0);
+
+    env.ledger().with_mut(|li| li.timestamp = 1_700_000_000);
+    client.register_oracle(&oracle1, &Symbol::new(&env, "Oracle1"));
+    assert_eq!(client.get_oracle_registered_at(&oracle1), 1_700_000_000);
+}
+
 #[test]
 fn test_register_multiple_oracles() {
     let env = create_test_env();
@@ -62,7 +93,7 @@ fn test_register_multiple_oracles() {
     let client = OracleManagerClient::new(&env, &oracle_id);
 
     let admin = Address::generate(&env);
-    client.initialize(&admin, &2u32);
+    client.initialize(&admin, &2u32, &OracleConfig::default());
 
     // Register 3 oracles
     let oracle1 = Address::generate(&env);
@@ -77,7 +108,36 @@ fn test_register_multiple_oracles() {
 }
 
 #[test]
-#[should_panic(expected = "Maximum oracle limit reached")]
+fn test_get_oracle_names_skips_deregistered() {
+    let env = create_test_env();
+    env.mock_all_auths();
+
+    let oracle_id = register_oracle(&env);
+    let client = OracleManagerClient::new(&env, &oracle_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin, &2u32, &OracleConfig::default());
+
+    let oracle1 = Address::generate(&env);
+    let oracle2 = Address::generate(&env);
+    let oracle3 = Address::generate(&env);
+    client.register_oracle(&oracle1, &Symbol::new(&env, "Oracle1"));
+    client.register_oracle(&oracle2, &Symbol::new(&env, "Oracle2"));
+    client.register_oracle(&oracle3, &Symbol::new(&env, "Oracle3"));
+    client.deregister_oracle(&oracle3);
+
+    let names = client.get_oracle_names();
+    assert_eq!(
+        names,
+        vec![
+            &env,
+            (oracle1, Symbol::new(&env, "Oracle1")),
+            (oracle2, Symbol::new(&env, "Oracle2")),
+        ]
+    );
+}
+
+#[test]
 fn test_register_oracle_exceeds_limit() {
     let env = create_test_env();
     env.mock_all_auths();
@@ -86,19 +146,21 @@ fn test_register_oracle_exceeds_limit() {
     let client = OracleManagerClient::new(&env, &oracle_id);
 
     let admin = Address::generate(&env);
-    client.initialize(&admin, &2u32);
+    client.initialize(&admin, &2u32, &OracleConfig::default());
 
-    // Register 11 oracles (limit is 10)
-    for i in 0..11 {
+    // Register 10 oracles (the limit)
+    for _ in 0..10 {
         let oracle = Address::generate(&env);
         let name = Symbol::new(&env, "Oracle");
         client.register_oracle(&oracle, &name);
     }
+
+    // The 11th is rejected
+    let result = client.try_register_oracle(&Address::generate(&env), &Symbol::new(&env, "Oracle"));
+    assert_eq!(result, Err(Ok(OracleError::MaxOraclesReached)));
 }
 
 #[test]
-#[should_panic(expected = "oracle already registered")]
-#[should_panic]
 fn test_register_duplicate_oracle() {
     let env = create_test_env();
     env.mock_all_auths();
@@ -107,7 +169,7 @@ fn test_register_duplicate_oracle() {
     let client = OracleManagerClient::new(&env, &oracle_id);
 
     let admin = Address::generate(&env);
-    client.initialize(&admin, &2u32);
+    client.initialize(&admin, &2u32, &OracleConfig::default());
 
     let oracle1 = Address::generate(&env);
     let name = Symbol::new(&env, "Oracle1");
@@ -116,7 +178,45 @@ fn test_register_duplicate_oracle() {
     client.register_oracle(&oracle1, &name);
 
     // Try to register same oracle again
-    client.register_oracle(&oracle1, &name);
+    let result = client.try_register_oracle(&oracle1, &name);
+    assert_eq!(result, Err(Ok(OracleError::OracleAlreadyRegistered)));
+}
+
+#[test]
+fn test_register_duplicate_name_rejected_when_unique() {
+    let env = create_test_env();
+    env.mock_all_auths();
+
+    let oracle_id = register_oracle(&env);
+    let client = OracleManagerClient::new(&env, &oracle_id);
+
+    let admin = Address::generate(&env);
+    let config = OracleConfig {
+        unique_oracle_names: true,
+        ..Default::default()
+    };
+    client.initialize(&admin, &2u32, &config);
+
+    let name = Symbol::new(&env, "Oracle1");
+    client.register_oracle(&Address::generate(&env), &name);
+    let result = client.try_register_oracle(&Address::generate(&env), &name);
+    assert_eq!(result, Err(Ok(OracleError::OracleNameTaken)));
+}
+
+#[test]
+fn test_register_duplicate_name_allowed_when_not_unique() {
+    let env = create_test_env();
+    env.mock_all_auths();
+
+    let oracle_id = register_oracle(&env);
+    let client = OracleManagerClient::new(&env, &oracle_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin, &2u32, &OracleConfig::default());
+
+    let name = Symbol::new(&env, "Oracle1");
+    client.register_oracle(&Address::generate(&env), &name);
+    client.register_oracle(&Address::generate(&env), &name);
 }
 
 #[test]
@@ -128,7 +228,7 @@ fn test_submit_attestation() {
     let client = OracleManagerClient::new(&env, &oracle_id);
 
     let admin = Address::generate(&env);
-    client.initialize(&admin, &2u32);
+    client.initialize(&admin, &2u32, &OracleConfig::default());
 
     let oracle1 = Address::generate(&env);
     client.register_oracle(&oracle1, &Symbol::new(&env, "Oracle1"));
@@ -145,6 +245,319 @@ fn test_submit_attestation() {
     assert_eq!(outcome, 0);
 }
 
+#[test]
+fn test_submit_attestation_errors() {
+    let env = create_test_env();
+    env.mock_all_auths();
+
+    let oracle_id = register_oracle(&env);
+    let client = OracleManagerClient::new(&env, &oracle_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin, &2u32, &OracleConfig::default());
+
+    let oracle1 = Address::generate(&env);
+    client.register_oracle(&oracle1, &Symbol::new(&env, "Oracle1"));
+
+    let market_id = BytesN::from_array(&env, &[1u8; 32]);
+    let data_hash = BytesN::from_array(&env, &[0u8; 32]);
+
+    let unregistered = Address::generate(&env);
+    let result = client.try_submit_attestation(&unregistered, &market_id, &1u32, &data_hash);
+    assert_eq!(result, Err(Ok(OracleError::OracleNotRegistered)));
+
+    let result = client.try_submit_attestation(&oracle1, &market_id, &2u32, &data_hash);
+    assert_eq!(result, Err(Ok(OracleError::InvalidAttestationResult)));
+
+    client.submit_attestation(&oracle1, &market_id, &1u32, &data_hash);
+    let result = client.try_submit_attestation(&oracle1, &market_id, &0u32, &data_hash);
+    assert_eq!(result, Err(Ok(OracleError::AlreadyAttested)));
+}
+
+#[test]
+fn test_get_market_result_transitions() {
+    let env = create_test_env();
+    env.mock_all_auths();
+
+    let oracle_id = register_oracle(&env);
+    let client = OracleManagerClient::new(&env, &oracle_id);
+    client.initialize(&Address::generate(&env), &2u32, &OracleConfig::default());
+
+    let oracle1 = Address::generate(&env);
+    let oracle2 = Address::generate(&env);
+    client.register_oracle(&oracle1, &Symbol::new(&env, "Oracle1"));
+    client.register_oracle(&oracle2, &Symbol::new(&env, "Oracle2"));
+
+    let market_id = BytesN::from_array(&env, &[1u8; 32]);
+    let data_hash = BytesN::from_array(&env, &[0u8; 32]);
+
+    // One vote: no consensus yet
+    client.submit_attestation(&oracle1, &market_id, &1u32, &data_hash);
+    let result = client.get_market_result(&market_id);
+    assert!(!result.consensus_reached);
+    assert!(!result.finalized);
+    assert_eq!(result.voter_count, 1);
+    assert_eq!(
+        client.try_finalize_resolution(&market_id),
+        Err(Ok(OracleError::ConsensusNotReached))
+    );
+
+    // Second vote reaches consensus; an open challenge blocks finality
+    client.submit_attestation(&oracle2, &market_id, &1u32, &data_hash);
+    let challenger = Address::generate(&env);
+    client.challenge_attestation(
+        &challenger,
+        &oracle2,
+        &market_id,
+        &Symbol::new(&env, "wrong"),
+    );
+    let result = client.get_market_result(&market_id);
+    assert!(result.consensus_reached);
+    assert_eq!(result.outcome, 1);
+    assert_eq!(result.voter_count, 2);
+    assert!(result.challenge_active);
+    assert!(!result.finalized);
+    assert_eq!(
+        client.try_finalize_resolution(&market_id),
+        Err(Ok(OracleError::ChallengeActive))
+    );
+
+    // Challenge rejected: the result can be finalized once the window passes
+    client.resolve_challenge(&oracle2, &market_id, &false);
+    assert!(!client.get_market_result(&market_id).challenge_active);
+    assert_eq!(
+        client.try_finalize_resolution(&market_id),
+        Err(Ok(OracleError::ChallengeWindowOpen))
+    );
+    let challenge_window = client.get_challenge_window();
+    env.ledger().with_mut(|li| li.timestamp += challenge_window);
+    assert_eq!(client.finalize_resolution(&market_id), 1);
+
+    let result = client.get_market_result(&market_id);
+    assert!(result.finalized);
+    assert_eq!(result.outcome, 1);
+    assert_eq!(client.get_consensus_result(&market_id), 1);
+    assert_eq!(
+        client.try_finalize_resolution(&market_id),
+        Err(Ok(OracleError::AlreadyFinalized))
+    );
+}
+
+#[test]
+fn test_finalize_waits_for_challenge_window() {
+    let env = create_test_env();
+    env.mock_all_auths();
+
+    let oracle_id = register_oracle(&env);
+    let client = OracleManagerClient::new(&env, &oracle_id);
+    let config = OracleConfig {
+        challenge_window: 3600,
+        ..OracleConfig::default()
+    };
+    client.initialize(&Address::generate(&env), &2u32, &config);
+    assert_eq!(client.get_challenge_window(), 3600);
+
+    let oracle1 = Address::generate(&env);
+    let oracle2 = Address::generate(&env);
+    client.register_oracle(&oracle1, &Symbol::new(&env, "Oracle1"));
+    client.register_oracle(&oracle2, &Symbol::new(&env, "Oracle2"));
+
+    let market_id = BytesN::from_array(&env, &[1u8; 32]);
+    let data_hash = BytesN::from_array(&env, &[0u8; 32]);
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    client.submit_attestation(&oracle1, &market_id, &1u32, &data_hash);
+    assert_eq!(client.get_consensus_time(&market_id), None);
+
+    // The window runs from the vote that formed consensus
+    env.ledger().with_mut(|li| li.timestamp = 2_000);
+    client.submit_attestation(&oracle2, &market_id, &1u32, &data_hash);
+    assert_eq!(client.get_consensus_time(&market_id), Some(2_000));
+
+    env.ledger().with_mut(|li| li.timestamp = 5_599);
+    assert_eq!(
+        client.try_finalize_resolution(&market_id),
+        Err(Ok(OracleError::ChallengeWindowOpen))
+    );
+
+    // A challenge raised inside the window still blocks finality after it
+    client.challenge_attestation(
+        &Address::generate(&env),
+        &oracle1,
+        &market_id,
+        &Symbol::new(&env, "wrong"),
+    );
+    env.ledger().with_mut(|li| li.timestamp = 5_600);
+    assert_eq!(
+        client.try_finalize_resolution(&market_id),
+        Err(Ok(OracleError::ChallengeActive))
+    );
+    client.resolve_challenge(&oracle1, &market_id, &false);
+    assert_eq!(client.finalize_resolution(&market_id), 1);
+}
+
+#[test]
+fn test_has_attested() {
+    let env = create_test_env();
+    env.mock_all_auths();
+
+    let oracle_id = register_oracle(&env);
+    let client = OracleManagerClient::new(&env, &oracle_id);
+    client.initialize(&Address::generate(&env), &2u32, &OracleConfig::default());
+
+    let oracle1 = Address::generate(&env);
+    let oracle2 = Address::generate(&env);
+    client.register_oracle(&oracle1, &Symbol::new(&env, "Oracle1"));
+    client.register_oracle(&oracle2, &Symbol::new(&env, "Oracle2"));
+
+    let market_id = BytesN::from_array(&env, &[1u8; 32]);
+    assert!(!client.has_attested(&oracle1, &market_id));
+
+    let data_hash = BytesN::from_array(&env, &[0u8; 32]);
+    client.submit_attestation(&oracle1, &market_id, &1u32, &data_hash);
+    assert!(client.has_attested(&oracle1, &market_id));
+    assert!(!client.has_attested(&oracle2, &market_id));
+}
+
+#[test]
+fn test_markets_awaiting_attestation() {
+    let env = create_test_env();
+    env.mock_all_auths();
+
+    let oracle_id = register_oracle(&env);
+    let client = OracleManagerClient::new(&env, &oracle_id);
+    let factory = Address::generate(&env);
+    let config = OracleConfig {
+        factory: Some(factory.clone()),
+        ..OracleConfig::default()
+    };
+    client.initialize(&Address::generate(&env), &2u32, &config);
+
+    let oracle1 = Address::generate(&env);
+    client.register_oracle(&oracle1, &Symbol::new(&env, "Oracle1"));
+
+    let voted = BytesN::from_array(&env, &[1u8; 32]);
+    let pending = BytesN::from_array(&env, &[2u8; 32]);
+    let future = BytesN::from_array(&env, &[3u8; 32]);
+    let market = Address::generate(&env);
+    client.request_resolution(&factory, &market, &voted, &1_000);
+    client.request_resolution(&factory, &market, &pending, &1_000);
+    client.request_resolution(&factory, &market, &future, &5_000);
+    assert_eq!(
+        client.try_request_resolution(&factory, &Address::generate(&env), &pending, &2_000),
+        Err(Ok(OracleError::ResolutionAlreadyRequested))
+    );
+    assert_eq!(client.get_requested_market_count(), 3);
+
+    // Nothing is due before resolution time
+    assert_eq!(
+        client
+            .get_markets_awaiting_attestation(&oracle1, &0, &10)
+            .len(),
+        0
+    );
+
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let data_hash = BytesN::from_array(&env, &[0u8; 32]);
+    client.submit_attestation(&oracle1, &voted, &1u32, &data_hash);
+
+    assert_eq!(
+        client.get_markets_awaiting_attestation(&oracle1, &0, &10),
+        vec![&env, pending.clone()]
+    );
+    // Paging only scans the requested window
+    assert_eq!(
+        client.get_markets_awaiting_attestation(&oracle1, &1, &1),
+        vec![&env, pending]
+    );
+    assert_eq!(
+        client
+            .get_markets_awaiting_attestation(&oracle1, &2, &10)
+            .len(),
+        0
+    );
+}
+
+#[test]
+fn test_update_resolution_time_only_by_requesting_market() {
+    let env = create_test_env();
+    env.mock_all_auths();
+
+    let oracle_id = register_oracle(&env);
+    let client = OracleManagerClient::new(&env, &oracle_id);
+    let factory = Address::generate(&env);
+    let config = OracleConfig {
+        factory: Some(factory.clone()),
+        ..OracleConfig::default()
+    };
+    client.initialize(&Address::generate(&env), &1u32, &config);
+    let oracle1 = Address::generate(&env);
+    client.register_oracle(&oracle1, &Symbol::new(&env, "Oracle1"));
+
+    let market = Address::generate(&env);
+    let market_id = BytesN::from_array(&env, &[1u8; 32]);
+    client.request_resolution(&factory, &market, &market_id, &1_000);
+
+    assert_eq!(
+        client.try_update_resolution_time(&Address::generate(&env), &market_id, &u64::MAX),
+        Err(Ok(OracleError::ResolutionNotRequested))
+    );
+    client.update_resolution_time(&market, &market_id, &2_000);
+
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    assert_eq!(
+        client
+            .get_markets_awaiting_attestation(&oracle1, &0, &10)
+            .len(),
+        0
+    );
+    env.ledger().with_mut(|li| li.timestamp = 2_000);
+    assert_eq!(
+        client.get_markets_awaiting_attestation(&oracle1, &0, &10),
+        vec![&env, market_id]
+    );
+}
+
+#[test]
+fn test_request_resolution_only_by_factory() {
+    let env = create_test_env();
+    env.mock_all_auths();
+
+    let oracle_id = register_oracle(&env);
+    let client = OracleManagerClient::new(&env, &oracle_id);
+    let factory = Address::generate(&env);
+    let config = OracleConfig {
+        factory: Some(factory.clone()),
+        ..OracleConfig::default()
+    };
+    client.initialize(&Address::generate(&env), &1u32, &config);
+
+    // A caller posing as a market cannot register itself
+    let market = Address::generate(&env);
+    let market_id = BytesN::from_array(&env, &[1u8; 32]);
+    assert_eq!(
+        client.try_request_resolution(&market, &market, &market_id, &1_000),
+        Err(Ok(OracleError::UnauthorizedFactory))
+    );
+    assert_eq!(client.get_requested_market_count(), 0);
+}
+
+#[test]
+fn test_request_resolution_requires_factory_auth() {
+    let env = create_test_env();
+    let oracle_id = register_oracle(&env);
+    let client = OracleManagerClient::new(&env, &oracle_id);
+
+    let market_id = BytesN::from_array(&env, &[1u8; 32]);
+    assert!(client
+        .try_request_resolution(
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &market_id,
+            &u64::MAX
+        )
+        .is_err());
+}
+
 #[test]
 fn test_check_consensus_reached() {
     let env = create_test_env();
@@ -154,7 +567,7 @@ fn test_check_consensus_reached() {
     let client = OracleManagerClient::new(&env, &oracle_id);
 
     let admin = Address::generate(&env);
-    client.initialize(&admin, &2u32);
+    client.initialize(&admin, &2u32, &OracleConfig::default());
 
     let oracle1 = Address::generate(&env);
     let oracle2 = Address::generate(&env);
@@ -177,6 +590,138 @@ fn test_check_consensus_reached() {
     assert_eq!(outcome, 1);
 }
 
+#[test]
+fn test_simulate_consensus_with_hypothetical_threshold() {
+    let env = create_test_env();
+    env.mock_all_auths();
+
+    let oracle_id = register_oracle(&env);
+    let client = OracleManagerClient::new(&env, &oracle_id);
+    client.initialize(&Address::generate(&env), &3u32, &OracleConfig::default());
+
+    let market_id = BytesN::from_array(&env, &[1u8; 32]);
+    let data_hash = BytesN::from_array(&env, &[0u8; 32]);
+    // 2 YES, 1 NO
+    for (i, vote) in [1u32, 1, 0].iter().enumerate() {
+        let oracle = Address::generate(&env);
+        client.register_oracle(&oracle, &Symbol::new(&env, ["A", "B", "C"][i]));
+        client.submit_attestation(&oracle, &market_id, vote, &data_hash);
+    }
+
+    assert_eq!(client.simulate_consensus(&market_id, &2u32), (true, 1));
+    assert_eq!(client.simulate_consensus(&market_id, &3u32), (false, 0));
+    // The stored threshold of 3 is untouched
+    assert_eq!(client.check_consensus(&market_id), (false, 0));
+    assert_eq!(client.get_required_consensus(), 3);
+}
+
+#[test]
+fn test_consensus_attestation_time_is_oldest_agreeing_vote() {
+    let env = create_test_env();
+    env.mock_all_auths();
+
+    let oracle_id = register_oracle(&env);
+    let client = OracleManagerClient::new(&env, &oracle_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin, &2u32, &OracleConfig::default());
+
+    let oracle1 = Address::generate(&env);
+    let oracle2 = Address::generate(&env);
+    let oracle3 = Address::generate(&env);
+    client.register_oracle(&oracle1, &Symbol::new(&env, "Oracle1"));
+    client.register_oracle(&oracle2, &Symbol::new(&env, "Oracle2"));
+    client.register_oracle(&oracle3, &Symbol::new(&env, "Oracle3"));
+
+    let market_id = BytesN::from_array(&env, &[1u8; 32]);
+    let data_hash = BytesN::from_array(&env, &[0u8; 32]);
+
+    // Dissenting vote first, then two agreeing votes
+    env.ledger().with_mut(|li| li.timestamp = 100);
+    client.submit_attestation(&oracle3, &market_id, &0u32, &data_hash);
+    assert_eq!(client.get_consensus_attestation_time(&market_id), 0);
+
+    env.ledger().with_mut(|li| li.timestamp = 200);
+    client.submit_attestation(&oracle1, &market_id, &1u32, &data_hash);
+    env.ledger().with_mut(|li| li.timestamp = 300);
+    client.submit_attestation(&oracle2, &market_id, &1u32, &data_hash);
+
+    assert_eq!(client.get_consensus_attestation_time(&market_id), 200);
+}
+
+/// Registers five oracles and records a 3 YES / 2 NO split under `config`
+fn check_three_to_two_split(
+    env: &Env,
+    required_consensus: u32,
+    config: OracleConfig,
+) -> (bool, u32) {
+    let oracle_id = register_oracle(env);
+    let client = OracleManagerClient::new(env, &oracle_id);
+
+    let admin = Address::generate(env);
+    client.initialize(&admin, &required_consensus, &config);
+
+    let market_id = BytesN::from_array(env, &[7u8; 32]);
+    let data_hash = BytesN::from_array(env, &[0u8; 32]);
+    for i in 0..5u32 {
+        let oracle = Address::generate(env);
+        client.register_oracle(&oracle, &Symbol::new(env, "Oracle"));
+        let outcome = if i < 3 { 1u32 } else { 0u32 };
+        client.submit_attestation(&oracle, &market_id, &outcome, &data_hash);
+    }
+
+    client.check_consensus(&market_id)
+}
+
+#[test]
+fn test_consensus_modes_on_same_vote_split() {
+    let env = create_test_env();
+    env.mock_all_auths();
+
+    // Absolute: 3 YES votes meet a threshold of 3
+    let absolute = check_three_to_two_split(&env, 3, OracleConfig::default());
+    assert_eq!(absolute, (true, 1));
+
+    // Percentage: 3 of 5 is 60%, short of a 66% super-majority
+    let super_majority = check_three_to_two_split(
+        &env,
+        3,
+        OracleConfig {
+            consensus_mode: ConsensusMode::Percentage,
+            threshold_pct: 66,
+            min_voters: 3,
+            ..Default::default()
+        },
+    );
+    assert_eq!(super_majority, (false, 0));
+
+    // Percentage: 60% clears a simple majority
+    let simple_majority = check_three_to_two_split(
+        &env,
+        3,
+        OracleConfig {
+            consensus_mode: ConsensusMode::Percentage,
+            threshold_pct: 51,
+            min_voters: 3,
+            ..Default::default()
+        },
+    );
+    assert_eq!(simple_majority, (true, 1));
+
+    // Percentage: too few voters even with a clear majority
+    let under_quorum = check_three_to_two_split(
+        &env,
+        3,
+        OracleConfig {
+            consensus_mode: ConsensusMode::Percentage,
+            threshold_pct: 51,
+            min_voters: 6,
+            ..Default::default()
+        },
+    );
+    assert_eq!(under_quorum, (false, 0));
+}
+
 #[test]
 fn test_check_consensus_not_reached() {
     let env = create_test_env();
@@ -186,7 +731,7 @@ fn test_check_consensus_not_reached() {
     let client = OracleManagerClient::new(&env, &oracle_id);
 
     let admin = Address::generate(&env);
-    client.initialize(&admin, &3u32); // Need 3 oracles
+    client.initialize(&admin, &3u32, &OracleConfig::default()); // Need 3 oracles
 
     let oracle1 = Address::generate(&env);
     let oracle2 = Address::generate(&env);
@@ -214,6 +759,46 @@ fn test_resolve_market_without_consensus() {
     // Cannot resolve yet
 }
 
+#[test]
+fn test_submit_attestations_batch() {
+    let env = create_test_env();
+    env.mock_all_auths();
+
+    let oracle_id = register_oracle(&env);
+    let client = OracleManagerClient::new(&env, &oracle_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin, &1u32, &OracleConfig::default());
+
+    let oracle1 = Address::generate(&env);
+    client.register_oracle(&oracle1, &Symbol::new(&env, "O1"));
+
+    let voted = BytesN::from_array(&env, &[9u8; 32]);
+    let market1 = BytesN::from_array(&env, &[1u8; 32]);
+    let market2 = BytesN::from_array(&env, &[2u8; 32]);
+    let market3 = BytesN::from_array(&env, &[3u8; 32]);
+    let data_hash = BytesN::from_array(&env, &[0u8; 32]);
+
+    // Already voted on one market individually
+    client.submit_attestation(&oracle1, &voted, &1u32, &data_hash);
+
+    let batch = vec![
+        &env,
+        (market1.clone(), 1u32, data_hash.clone()),
+        (voted.clone(), 0u32, data_hash.clone()),
+        (market2.clone(), 0u32, data_hash.clone()),
+        (market3.clone(), 1u32, data_hash.clone()),
+    ];
+    let submitted = client.submit_attestations_batch(&oracle1, &batch);
+
+    // Three new votes landed, the duplicate was skipped without aborting
+    assert_eq!(submitted, 3);
+    assert_eq!(client.check_consensus(&market1), (true, 1));
+    assert_eq!(client.check_consensus(&market2), (true, 0));
+    assert_eq!(client.check_consensus(&market3), (true, 1));
+    assert_eq!(client.check_consensus(&voted), (true, 1));
+}
+
 #[test]
 fn test_check_consensus_tie_handling() {
     let env = create_test_env();
@@ -223,7 +808,7 @@ fn test_check_consensus_tie_handling() {
     let client = OracleManagerClient::new(&env, &oracle_id);
 
     let admin = Address::generate(&env);
-    client.initialize(&admin, &2u32); // threshold 2
+    client.initialize(&admin, &2u32, &OracleConfig::default()); // threshold 2
 
     let oracle1 = Address::generate(&env);
     let oracle2 = Address::generate(&env);
@@ -249,6 +834,142 @@ fn test_check_consensus_tie_handling() {
     assert!(!reached);
 }
 
+/// Deploys an oracle with a challenge bond and one attestation to dispute
+fn setup_bonded_challenge(
+    env: &Env,
+    bond: i128,
+) -> (
+    OracleManagerClient<'_>,
+    Address,
+    Address,
+    Address,
+    Address,
+    BytesN<32>,
+) {
+    let oracle_id = register_oracle(env);
+    let client = OracleManagerClient::new(env, &oracle_id);
+
+    let admin = Address::generate(env);
+    let treasury = Address::generate(env);
+    let usdc_admin = Address::generate(env);
+    let usdc_token = env.register_stellar_asset_contract_v2(usdc_admin).address();
+
+    let config = OracleConfig {
+        usdc_token: Some(usdc_token.clone()),
+        treasury: Some(treasury.clone()),
+        challenge_bond: bond,
+        ..Default::default()
+    };
+    client.initialize(&admin, &1u32, &config);
+
+    let oracle1 = Address::generate(env);
+    client.register_oracle(&oracle1, &Symbol::new(env, "O1"));
+
+    let market_id = BytesN::from_array(env, &[7u8; 32]);
+    let data_hash = BytesN::from_array(env, &[0u8; 32]);
+    client.submit_attestation(&oracle1, &market_id, &1u32, &data_hash);
+
+    let challenger = Address::generate(env);
+    StellarAssetClient::new(env, &usdc_token).mint(&challenger, &bond);
+
+    (client, usdc_token, treasury, challenger, oracle1, market_id)
+}
+
+#[test]
+fn test_valid_challenge_refunds_bond() {
+    let env = create_test_env();
+    env.mock_all_auths();
+
+    let bond = 50_000_000i128;
+    let (client, usdc_token, treasury, challenger, oracle1, market_id) =
+        setup_bonded_challenge(&env, bond);
+    let usdc = TokenClient::new(&env, &usdc_token);
+
+    client.challenge_attestation(
+        &challenger,
+        &oracle1,
+        &market_id,
+        &Symbol::new(&env, "wrong_result"),
+    );
+
+    // Bond held by oracle contract while challenge is open
+    assert_eq!(usdc.balance(&challenger), 0);
+    assert_eq!(usdc.balance(&client.address), bond);
+
+    client.resolve_challenge(&oracle1, &market_id, &true);
+
+    // Bond returned to challenger
+    assert_eq!(usdc.balance(&challenger), bond);
+    assert_eq!(usdc.balance(&treasury), 0);
+    assert_eq!(usdc.balance(&client.address), 0);
+}
+
+#[test]
+fn test_valid_challenge_emits_accuracy_update() {
+    let env = create_test_env();
+    env.mock_all_auths();
+
+    let (client, _usdc_token, _treasury, challenger, oracle1, market_id) =
+        setup_bonded_challenge(&env, 50_000_000);
+    client.challenge_attestation(
+        &challenger,
+        &oracle1,
+        &market_id,
+        &Symbol::new(&env, "wrong_result"),
+    );
+    client.resolve_challenge(&oracle1, &market_id, &true);
+
+    let (oracle, old_score, new_score): (Address, u32, u32) =
+        find_event(&env, &client.address, "OracleAccuracyUpdated");
+    assert_eq!(oracle, oracle1);
+    assert_eq!(old_score, 100);
+    assert_eq!(new_score, 90);
+}
+
+#[test]
+fn test_deregister_oracle_emits_event() {
+    let env = create_test_env();
+    env.mock_all_auths();
+
+    let oracle_id = register_oracle(&env);
+    let client = OracleManagerClient::new(&env, &oracle_id);
+    client.initialize(&Address::generate(&env), &2u32, &OracleConfig::default());
+
+    let oracle1 = Address::generate(&env);
+    client.register_oracle(&oracle1, &Symbol::new(&env, "Oracle1"));
+
+    env.ledger().with_mut(|li| li.timestamp = 5_000);
+    client.deregister_oracle(&oracle1);
+
+    let (oracle, timestamp): (Address, u64) = find_event(&env, &oracle_id, "OracleDeregistered");
+    assert_eq!(oracle, oracle1);
+    assert_eq!(timestamp, 5_000);
+}
+
+#[test]
+fn test_invalid_challenge_forfeits_bond() {
+    let env = create_test_env();
+    env.mock_all_auths();
+
+    let bond = 50_000_000i128;
+    let (client, usdc_token, treasury, challenger, oracle1, market_id) =
+        setup_bonded_challenge(&env, bond);
+    let usdc = TokenClient::new(&env, &usdc_token);
+
+    client.challenge_attestation(
+        &challenger,
+        &oracle1,
+        &market_id,
+        &Symbol::new(&env, "spam"),
+    );
+    client.resolve_challenge(&oracle1, &market_id, &false);
+
+    // Bond forfeited to treasury
+    assert_eq!(usdc.balance(&challenger), 0);
+    assert_eq!(usdc.balance(&treasury), bond);
+    assert_eq!(usdc.balance(&client.address), 0);
+}
+
 #[test]
 fn test_remove_oracle() {
     // TODO: Implement when remove_oracle is ready
@@ -262,3 +983,195 @@ fn test_update_oracle_accuracy() {
     // Track oracle accuracy over time
     // Accurate predictions increase accuracy score
 }
+
+/// Deploys an oracle that requires registration stake
+fn setup_staked_oracle(
+    env: &Env,
+    stake: i128,
+    slash_bps: u32,
+) -> (OracleManagerClient<'_>, Address, Address, Address) {
+    let oracle_id = register_oracle(env);
+    let client = OracleManagerClient::new(env, &oracle_id);
+
+    let admin = Address::generate(env);
+    let treasury = Address::generate(env);
+    let usdc_admin = Address::generate(env);
+    let usdc_token = env.register_stellar_asset_contract_v2(usdc_admin).address();
+
+    let config = OracleConfig {
+        usdc_token: Some(usdc_token.clone()),
+        treasury: Some(treasury.clone()),
+        min_oracle_stake: stake,
+        slash_bps,
+        ..Default::default()
+    };
+    client.initialize(&admin, &1u32, &config);
+
+    let oracle1 = Address::generate(env);
+    StellarAssetClient::new(env, &usdc_token).mint(&oracle1, &stake);
+
+    (client, usdc_token, treasury, oracle1)
+}
+
+#[test]
+fn test_register_oracle_locks_stake() {
+    let env = create_test_env();
+    env.mock_all_auths();
+
+    let stake = 100_000_000i128;
+    let (client, usdc_token, _treasury, oracle1) = setup_staked_oracle(&env, stake, 0);
+    client.register_oracle(&oracle1, &Symbol::new(&env, "O1"));
+
+    let token = TokenClient::new(&env, &usdc_token);
+    assert_eq!(token.balance(&oracle1), 0);
+    assert_eq!(token.balance(&client.address), stake);
+    assert_eq!(client.get_oracle_stake(&oracle1), stake);
+}
+
+#[test]
+fn test_register_oracle_without_stake_funds_fails() {
+    let env = create_test_env();
+    env.mock_all_auths();
+
+    let (client, _usdc_token, _treasury, _oracle1) = setup_staked_oracle(&env, 100_000_000, 0);
+    let unfunded = Address::generate(&env);
+
+    let result = client.try_register_oracle(&unfunded, &Symbol::new(&env, "O2"));
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_valid_challenge_slashes_oracle_stake() {
+    let env = create_test_env();
+    env.mock_all_auths();
+
+    let stake = 100_000_000i128;
+    let (client, usdc_token, treasury, oracle1) = setup_staked_oracle(&env, stake, 10000);
+    client.register_oracle(&oracle1, &Symbol::new(&env, "O1"));
+
+    let market_id = BytesN::from_array(&env, &[7u8; 32]);
+    let data_hash = BytesN::from_array(&env, &[0u8; 32]);
+    client.submit_attestation(&oracle1, &market_id, &1u32, &data_hash);
+
+    let challenger = Address::generate(&env);
+    client.challenge_attestation(
+        &challenger,
+        &oracle1,
+        &market_id,
+        &Symbol::new(&env, "wrong"),
+    );
+    client.resolve_challenge(&oracle1, &market_id, &true);
+
+    let token = TokenClient::new(&env, &usdc_token);
+    assert_eq!(token.balance(&treasury), stake);
+    assert_eq!(token.balance(&client.address), 0);
+    assert_eq!(client.get_oracle_stake(&oracle1), 0);
+
+    // Fully slashed oracle is deregistered and can no longer attest
+    let next_market = BytesN::from_array(&env, &[8u8; 32]);
+    let result = client.try_submit_attestation(&oracle1, &next_market, &1u32, &data_hash);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_valid_challenge_without_slash_bps_keeps_stake() {
+    let env = create_test_env();
+    env.mock_all_auths();
+
+    let stake = 100_000_000i128;
+    let (client, usdc_token, treasury, oracle1) = setup_staked_oracle(&env, stake, 0);
+    client.register_oracle(&oracle1, &Symbol::new(&env, "O1"));
+
+    let market_id = BytesN::from_array(&env, &[7u8; 32]);
+    let data_hash = BytesN::from_array(&env, &[0u8; 32]);
+    client.submit_attestation(&oracle1, &market_id, &1u32, &data_hash);
+
+    let challenger = Address::generate(&env);
+    client.challenge_attestation(
+        &challenger,
+        &oracle1,
+        &market_id,
+        &Symbol::new(&env, "wrong"),
+    );
+    client.resolve_challenge(&oracle1, &market_id, &true);
+
+    // Slashing is disabled by default
+    assert_eq!(TokenClient::new(&env, &usdc_token).balance(&treasury), 0);
+    assert_eq!(client.get_oracle_stake(&oracle1), stake);
+}
+
+#[test]
+fn test_valid_challenge_slashes_configured_fraction() {
+    let env = create_test_env();
+    env.mock_all_auths();
+
+    let oracle_id = register_oracle(&env);
+    let client = OracleManagerClient::new(&env, &oracle_id);
+
+    let admin = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let usdc_admin = Address::generate(&env);
+    let usdc_token = env.register_stellar_asset_contract_v2(usdc_admin).address();
+
+    let stake = 100_000_000i128;
+    let config = OracleConfig {
+        usdc_token: Some(usdc_token.clone()),
+        treasury: Some(treasury.clone()),
+        min_oracle_stake: stake,
+        slash_bps: 2000, // 20%
+        ..Default::default()
+    };
+    client.initialize(&admin, &1u32, &config);
+
+    let oracle1 = Address::generate(&env);
+    StellarAssetClient::new(&env, &usdc_token).mint(&oracle1, &stake);
+    client.register_oracle(&oracle1, &Symbol::new(&env, "O1"));
+
+    let market_id = BytesN::from_array(&env, &[7u8; 32]);
+    let data_hash = BytesN::from_array(&env, &[0u8; 32]);
+    client.submit_attestation(&oracle1, &market_id, &1u32, &data_hash);
+
+    let challenger = Address::generate(&env);
+    client.challenge_attestation(
+        &challenger,
+        &oracle1,
+        &market_id,
+        &Symbol::new(&env, "wrong"),
+    );
+    client.resolve_challenge(&oracle1, &market_id, &true);
+
+    let token = TokenClient::new(&env, &usdc_token);
+    assert_eq!(token.balance(&treasury), 20_000_000);
+    assert_eq!(client.get_oracle_stake(&oracle1), 80_000_000);
+
+    // Oracle keeps its remaining stake and can still attest
+    let next_market = BytesN::from_array(&env, &[8u8; 32]);
+    client.submit_attestation(&oracle1, &next_market, &1u32, &data_hash);
+}
+
+#[test]
+fn test_withdraw_stake_after_cooldown() {
+    let env = create_test_env();
+    env.mock_all_auths();
+
+    let stake = 100_000_000i128;
+    let (client, usdc_token, _treasury, oracle1) = setup_staked_oracle(&env, stake, 0);
+    client.register_oracle(&oracle1, &Symbol::new(&env, "O1"));
+
+    // Still registered: nothing to withdraw
+    assert!(client.try_withdraw_stake(&oracle1).is_err());
+
+    client.deregister_oracle(&oracle1);
+
+    // Cooldown has not elapsed yet
+    assert!(client.try_withdraw_stake(&oracle1).is_err());
+
+    env.ledger().with_mut(|li| {
+        li.timestamp += 7 * 24 * 60 * 60;
+    });
+    let withdrawn = client.withdraw_stake(&oracle1);
+
+    assert_eq!(withdrawn, stake);
+    assert_eq!(TokenClient::new(&env, &usdc_token).balance(&oracle1), stake);
+    assert_eq!(client.get_oracle_stake(&oracle1), 0);
+}